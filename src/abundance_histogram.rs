@@ -0,0 +1,130 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// How the range of the arc weights is divided into the bins of an [`AbundanceHistogram`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistogramScale {
+    /// Bins of equal width.
+    #[default]
+    Linear,
+    /// Bins whose boundaries grow geometrically, such that low weights are binned finer than high weights.
+    Log,
+}
+
+/// A bin of an [`AbundanceHistogram`], counting the arcs with weights `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bin {
+    pub start: usize,
+    pub end: usize,
+    pub arc_count: usize,
+}
+
+/// The number of arcs with weights in each bin, covering the weights from zero to the maximum weight.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbundanceHistogram {
+    pub bins: Vec<Bin>,
+}
+
+impl AbundanceHistogram {
+    /// Distribute the arcs into at most `bin_count` bins, given the number of arcs with each weight.
+    /// Bins that would be empty ranges are left out, so there may be fewer bins for small weights.
+    pub fn new(weights: &BTreeMap<usize, usize>, bin_count: usize, scale: HistogramScale) -> Self {
+        assert!(
+            bin_count > 0,
+            "an abundance histogram needs at least one bin"
+        );
+        let Some(&max_weight) = weights.keys().next_back() else {
+            return Self::default();
+        };
+        let end = max_weight + 1;
+
+        let mut boundaries = vec![0];
+        for bin in 1..=bin_count {
+            let boundary = match scale {
+                HistogramScale::Linear => {
+                    let width = (end + bin_count - 1) / bin_count;
+                    (bin * width).min(end)
+                }
+                HistogramScale::Log => {
+                    let boundary = (end as f64).powf(bin as f64 / bin_count as f64).ceil();
+                    (boundary as usize).min(end)
+                }
+            };
+            if boundary > *boundaries.last().unwrap() {
+                boundaries.push(boundary);
+            }
+        }
+        // Rounding may stop the boundaries just short of the end.
+        *boundaries.last_mut().unwrap() = end;
+
+        let bins = boundaries
+            .windows(2)
+            .map(|window| Bin {
+                start: window[0],
+                end: window[1],
+                arc_count: weights
+                    .range(window[0]..window[1])
+                    .map(|(_, count)| count)
+                    .sum(),
+            })
+            .collect();
+        Self { bins }
+    }
+
+    pub fn arc_count(&self) -> usize {
+        self.bins.iter().map(|bin| bin.arc_count).sum()
+    }
+}
+
+impl Display for AbundanceHistogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} arcs by weight:", self.arc_count())?;
+        for bin in &self.bins {
+            write!(f, "\n  {}-{}: {}", bin.start, bin.end - 1, bin.arc_count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AbundanceHistogram, HistogramScale};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_bin_boundaries() {
+        let weights = BTreeMap::from([(0, 1), (1, 2), (3, 1), (10, 4), (99, 1)]);
+        let boundaries = |histogram: &AbundanceHistogram| -> Vec<_> {
+            histogram
+                .bins
+                .iter()
+                .map(|bin| (bin.start, bin.end, bin.arc_count))
+                .collect()
+        };
+
+        let linear = AbundanceHistogram::new(&weights, 4, HistogramScale::Linear);
+        assert_eq!(
+            boundaries(&linear),
+            vec![(0, 25, 8), (25, 50, 0), (50, 75, 0), (75, 100, 1)]
+        );
+        assert_eq!(linear.arc_count(), 9);
+
+        let log = AbundanceHistogram::new(&weights, 4, HistogramScale::Log);
+        assert_eq!(
+            boundaries(&log),
+            vec![(0, 4, 4), (4, 10, 0), (10, 32, 4), (32, 100, 1)]
+        );
+        assert_eq!(log.arc_count(), 9);
+        assert_eq!(
+            log.to_string(),
+            "9 arcs by weight:\n  0-3: 4\n  4-9: 0\n  10-31: 4\n  32-99: 1"
+        );
+
+        // There are at most as many bins as weights.
+        let small = AbundanceHistogram::new(&BTreeMap::from([(1, 3)]), 10, HistogramScale::Linear);
+        assert_eq!(boundaries(&small), vec![(0, 1, 0), (1, 2, 3)]);
+    }
+}
@@ -0,0 +1,133 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+
+/// The maximum number of sequence characters that are reported for an outlier.
+const MAX_SEQUENCE_LENGTH: usize = 20;
+
+/// An arc with an extreme weight.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Outlier {
+    pub weight: usize,
+    pub from: String,
+    pub to: String,
+    /// The start of the sequence of the arc, at most [`MAX_SEQUENCE_LENGTH`] characters.
+    pub sequence: String,
+}
+
+impl Outlier {
+    pub fn new(weight: usize, from: String, to: String, sequence: &str) -> Self {
+        Self {
+            weight,
+            from,
+            to,
+            sequence: sequence.chars().take(MAX_SEQUENCE_LENGTH).collect(),
+        }
+    }
+}
+
+/// The arcs with the highest and the lowest weights, collected in bounded heaps while writing.
+#[derive(Debug, Clone)]
+pub struct AbundanceOutliers {
+    capacity: usize,
+    /// A min-heap of the highest weights, such that the lowest of them is replaced first.
+    highest: BinaryHeap<Reverse<Outlier>>,
+    /// A max-heap of the lowest weights, such that the highest of them is replaced first.
+    lowest: BinaryHeap<Outlier>,
+}
+
+impl AbundanceOutliers {
+    /// Collect the `capacity` arcs with the highest and with the lowest weights.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            highest: BinaryHeap::with_capacity(capacity + 1),
+            lowest: BinaryHeap::with_capacity(capacity + 1),
+        }
+    }
+
+    pub fn record(&mut self, outlier: Outlier) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.highest.push(Reverse(outlier.clone()));
+        if self.highest.len() > self.capacity {
+            self.highest.pop();
+        }
+        self.lowest.push(outlier);
+        if self.lowest.len() > self.capacity {
+            self.lowest.pop();
+        }
+    }
+
+    /// The arcs with the highest weights, highest first.
+    pub fn highest(&self) -> Vec<Outlier> {
+        let mut highest: Vec<_> = self
+            .highest
+            .iter()
+            .map(|Reverse(outlier)| outlier.clone())
+            .collect();
+        highest.sort_unstable_by(|a, b| b.cmp(a));
+        highest
+    }
+
+    /// The arcs with the lowest weights, lowest first.
+    pub fn lowest(&self) -> Vec<Outlier> {
+        let mut lowest: Vec<_> = self.lowest.iter().cloned().collect();
+        lowest.sort_unstable();
+        lowest
+    }
+}
+
+impl PartialEq for AbundanceOutliers {
+    fn eq(&self, other: &Self) -> bool {
+        self.capacity == other.capacity
+            && self.highest() == other.highest()
+            && self.lowest() == other.lowest()
+    }
+}
+
+impl Eq for AbundanceOutliers {}
+
+impl Display for AbundanceOutliers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (title, outliers) in [("highest", self.highest()), ("lowest", self.lowest())] {
+            write!(f, "Arcs with the {title} weights:")?;
+            for outlier in outliers {
+                write!(
+                    f,
+                    "\n  {} -> {} weight {} {}",
+                    outlier.from, outlier.to, outlier.weight, outlier.sequence
+                )?;
+            }
+            if title == "highest" {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AbundanceOutliers, Outlier};
+
+    #[test]
+    fn test_bounded_outliers() {
+        let mut outliers = AbundanceOutliers::new(2);
+        for weight in [5, 1, 9, 3, 7] {
+            outliers.record(Outlier::new(
+                weight,
+                "0".to_owned(),
+                "1".to_owned(),
+                &"ACGT".repeat(10),
+            ));
+        }
+        let weights = |outliers: Vec<Outlier>| -> Vec<_> {
+            outliers.into_iter().map(|outlier| outlier.weight).collect()
+        };
+        assert_eq!(weights(outliers.highest()), vec![9, 7]);
+        assert_eq!(weights(outliers.lowest()), vec![1, 3]);
+        assert_eq!(outliers.highest()[0].sequence.len(), 20);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::record_transformer::FastaRecord;
+
+/// Set the abundance tags `KC` and `km` of a bcalm2 record from the mean abundance in the tag with the given name,
+/// e.g. `ab` for `ab:f:12.5`, and remove that tag, since the bcalm2 reader does not accept unknown tags.
+/// The k-mer count `KC` is the mean abundance times the number of k-mers of the record, rounded to the nearest integer.
+/// Records without the tag or with a malformed value are collected in `malformed`.
+pub fn apply_abundance_tag(
+    record: &mut FastaRecord,
+    name: &str,
+    k: usize,
+    malformed: &mut Vec<String>,
+) -> bool {
+    let mut abundance = None;
+    let header: Vec<_> = record
+        .header
+        .split_whitespace()
+        .filter(|token| {
+            let mut parts = token.splitn(3, ':');
+            if parts.next() == Some(name) && parts.next().is_some() {
+                abundance = Some(parts.next().and_then(|value| value.parse::<f64>().ok()));
+                false
+            } else {
+                true
+            }
+        })
+        .map(ToOwned::to_owned)
+        .collect();
+
+    let Some(Some(abundance)) = abundance else {
+        malformed.push(record.header.clone());
+        return true;
+    };
+    record.header = header.join(" ");
+    let kmer_count = (record.sequence.len() + 1).saturating_sub(k);
+    record.set_tag("KC:i:", (abundance * kmer_count as f64).round() as usize);
+    record.set_tag("km:f:", abundance);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply_abundance_tag;
+    use crate::record_transformer::FastaRecord;
+
+    #[test]
+    fn test_apply_abundance_tag() {
+        let mut record = FastaRecord {
+            header: "0 LN:i:6 ab:f:2.5 L:+:1:+".to_owned(),
+            sequence: b"ACGTAC".to_vec(),
+        };
+        let mut malformed = Vec::new();
+        assert!(apply_abundance_tag(&mut record, "ab", 3, &mut malformed));
+        assert_eq!(record.header, "0 LN:i:6 L:+:1:+ KC:i:10 km:f:2.5");
+        assert!(malformed.is_empty());
+
+        for header in ["0 LN:i:6", "0 LN:i:6 ab:f:many"] {
+            let mut record = FastaRecord {
+                header: header.to_owned(),
+                sequence: b"ACGTAC".to_vec(),
+            };
+            apply_abundance_tag(&mut record, "ab", 3, &mut malformed);
+        }
+        assert_eq!(malformed, vec!["0 LN:i:6", "0 LN:i:6 ab:f:many"]);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use std::io::Write;
+
+/// Write the graph as adjacency list with one line `<node>: <neighbor> <weight> [<sequence>] ...` per node,
+/// listing the arcs leaving the node in the order they appear in the graph.
+/// Nodes without outgoing arcs get a line with only the node.
+/// The arc sequences are written after each weight if `with_sequences` is true.
+pub fn write_adjacency_list(
+    graph: &ArcCentricGraph,
+    with_sequences: bool,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let mut adjacencies = vec![Vec::new(); graph.node_count];
+    for arc in &graph.arcs {
+        adjacencies[arc.from].push(arc);
+    }
+
+    for (node, arcs) in adjacencies.into_iter().enumerate() {
+        write!(output, "{node}:")?;
+        for arc in arcs {
+            write!(output, " {} {}", arc.to, arc.weight)?;
+            if with_sequences {
+                write!(output, " {}", arc.sequence)?;
+            }
+        }
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_adjacency_list;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_adjacency_list() {
+        let graph = read_arc_centric_graph(
+            "3\n0 1 2 2 0 AC\n0 2 5 1 0 AG\n2 0 5 0 1 CT\n1 0 2 0 2 GT\n".as_bytes(),
+        )
+        .unwrap();
+        let convert = |with_sequences| {
+            let mut output = Vec::new();
+            write_adjacency_list(&graph, with_sequences, &mut output).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(convert(false), "0: 1 2 2 5\n1: 0 2\n2: 0 5\n");
+        assert_eq!(convert(true), "0: 1 2 AC 2 5 AG\n1: 0 2 GT\n2: 0 5 CT\n");
+    }
+}
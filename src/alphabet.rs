@@ -0,0 +1,157 @@
+use crate::record_transformer::FastaRecord;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The alphabets of the input sequences.
+///
+/// Sequences are always stored as DNA.
+/// RNA input is translated by replacing `U` with `T` while reading, and back when writing the arc-centric output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Alphabet {
+    /// Sequences over `ACGT`.
+    #[default]
+    Dna,
+    /// Sequences over `ACGU`.
+    Rna,
+    /// Select DNA or RNA depending on whether the sequences contain `T` or `U`.
+    /// Fails if the sequences contain both.
+    /// Sequences that contain neither are read as DNA.
+    Auto,
+}
+
+/// Decides the alphabet of the input from the records that pass through it,
+/// and translates RNA records to DNA.
+#[derive(Debug)]
+pub struct AlphabetDetector {
+    alphabet: Alphabet,
+    /// The header of the first record that contradicts the alphabet, if any.
+    conflicting_header: Option<String>,
+}
+
+impl AlphabetDetector {
+    pub fn new(alphabet: Alphabet) -> Self {
+        Self {
+            alphabet,
+            conflicting_header: None,
+        }
+    }
+
+    /// Record the alphabet of the given record and translate it to DNA.
+    /// Never drops the record, since it can be used directly with a [`RecordTransformer`](crate::record_transformer::RecordTransformer).
+    pub fn transform_record(&mut self, record: &mut FastaRecord) -> bool {
+        let contains_t = record
+            .sequence
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(&b'T'));
+        let contains_u = record
+            .sequence
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(&b'U'));
+
+        let conflicting = match self.alphabet {
+            Alphabet::Auto if contains_t && contains_u => true,
+            Alphabet::Auto if contains_t => {
+                self.alphabet = Alphabet::Dna;
+                false
+            }
+            Alphabet::Auto if contains_u => {
+                self.alphabet = Alphabet::Rna;
+                false
+            }
+            Alphabet::Auto => false,
+            Alphabet::Dna => contains_u,
+            Alphabet::Rna => contains_t,
+        };
+        if conflicting && self.conflicting_header.is_none() {
+            self.conflicting_header = Some(record.header.clone());
+        }
+
+        if contains_u {
+            for character in &mut record.sequence {
+                match character {
+                    b'U' => *character = b'T',
+                    b'u' => *character = b't',
+                    _ => {}
+                }
+            }
+        }
+        true
+    }
+
+    /// The alphabet of the records seen so far, which is never [`Alphabet::Auto`].
+    /// Fails if a record contained characters of both DNA and RNA.
+    pub fn finish(self) -> anyhow::Result<Alphabet> {
+        if let Some(header) = self.conflicting_header {
+            anyhow::bail!("Found a mix of DNA and RNA sequences, e.g. >{header}");
+        }
+        Ok(match self.alphabet {
+            Alphabet::Auto => Alphabet::Dna,
+            alphabet => alphabet,
+        })
+    }
+}
+
+/// The case of the written sequences.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SequenceCase {
+    Upper,
+    Lower,
+    /// Write the characters as they are stored, which is uppercase,
+    /// since the sequence store does not keep the case of the input.
+    #[default]
+    Preserve,
+}
+
+impl SequenceCase {
+    pub fn apply(self, character: u8) -> u8 {
+        match self {
+            Self::Upper => character.to_ascii_uppercase(),
+            Self::Lower => character.to_ascii_lowercase(),
+            Self::Preserve => character,
+        }
+    }
+}
+
+/// Translate a DNA character to the given alphabet.
+pub fn translate_character(character: u8, alphabet: Alphabet) -> u8 {
+    match (alphabet, character) {
+        (Alphabet::Rna, b'T') => b'U',
+        (Alphabet::Rna, b't') => b'u',
+        (_, character) => character,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Alphabet, AlphabetDetector};
+    use crate::record_transformer::FastaRecord;
+
+    fn record(sequence: &str) -> FastaRecord {
+        FastaRecord {
+            header: "0 LN:i:4".to_owned(),
+            sequence: sequence.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        let mut detector = AlphabetDetector::new(Alphabet::Auto);
+        let mut first = record("ACGA");
+        let mut second = record("ACGU");
+        assert!(detector.transform_record(&mut first));
+        assert!(detector.transform_record(&mut second));
+        assert_eq!(second.sequence, b"ACGT");
+        assert_eq!(detector.finish().unwrap(), Alphabet::Rna);
+
+        let mut detector = AlphabetDetector::new(Alphabet::Auto);
+        detector.transform_record(&mut record("ACGT"));
+        detector.transform_record(&mut record("ACGU"));
+        assert!(detector.finish().is_err());
+
+        let mut detector = AlphabetDetector::new(Alphabet::Auto);
+        detector.transform_record(&mut record("ACGA"));
+        assert_eq!(detector.finish().unwrap(), Alphabet::Dna);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::colors::{format_colors, parse_colors, COLOR_TAG};
+use crate::id_encoding::IdEncoding;
+use anyhow::{bail, ensure, Context};
+use std::io::{BufRead, Write};
+
+/// An arc as written in the arc-centric output format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArcRecord {
+    pub from: usize,
+    pub to: usize,
+    pub weight: usize,
+    pub mirror_from: usize,
+    pub mirror_to: usize,
+    pub sequence: String,
+    /// The samples the arc is present in, as given by the color tag. Empty if there is no color tag.
+    pub colors: Vec<bool>,
+    /// Additional columns after the sequence, e.g. tags of the form `<name>:<type>:<value>`.
+    pub tags: Vec<String>,
+}
+
+/// A graph in the arc-centric output format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArcCentricGraph {
+    /// The number of nodes as given in the header.
+    pub node_count: usize,
+    pub arcs: Vec<ArcRecord>,
+}
+
+impl std::str::FromStr for ArcRecord {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Self::parse(line, IdEncoding::Decimal)
+    }
+}
+
+impl ArcRecord {
+    /// Parse a line of the arc-centric output format, with node indices in the given encoding.
+    pub fn parse(line: &str, id_encoding: IdEncoding) -> anyhow::Result<Self> {
+        let mut columns = line.split_whitespace();
+        let mut next_number = |name, encoding: IdEncoding| -> anyhow::Result<usize> {
+            let column = columns
+                .next()
+                .with_context(|| format!("missing column {name}"))?;
+            encoding
+                .decode(column)
+                .with_context(|| format!("column {name} is not a number: {column:?}"))
+        };
+
+        let from = next_number("from", id_encoding)?;
+        let to = next_number("to", id_encoding)?;
+        let weight = next_number("weight", IdEncoding::Decimal)?;
+        let mirror_from = next_number("mirror_from", id_encoding)?;
+        let mirror_to = next_number("mirror_to", id_encoding)?;
+        let Some(sequence) = columns.next() else {
+            bail!("missing column sequence");
+        };
+
+        let mut colors = Vec::new();
+        let mut tags = Vec::new();
+        for column in columns {
+            if let Some(bitmask) = column.strip_prefix(COLOR_TAG) {
+                colors = parse_colors(bitmask)
+                    .with_context(|| format!("malformed color tag: {column:?}"))?;
+            } else {
+                tags.push(column.to_owned());
+            }
+        }
+
+        Ok(Self {
+            from,
+            to,
+            weight,
+            mirror_from,
+            mirror_to,
+            sequence: sequence.to_owned(),
+            colors,
+            tags,
+        })
+    }
+}
+
+impl std::fmt::Display for ArcRecord {
+    /// Format the arc as a line of the arc-centric output format, without the trailing newline.
+    /// The color tag is written after the other tags.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.from, self.to, self.weight, self.mirror_from, self.mirror_to, self.sequence
+        )?;
+        for tag in &self.tags {
+            write!(f, " {tag}")?;
+        }
+        if !self.colors.is_empty() {
+            write!(f, " {COLOR_TAG}{}", format_colors(&self.colors))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a graph in the arc-centric output format.
+pub fn write_arc_centric_graph(
+    graph: &ArcCentricGraph,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    writeln!(output, "{}", graph.node_count)?;
+    for arc in &graph.arcs {
+        writeln!(output, "{arc}")?;
+    }
+    Ok(())
+}
+
+/// Read a graph in the arc-centric output format.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn read_arc_centric_graph(input: impl BufRead) -> anyhow::Result<ArcCentricGraph> {
+    read_arc_centric_graph_with_id_encoding(input, IdEncoding::Decimal)
+}
+
+/// Like [`read_arc_centric_graph`], but with node indices in the given encoding, as written with `--id-encoding`.
+pub fn read_arc_centric_graph_with_id_encoding(
+    input: impl BufRead,
+    id_encoding: IdEncoding,
+) -> anyhow::Result<ArcCentricGraph> {
+    read_arc_centric_graph_with_node_indexing(input, id_encoding, false)
+}
+
+/// Like [`read_arc_centric_graph_with_id_encoding`], but if `one_based` is set, then the node indices start at one,
+/// as written with `--one-based`. The returned graph uses zero-based node indices.
+pub fn read_arc_centric_graph_with_node_indexing(
+    input: impl BufRead,
+    id_encoding: IdEncoding,
+    one_based: bool,
+) -> anyhow::Result<ArcCentricGraph> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| {
+            line.as_ref().map_or(true, |line| {
+                !line.trim().is_empty() && !line.starts_with('#')
+            })
+        });
+
+    let Some((_, header)) = lines.next() else {
+        bail!("missing node count header");
+    };
+    let header = header?;
+    let node_count = header
+        .trim()
+        .parse()
+        .with_context(|| format!("malformed node count header: {header:?}"))?;
+
+    let mut arcs = Vec::new();
+    for (line_number, line) in lines {
+        let mut arc = ArcRecord::parse(&line?, id_encoding)
+            .with_context(|| format!("malformed arc in line {line_number}"))?;
+        if one_based {
+            for node in [
+                &mut arc.from,
+                &mut arc.to,
+                &mut arc.mirror_from,
+                &mut arc.mirror_to,
+            ] {
+                ensure!(
+                    *node > 0,
+                    "node 0 in line {line_number} is not a one-based node index"
+                );
+                *node -= 1;
+            }
+        }
+        for node in [arc.from, arc.to, arc.mirror_from, arc.mirror_to] {
+            ensure!(
+                node < node_count,
+                "node {node} in line {line_number} is out of range for {node_count} nodes"
+            );
+        }
+        arcs.push(arc);
+    }
+
+    Ok(ArcCentricGraph { node_count, arcs })
+}
+
+/// Compute the reverse complement of a DNA or RNA sequence given as ASCII characters.
+/// `U` is complemented to `A`, and `A` is complemented to `U` if the sequence contains `U`, and to `T` otherwise.
+/// Characters other than `ACGTU` are kept as they are.
+pub fn reverse_complement(sequence: &str) -> String {
+    let is_rna = sequence.contains(['U', 'u']);
+    sequence
+        .chars()
+        .rev()
+        .map(|character| match character {
+            'A' if is_rna => 'U',
+            'a' if is_rna => 'u',
+            'A' => 'T',
+            'C' => 'G',
+            'G' => 'C',
+            'T' | 'U' => 'A',
+            'a' => 't',
+            'c' => 'g',
+            'g' => 'c',
+            't' | 'u' => 'a',
+            character => character,
+        })
+        .collect()
+}
+
+/// The sequence in upper case with `U` replaced by `T`, such that DNA and RNA sequences can be compared.
+pub fn normalise_sequence(sequence: &str) -> String {
+    sequence
+        .chars()
+        .map(|character| match character.to_ascii_uppercase() {
+            'U' => 'T',
+            character => character,
+        })
+        .collect()
+}
+
+/// Whether `second` is the reverse complement of `first`, ignoring case and treating `T` and `U` as equal.
+pub fn is_reverse_complement(first: &str, second: &str) -> bool {
+    normalise_sequence(&reverse_complement(first)) == normalise_sequence(second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_reverse_complement, read_arc_centric_graph, reverse_complement, write_arc_centric_graph,
+        ArcRecord,
+    };
+
+    #[test]
+    fn test_rna_reverse_complement() {
+        assert_eq!(reverse_complement("ACGT"), "ACGT");
+        assert_eq!(reverse_complement("AACGU"), "ACGUU");
+        assert_eq!(reverse_complement("aacgu"), "acguu");
+        // Without `U`, the alphabet of the sequence is unknown.
+        assert_eq!(reverse_complement("AAAC"), "GTTT");
+        assert!(is_reverse_complement("AAAC", "GUUU"));
+        assert!(is_reverse_complement("AAAC", "gttt"));
+        assert!(!is_reverse_complement("AAAC", "GUUA"));
+    }
+
+    #[test]
+    fn test_read_arc_centric_graph() {
+        let graph = read_arc_centric_graph(
+            "4\n# comment\n0 1 5 2 3 ACGT PA:i:1 CL:Z:01\n2 3 5 0 1 ACGT\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(graph.node_count, 4);
+        assert_eq!(
+            graph.arcs[0],
+            ArcRecord {
+                from: 0,
+                to: 1,
+                weight: 5,
+                mirror_from: 2,
+                mirror_to: 3,
+                sequence: "ACGT".to_owned(),
+                colors: vec![false, true],
+                tags: vec!["PA:i:1".to_owned()],
+            }
+        );
+        assert_eq!(graph.arcs.len(), 2);
+
+        assert!(read_arc_centric_graph("2\n0 1 5 2 3 ACGT\n".as_bytes()).is_err());
+        assert!(read_arc_centric_graph("2\n0 1 5 1\n".as_bytes()).is_err());
+        assert!(read_arc_centric_graph("".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_arc_centric_graph() {
+        let input = "4\n0 1 5 2 3 ACGT PA:i:1 CL:Z:01\n2 3 5 0 1 ACGT\n";
+        let graph = read_arc_centric_graph(input.as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write_arc_centric_graph(&graph, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+}
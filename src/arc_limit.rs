@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Decides which arcs are written with `--limit-arcs`, while the arcs are written.
+///
+/// The first `limit` arcs are written, together with their mirror arcs, such that no pair of mirror arcs
+/// is cut in half. Since the mirror of the last admitted arc is written as well, one arc more than the limit
+/// may be written. Once the limit is reached and all pending mirror arcs are written, the writer can stop.
+pub struct ArcLimit<Edge> {
+    limit: usize,
+    admitted_count: usize,
+    written_count: usize,
+    pending_mirrors: HashSet<Edge>,
+}
+
+impl<Edge: Hash + Eq> ArcLimit<Edge> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            admitted_count: 0,
+            written_count: 0,
+            pending_mirrors: HashSet::new(),
+        }
+    }
+
+    /// Returns true if the arc of the edge is written, and records it as written.
+    /// The mirror edge is `None` if the arc is its own mirror, e.g. if it is merged with its mirror.
+    pub fn admit(&mut self, edge: Edge, mirror_edge: Option<Edge>) -> bool {
+        if !self.pending_mirrors.remove(&edge) {
+            if self.admitted_count >= self.limit {
+                return false;
+            }
+            self.admitted_count += 1;
+            if let Some(mirror_edge) = mirror_edge {
+                self.admitted_count += 1;
+                self.pending_mirrors.insert(mirror_edge);
+            }
+        }
+        self.written_count += 1;
+        true
+    }
+
+    /// True if no further arc will be written.
+    pub fn is_complete(&self) -> bool {
+        self.admitted_count >= self.limit && self.pending_mirrors.is_empty()
+    }
+
+    /// The number of arcs written so far.
+    pub fn written_count(&self) -> usize {
+        self.written_count
+    }
+}
@@ -0,0 +1,31 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use std::io::{self, Write};
+
+/// The maximum number of nodes of a graph that is previewed with `--ascii-preview`.
+pub const ASCII_PREVIEW_MAX_NODES: usize = 32;
+
+/// Write a textual layout of the graph, with one line per node listing its outgoing arcs,
+/// like `(0) 2 arcs: -42-> (1), -1-> (4)`, where the numbers on the arrows are the weights.
+pub fn write_ascii_preview(graph: &ArcCentricGraph, output: &mut impl Write) -> io::Result<()> {
+    let mut out_arcs = vec![Vec::new(); graph.node_count];
+    for arc in &graph.arcs {
+        out_arcs[arc.from].push(arc);
+    }
+
+    writeln!(
+        output,
+        "Graph with {} nodes and {} arcs:",
+        graph.node_count,
+        graph.arcs.len()
+    )?;
+    for (node, arcs) in out_arcs.iter().enumerate() {
+        let plural = if arcs.len() == 1 { "" } else { "s" };
+        write!(output, "  ({node}) {} arc{plural}", arcs.len())?;
+        for (index, arc) in arcs.iter().enumerate() {
+            let separator = if index == 0 { ":" } else { "," };
+            write!(output, "{separator} -{}-> ({})", arc.weight, arc.to)?;
+        }
+        writeln!(output)?;
+    }
+    Ok(())
+}
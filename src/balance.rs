@@ -0,0 +1,87 @@
+use crate::Graph;
+use genome_graph::bigraph::interface::static_bigraph::{StaticBigraph, StaticEdgeCentricBigraph};
+use genome_graph::bigraph::traitgraph::index::GraphIndex;
+use genome_graph::bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer, NavigableGraph};
+
+/// Check that the edges of the bigraph are closed under taking mirrors,
+/// and that the number of edges minus the number of self-mirror edges is even.
+/// Returns a description of each violation.
+pub fn find_imbalances(graph: &Graph) -> Vec<String> {
+    let mut imbalances = Vec::new();
+    let mut self_mirror_count = 0;
+
+    for edge_id in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+        let describe = || {
+            format!(
+                "edge {} ({} -> {}) of unitig >{}",
+                edge_id.as_usize(),
+                from_node.as_usize(),
+                to_node.as_usize(),
+                graph.edge_data(edge_id).id
+            )
+        };
+
+        match graph.mirror_edge_edge_centric(edge_id) {
+            None => imbalances.push(format!("{} has no mirror edge", describe())),
+            Some(mirror_edge) if mirror_edge == edge_id => self_mirror_count += 1,
+            Some(mirror_edge) => {
+                if graph.mirror_edge_edge_centric(mirror_edge) != Some(edge_id) {
+                    imbalances.push(format!(
+                        "{} has mirror edge {}, whose mirror edge is not the original edge",
+                        describe(),
+                        mirror_edge.as_usize()
+                    ));
+                }
+            }
+        }
+    }
+
+    if (graph.edge_count() - self_mirror_count) % 2 != 0 {
+        imbalances.push(format!(
+            "the graph has {} edges of which {self_mirror_count} are self-mirrors, so the remaining edges cannot be paired",
+            graph.edge_count()
+        ));
+    }
+
+    imbalances
+}
+
+/// Check that each forwards edge has the same total abundance as the reverse edge of the same unitig,
+/// which goes between the mirror nodes in the opposite direction.
+/// Returns a description of each violation.
+pub fn find_abundance_violations(graph: &Graph) -> Vec<String> {
+    let mut violations = Vec::new();
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        if !edge_data.forwards {
+            continue;
+        }
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+        let (Some(mirror_from), Some(mirror_to)) =
+            (graph.mirror_node(to_node), graph.mirror_node(from_node))
+        else {
+            continue;
+        };
+
+        for mirror_edge in graph.edges_between(mirror_from, mirror_to) {
+            let mirror_data = graph.edge_data(mirror_edge);
+            if mirror_data.id == edge_data.id
+                && !mirror_data.forwards
+                && mirror_data.total_abundance != edge_data.total_abundance
+            {
+                violations.push(format!(
+                    "edge {} ({} -> {}) of unitig >{} has total abundance {}, but its mirror edge {} has {}",
+                    edge_id.as_usize(),
+                    from_node.as_usize(),
+                    to_node.as_usize(),
+                    edge_data.id,
+                    edge_data.total_abundance,
+                    mirror_edge.as_usize(),
+                    mirror_data.total_abundance
+                ));
+            }
+        }
+    }
+    violations
+}
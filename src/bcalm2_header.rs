@@ -0,0 +1,248 @@
+use std::io::{BufRead, Read};
+
+/// A link between two unitigs as given by an `L:` tag in a bcalm2 header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bcalm2Link {
+    /// `true` if the link leaves the forward strand of the source unitig.
+    pub from_forwards: bool,
+    /// The bcalm2 id of the target unitig.
+    pub to_id: usize,
+    /// `true` if the link enters the forward strand of the target unitig.
+    pub to_forwards: bool,
+}
+
+/// The parts of a bcalm2 header line that are not preserved by the graph reader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bcalm2Header {
+    /// The bcalm2 id of the unitig.
+    pub id: usize,
+    /// The links stored at the unitig.
+    pub links: Vec<Bcalm2Link>,
+}
+
+impl Bcalm2Header {
+    /// Parse a fasta header line of a bcalm2 record, with or without the leading `>`.
+    /// Returns `None` if the id or the links cannot be parsed.
+    /// Malformed records are rejected by the graph reader, so there is no need to report details here.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens = line.trim_start_matches('>').split_whitespace();
+        let id = tokens.next()?.parse().ok()?;
+        let links = tokens
+            .filter(|token| token.starts_with("L:"))
+            .map(Bcalm2Link::parse)
+            .collect::<Option<_>>()?;
+        Some(Self { id, links })
+    }
+}
+
+impl Bcalm2Link {
+    fn parse(token: &str) -> Option<Self> {
+        let parse_side = |side| match side {
+            "+" => Some(true),
+            "-" => Some(false),
+            _ => None,
+        };
+
+        let mut parts = token.split(':').skip(1);
+        let from_forwards = parse_side(parts.next()?)?;
+        let to_id = parts.next()?.parse().ok()?;
+        let to_forwards = parse_side(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            from_forwards,
+            to_id,
+            to_forwards,
+        })
+    }
+}
+
+impl std::fmt::Display for Bcalm2Link {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side = |forwards| if forwards { '+' } else { '-' };
+        write!(
+            f,
+            "L:{}:{}:{}",
+            side(self.from_forwards),
+            self.to_id,
+            side(self.to_forwards)
+        )
+    }
+}
+
+/// The number of records and links in a bcalm2 file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bcalm2RecordCounts {
+    /// The number of records, i.e. unitigs.
+    pub records: usize,
+    /// The number of `L:` links over all records.
+    pub links: usize,
+}
+
+/// Count the records and links in a bcalm2 file without storing any sequences.
+pub fn count_records(input: impl BufRead) -> std::io::Result<Bcalm2RecordCounts> {
+    let mut counts = Bcalm2RecordCounts::default();
+    for line in input.split(b'\n') {
+        let line = line?;
+        if line.first() == Some(&b'>') {
+            counts.records += 1;
+            counts.links += line
+                .split(|byte| byte.is_ascii_whitespace())
+                .filter(|token| token.starts_with(b"L:"))
+                .count();
+        }
+    }
+    Ok(counts)
+}
+
+/// A [`BufRead`] adapter that passes all data through unchanged,
+/// but calls a function on each fasta header line that is consumed from it.
+///
+/// This allows to capture information from the input that the bcalm2 reader discards.
+pub struct HeaderInspector<R, F> {
+    inner: R,
+    inspect: F,
+    header: Vec<u8>,
+    state: LineState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineState {
+    LineStart,
+    Header,
+    Other,
+}
+
+impl<R: BufRead, F: FnMut(&str)> HeaderInspector<R, F> {
+    pub fn new(inner: R, inspect: F) -> Self {
+        Self {
+            inner,
+            inspect,
+            header: Vec::new(),
+            state: LineState::LineStart,
+        }
+    }
+
+    fn finish_header(&mut self) {
+        if self.state == LineState::Header {
+            let header = String::from_utf8_lossy(&self.header);
+            (self.inspect)(header.trim_end_matches('\r'));
+            self.header.clear();
+        }
+        self.state = LineState::LineStart;
+    }
+
+    fn inspect_consumed(&mut self, consumed: &[u8]) {
+        let mut consumed = consumed;
+        while !consumed.is_empty() {
+            if self.state == LineState::LineStart {
+                self.state = if consumed[0] == b'>' {
+                    LineState::Header
+                } else {
+                    LineState::Other
+                };
+            }
+
+            let line_end = consumed.iter().position(|&byte| byte == b'\n');
+            let line_part = &consumed[..line_end.unwrap_or(consumed.len())];
+            if self.state == LineState::Header {
+                self.header.extend_from_slice(line_part);
+            }
+
+            if let Some(line_end) = line_end {
+                self.finish_header();
+                consumed = &consumed[line_end + 1..];
+            } else {
+                consumed = &[];
+            }
+        }
+    }
+}
+
+impl<R: BufRead, F: FnMut(&str)> Read for HeaderInspector<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead, F: FnMut(&str)> BufRead for HeaderInspector<R, F> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.inner.fill_buf()?.is_empty() {
+            // The last header may not be terminated by a newline.
+            self.finish_header();
+        }
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // The buffer is still filled from the last call to `fill_buf`, so this does not read anything.
+        if let Ok(buffer) = self.inner.fill_buf() {
+            let consumed = buffer[..amount.min(buffer.len())].to_vec();
+            self.inspect_consumed(&consumed);
+        }
+        self.inner.consume(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_records, Bcalm2Header, Bcalm2Link, Bcalm2RecordCounts, HeaderInspector};
+    use std::io::{BufReader, Read};
+
+    #[test]
+    fn test_parse_header() {
+        let header = Bcalm2Header::parse(">2 LN:i:14 KC:i:43 km:f:43.0   L:+:1:+ L:-:0:-").unwrap();
+        assert_eq!(header.id, 2);
+        assert_eq!(
+            header.links,
+            vec![
+                Bcalm2Link {
+                    from_forwards: true,
+                    to_id: 1,
+                    to_forwards: true
+                },
+                Bcalm2Link {
+                    from_forwards: false,
+                    to_id: 0,
+                    to_forwards: false
+                },
+            ]
+        );
+        assert_eq!(header.links[1].to_string(), "L:-:0:-");
+        assert_eq!(Bcalm2Header::parse(">2 L:+:1"), None);
+    }
+
+    #[test]
+    fn test_header_inspector_passes_data_through() {
+        let input = ">0 LN:i:4\nACGT\n>1 LN:i:3\r\nACG";
+        let mut headers = Vec::new();
+        let mut output = String::new();
+        // A tiny buffer makes header lines span multiple reads.
+        HeaderInspector::new(BufReader::with_capacity(3, input.as_bytes()), |header| {
+            headers.push(header.to_owned())
+        })
+        .read_to_string(&mut output)
+        .unwrap();
+
+        assert_eq!(output, input);
+        assert_eq!(headers, vec![">0 LN:i:4", ">1 LN:i:3"]);
+    }
+
+    #[test]
+    fn test_count_records() {
+        let input = ">0 LN:i:4 L:+:1:+ L:-:1:-\nACGT\n>1 LN:i:4 L:-:0:-\nCGTA";
+        assert_eq!(
+            count_records(input.as_bytes()).unwrap(),
+            Bcalm2RecordCounts {
+                records: 2,
+                links: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::mirror_index::find_mirror_arcs;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// A simple bubble, i.e. parallel arcs between the same pair of nodes.
+/// Since the arcs of a de Bruijn graph are maximal unitigs, the branches of a simple bubble are single arcs,
+/// e.g. the two alleles of a SNP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bubble {
+    pub from: usize,
+    pub to: usize,
+    /// The indices of the arcs of the branches, ordered by descending weight, and by index for equal weights.
+    pub branches: Vec<usize>,
+    /// The weights of the branches.
+    pub weights: Vec<usize>,
+}
+
+impl Display for Bubble {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} with branch weights", self.from, self.to)?;
+        for (index, weight) in self.weights.iter().enumerate() {
+            let separator = if index == 0 { ' ' } else { ',' };
+            write!(f, "{separator}{weight}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the simple bubbles of the graph, in the order of their endpoints.
+/// An arc that is self-complemental, i.e. parallel to its own mirror arc, does not form a bubble with its mirror.
+pub fn find_bubbles(graph: &ArcCentricGraph) -> anyhow::Result<Vec<Bubble>> {
+    let mirrors = find_mirror_arcs(graph)?;
+    let mut parallel_arcs: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        let arcs = parallel_arcs.entry((arc.from, arc.to)).or_default();
+        // Of a self-complemental pair, only one arc is a branch.
+        if !arcs.contains(&mirrors[index]) {
+            arcs.push(index);
+        }
+    }
+
+    Ok(parallel_arcs
+        .into_iter()
+        .filter(|(_, branches)| branches.len() > 1)
+        .map(|((from, to), mut branches)| {
+            branches.sort_by_key(|&branch| std::cmp::Reverse(graph.arcs[branch].weight));
+            let weights = branches
+                .iter()
+                .map(|&branch| graph.arcs[branch].weight)
+                .collect();
+            Bubble {
+                from,
+                to,
+                branches,
+                weights,
+            }
+        })
+        .collect())
+}
+
+/// Remove all branches but the one with the highest weight from each bubble, together with their mirror arcs.
+/// Since the mirror of a bubble is a bubble as well, the kept branches of a bubble and its mirror are mirrors.
+/// Returns the graph and the number of removed arcs.
+pub fn pop_bubbles(
+    graph: &ArcCentricGraph,
+    bubbles: &[Bubble],
+) -> anyhow::Result<(ArcCentricGraph, usize)> {
+    let mirrors = find_mirror_arcs(graph)?;
+    let mut is_removed = vec![false; graph.arcs.len()];
+    for bubble in bubbles {
+        let branches: Vec<_> = bubble
+            .branches
+            .iter()
+            .copied()
+            .filter(|&branch| !is_removed[branch])
+            .collect();
+        let Some((&kept_branch, removed_branches)) = branches.split_first() else {
+            continue;
+        };
+        for &branch in removed_branches {
+            if branch != mirrors[kept_branch] {
+                is_removed[branch] = true;
+                is_removed[mirrors[branch]] = true;
+            }
+        }
+    }
+
+    let arcs: Vec<_> = graph
+        .arcs
+        .iter()
+        .zip(&is_removed)
+        .filter(|(_, is_removed)| !**is_removed)
+        .map(|(arc, _)| arc.clone())
+        .collect();
+    let removed_count = graph.arcs.len() - arcs.len();
+    Ok((
+        ArcCentricGraph {
+            node_count: graph.node_count,
+            arcs,
+        },
+        removed_count,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_bubbles, pop_bubbles, Bubble};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::output_validation::find_arcs_without_mirror;
+
+    #[test]
+    fn test_pop_bubbles() {
+        // A SNP bubble from node 0 to node 1 with the alleles C and G, and its mirror from node 2 to node 3.
+        let graph = read_arc_centric_graph(
+            "4\n0 1 9 2 3 AACTT\n0 1 2 2 3 AAGTT\n2 3 2 0 1 AACTT\n2 3 9 0 1 AAGTT\n".as_bytes(),
+        )
+        .unwrap();
+        let bubbles = find_bubbles(&graph).unwrap();
+        assert_eq!(
+            bubbles,
+            vec![
+                Bubble {
+                    from: 0,
+                    to: 1,
+                    branches: vec![0, 1],
+                    weights: vec![9, 2],
+                },
+                Bubble {
+                    from: 2,
+                    to: 3,
+                    branches: vec![3, 2],
+                    weights: vec![9, 2],
+                },
+            ]
+        );
+        assert_eq!(bubbles[0].to_string(), "0 -> 1 with branch weights 9,2");
+
+        let (popped, removed_count) = pop_bubbles(&graph, &bubbles).unwrap();
+        assert_eq!(removed_count, 2);
+        assert!(find_arcs_without_mirror(&popped).is_empty());
+        let sequences: Vec<_> = popped
+            .arcs
+            .iter()
+            .map(|arc| arc.sequence.as_str())
+            .collect();
+        assert_eq!(sequences, vec!["AACTT", "AAGTT"]);
+        assert_eq!(popped.arcs[0].weight, 9);
+        assert_eq!(popped.arcs[1].weight, 9);
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::{BufRead, Read, Write};
+
+/// An adapter that counts the bytes read from or written to the wrapped reader or writer.
+pub struct ByteCounter<T> {
+    inner: T,
+    bytes: usize,
+}
+
+impl<T> ByteCounter<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner, bytes: 0 }
+    }
+
+    /// The number of bytes read or written so far.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+impl<T: Read> Read for ByteCounter<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let amount = self.inner.read(buf)?;
+        self.bytes += amount;
+        Ok(amount)
+    }
+}
+
+impl<T: BufRead> BufRead for ByteCounter<T> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.bytes += amount;
+        self.inner.consume(amount);
+    }
+}
+
+impl<T: Write> Write for ByteCounter<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let amount = self.inner.write(buf)?;
+        self.bytes += amount;
+        Ok(amount)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ByteCounter;
+    use std::io::{BufRead, Write};
+
+    #[test]
+    fn test_byte_counter() {
+        let mut input = ByteCounter::new("first\nsecond\n".as_bytes());
+        let mut line = String::new();
+        input.read_line(&mut line).unwrap();
+        assert_eq!(input.bytes(), 6);
+
+        let mut output = ByteCounter::new(Vec::new());
+        write!(output, "{line}{line}").unwrap();
+        assert_eq!(output.bytes(), 12);
+    }
+}
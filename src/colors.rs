@@ -0,0 +1,94 @@
+use crate::record_transformer::FastaRecord;
+use std::collections::HashMap;
+
+/// The header tag that stores the colors of a unitig.
+/// The value is a string of `0` and `1` characters, where the `i`th character indicates
+/// if the unitig is present in the `i`th sample, e.g. `CL:Z:101`.
+pub const COLOR_TAG: &str = "CL:Z:";
+
+/// The colors of each bcalm2 record, indexed by its id.
+pub type ColorTable = HashMap<usize, Vec<bool>>;
+
+/// Parse a color bitmask as written after [`COLOR_TAG`].
+pub fn parse_colors(bitmask: &str) -> Option<Vec<bool>> {
+    bitmask
+        .chars()
+        .map(|character| match character {
+            '0' => Some(false),
+            '1' => Some(true),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Format colors as a bitmask as written after [`COLOR_TAG`].
+pub fn format_colors(colors: &[bool]) -> String {
+    colors
+        .iter()
+        .map(|&color| if color { '1' } else { '0' })
+        .collect()
+}
+
+/// Add the colors of `other` to `colors`, extending `colors` if `other` contains more samples.
+pub fn union_colors(colors: &mut Vec<bool>, other: &[bool]) {
+    if colors.len() < other.len() {
+        colors.resize(other.len(), false);
+    }
+    for (color, &other) in colors.iter_mut().zip(other) {
+        *color |= other;
+    }
+}
+
+/// Remove the color tag from a bcalm2 record, since the bcalm2 reader does not accept unknown tags,
+/// and store its colors in the table.
+/// Records with malformed color tags are collected in `malformed`.
+pub fn extract_record_colors(
+    record: &mut FastaRecord,
+    colors: &mut ColorTable,
+    malformed: &mut Vec<String>,
+) -> bool {
+    let mut bitmask = None;
+    let header: Vec<_> = record
+        .header
+        .split_whitespace()
+        .filter(|token| {
+            if let Some(value) = token.strip_prefix(COLOR_TAG) {
+                bitmask = Some(value.to_owned());
+                false
+            } else {
+                true
+            }
+        })
+        .collect();
+    let header = header.join(" ");
+
+    if let Some(bitmask) = bitmask {
+        let id = header
+            .split_whitespace()
+            .next()
+            .and_then(|id| id.parse().ok());
+        match (id, parse_colors(&bitmask)) {
+            (Some(id), Some(record_colors)) => {
+                colors.insert(id, record_colors);
+            }
+            _ => malformed.push(record.header.clone()),
+        }
+    }
+
+    record.header = header;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_colors, parse_colors, union_colors};
+
+    #[test]
+    fn test_colors() {
+        let mut colors = parse_colors("100").unwrap();
+        assert_eq!(colors, vec![true, false, false]);
+        union_colors(&mut colors, &parse_colors("0101").unwrap());
+        assert_eq!(format_colors(&colors), "1101");
+        assert_eq!(parse_colors("12"), None);
+    }
+}
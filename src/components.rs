@@ -0,0 +1,259 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use crate::mirror_index::find_mirror_arcs;
+
+/// A union-find structure over the nodes of a graph.
+pub struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    pub fn new(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    /// Join the sets of the two elements.
+    /// Returns false if they were already in the same set.
+    pub fn union(&mut self, first: usize, second: usize) -> bool {
+        let first = self.find(first);
+        let second = self.find(second);
+        self.parents[first] = second;
+        first != second
+    }
+}
+
+/// The index of the weakly connected component of each node.
+/// Components are numbered consecutively in the order of their lowest node.
+pub fn weak_components(graph: &ArcCentricGraph) -> Vec<usize> {
+    let mut sets = DisjointSets::new(graph.node_count);
+    for arc in &graph.arcs {
+        sets.union(arc.from, arc.to);
+    }
+    component_indices(&mut sets, graph.node_count)
+}
+
+/// The index of the connected component of each node, where the nodes of each arc are joined with the nodes
+/// of its mirror arc, such that both strands of a component are in the same component.
+/// Components are numbered like in [`weak_components`].
+pub fn bidirected_components(graph: &ArcCentricGraph) -> Vec<usize> {
+    let mut sets = mirror_joined_sets(graph);
+    component_indices(&mut sets, graph.node_count)
+}
+
+/// The nodes of each arc joined with each other and with the nodes of its mirror arc.
+fn mirror_joined_sets(graph: &ArcCentricGraph) -> DisjointSets {
+    let mut sets = DisjointSets::new(graph.node_count);
+    for arc in &graph.arcs {
+        sets.union(arc.from, arc.to);
+        sets.union(arc.from, arc.mirror_to);
+        sets.union(arc.to, arc.mirror_from);
+    }
+    sets
+}
+
+/// Number the sets consecutively in the order of their lowest element.
+fn component_indices(sets: &mut DisjointSets, node_count: usize) -> Vec<usize> {
+    let mut component_indices = vec![None; node_count];
+    let mut component_count = 0;
+    (0..node_count)
+        .map(|node| {
+            let root = sets.find(node);
+            *component_indices[root].get_or_insert_with(|| {
+                component_count += 1;
+                component_count - 1
+            })
+        })
+        .collect()
+}
+
+/// Keep only the component with the most arcs, and renumber its nodes consecutively in the order of their indices.
+/// Ties are broken towards the component with the lowest node.
+///
+/// The nodes of each arc are joined with the nodes of its mirror arc, such that the reverse complemental strand
+/// of the component is kept as well, and the mirror columns stay valid.
+/// Returns the kept component and the number of dropped components that contain arcs.
+pub fn largest_component(graph: &ArcCentricGraph) -> (ArcCentricGraph, usize) {
+    let mut sets = mirror_joined_sets(graph);
+
+    let mut arc_counts = vec![0; graph.node_count];
+    for arc in &graph.arcs {
+        arc_counts[sets.find(arc.from)] += 1;
+    }
+    let component_count = arc_counts.iter().filter(|&&count| count > 0).count();
+    let Some(largest) = (0..graph.node_count)
+        .map(|node| sets.find(node))
+        .filter(|&root| arc_counts[root] > 0)
+        .reduce(|largest, root| {
+            if arc_counts[root] > arc_counts[largest] {
+                root
+            } else {
+                largest
+            }
+        })
+    else {
+        return (graph.clone(), 0);
+    };
+
+    let mut node_indices = vec![None; graph.node_count];
+    let mut node_count = 0;
+    for (node, node_index) in node_indices.iter_mut().enumerate() {
+        if sets.find(node) == largest {
+            *node_index = Some(node_count);
+            node_count += 1;
+        }
+    }
+    let node_index = |node: usize| node_indices[node].expect("nodes of kept arcs are kept");
+
+    let arcs = graph
+        .arcs
+        .iter()
+        .filter(|arc| sets.find(arc.from) == largest)
+        .map(|arc| ArcRecord {
+            from: node_index(arc.from),
+            to: node_index(arc.to),
+            mirror_from: node_index(arc.mirror_from),
+            mirror_to: node_index(arc.mirror_to),
+            ..arc.clone()
+        })
+        .collect();
+    (ArcCentricGraph { node_count, arcs }, component_count - 1)
+}
+
+/// Keep only the arcs that join two connected components when joining the arcs in order,
+/// together with their mirror arcs, such that the mirror columns stay valid.
+///
+/// Each node is joined with its reverse complemental node beforehand, so the kept arcs form a spanning forest
+/// of the bidirected graph, where an arc and its mirror arc are a single edge between two nodes.
+/// This also holds if a component contains both strands.
+/// Returns the kept arcs and the number of dropped arcs.
+pub fn spanning_forest(graph: &ArcCentricGraph) -> anyhow::Result<(ArcCentricGraph, usize)> {
+    let mirrors = find_mirror_arcs(graph)?;
+    let mut sets = DisjointSets::new(graph.node_count);
+    for arc in &graph.arcs {
+        sets.union(arc.from, arc.mirror_to);
+        sets.union(arc.to, arc.mirror_from);
+    }
+    let mut is_kept = vec![false; graph.arcs.len()];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        if is_kept[index] || !sets.union(arc.from, arc.to) {
+            continue;
+        }
+        is_kept[index] = true;
+        is_kept[mirrors[index]] = true;
+    }
+
+    let arcs: Vec<_> = graph
+        .arcs
+        .iter()
+        .zip(&is_kept)
+        .filter(|(_, is_kept)| **is_kept)
+        .map(|(arc, _)| arc.clone())
+        .collect();
+    let dropped_count = graph.arcs.len() - arcs.len();
+    Ok((
+        ArcCentricGraph {
+            node_count: graph.node_count,
+            arcs,
+        },
+        dropped_count,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bidirected_components, largest_component, spanning_forest, weak_components};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_weak_components() {
+        let graph =
+            read_arc_centric_graph("5\n3 1 2 1 3 ACGT\n4 2 2 2 4 ACGT\n".as_bytes()).unwrap();
+        assert_eq!(weak_components(&graph), vec![0, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn test_largest_component() {
+        // A small self-complemental component on nodes 0 and 1, and a larger one on nodes 2 to 5.
+        let graph = read_arc_centric_graph(
+            "6\n0 1 5 0 1 AT\n2 3 1 4 5 AAC\n4 5 1 2 3 GTT\n3 4 1 3 4 ACGT\n".as_bytes(),
+        )
+        .unwrap();
+        let (largest, dropped) = largest_component(&graph);
+        assert_eq!(dropped, 1);
+        assert_eq!(largest.node_count, 4);
+        let arcs: Vec<_> = largest
+            .arcs
+            .iter()
+            .map(|arc| {
+                (
+                    arc.from,
+                    arc.to,
+                    arc.mirror_from,
+                    arc.mirror_to,
+                    arc.sequence.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            arcs,
+            vec![
+                (0, 1, 2, 3, "AAC"),
+                (2, 3, 0, 1, "GTT"),
+                (1, 2, 1, 2, "ACGT")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spanning_forest() {
+        // A cycle through nodes 0, 1 and 2, and its mirror cycle through nodes 3, 4 and 5.
+        let graph = read_arc_centric_graph(
+            "6\n0 1 1 4 3 AAAC\n4 3 1 0 1 GTTT\n1 2 1 5 4 AACC\n5 4 1 1 2 GGTT\n2 0 1 3 5 ACCC\n3 5 1 2 0 GGGT\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        let (forest, dropped) = spanning_forest(&graph).unwrap();
+        assert_eq!(dropped, 2);
+        assert_eq!(weak_components(&forest), weak_components(&graph));
+        // A forest has one arc less than nodes in each component.
+        let component_count = weak_components(&forest).into_iter().max().unwrap() + 1;
+        assert_eq!(forest.arcs.len(), forest.node_count - component_count);
+        let sequences: Vec<_> = forest
+            .arcs
+            .iter()
+            .map(|arc| arc.sequence.as_str())
+            .collect();
+        assert_eq!(sequences, vec!["AAAC", "GTTT", "AACC", "GGTT"]);
+    }
+
+    #[test]
+    fn test_spanning_forest_of_both_strands() {
+        // The arcs ACA and CAGT both join the node AC and its reverse complement GT with the node CA
+        // and its reverse complement TG, so they form a cycle in the bidirected graph.
+        let graph = read_arc_centric_graph(
+            "4\n0 2 1 3 1 ACA\n3 1 1 0 2 TGT\n2 1 1 0 3 CAGT\n0 3 1 2 1 ACTG\n".as_bytes(),
+        )
+        .unwrap();
+        let (forest, dropped) = spanning_forest(&graph).unwrap();
+        assert_eq!(dropped, 2);
+        assert_eq!(
+            bidirected_components(&forest),
+            bidirected_components(&graph)
+        );
+        let sequences: Vec<_> = forest
+            .arcs
+            .iter()
+            .map(|arc| arc.sequence.as_str())
+            .collect();
+        assert_eq!(sequences, vec!["ACA", "TGT"]);
+    }
+}
@@ -0,0 +1,45 @@
+use flate2::bufread::MultiGzDecoder;
+use log::info;
+use std::io::{BufRead, BufReader};
+
+/// The magic bytes at the start of each gzip member.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The compression of an input, as detected by [`detect_compression`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    /// Gzip with the block structure of bgzip, marked by a `BC` extra subfield in each member.
+    Bgzip,
+}
+
+/// Detect the compression of an input from its first bytes, without consuming them.
+pub fn detect_compression(input: &mut impl BufRead) -> std::io::Result<Compression> {
+    let header = input.fill_buf()?;
+    if !header.starts_with(&GZIP_MAGIC) {
+        return Ok(Compression::None);
+    }
+
+    // The FEXTRA flag is set and the first extra subfield has the identifier `BC`.
+    let has_extra_field = header.len() > 3 && header[3] & 0x04 != 0;
+    if has_extra_field && header.len() >= 14 && &header[12..14] == b"BC" {
+        Ok(Compression::Bgzip)
+    } else {
+        Ok(Compression::Gzip)
+    }
+}
+
+/// Transparently decompress the input if it is gzip or bgzip compressed.
+///
+/// bgzip files consist of many concatenated gzip members, so they are decompressed as a stream of all members.
+/// Their block index is not used, since the input is always read from start to end.
+pub fn decompress<'a>(mut input: impl BufRead + 'a) -> std::io::Result<Box<dyn BufRead + 'a>> {
+    Ok(match detect_compression(&mut input)? {
+        Compression::None => Box::new(input),
+        compression => {
+            info!("Decompressing {compression:?} input");
+            Box::new(BufReader::new(MultiGzDecoder::new(input)))
+        }
+    })
+}
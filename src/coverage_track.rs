@@ -0,0 +1,134 @@
+use anyhow::{bail, ensure, Context};
+use std::io::Write;
+
+/// A linear order of oriented unitigs, e.g. along a reference genome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceOrder {
+    /// The name of the reference sequence in the coverage track.
+    pub name: String,
+    /// The bcalm2 ids of the unitigs, with `true` if the unitig is in forward orientation.
+    pub unitigs: Vec<(usize, bool)>,
+}
+
+impl ReferenceOrder {
+    /// Parse oriented unitigs like `3+` or `5-`, separated by whitespace or commas.
+    pub fn parse(name: String, content: &str) -> anyhow::Result<Self> {
+        let unitigs = content
+            .split(|character: char| character.is_whitespace() || character == ',')
+            .filter(|unitig| !unitig.is_empty())
+            .map(|unitig| {
+                let (id, forwards) = if let Some(id) = unitig.strip_suffix('+') {
+                    (id, true)
+                } else if let Some(id) = unitig.strip_suffix('-') {
+                    (id, false)
+                } else {
+                    bail!("Oriented unitig {unitig:?} does not end with + or -");
+                };
+                let id = id
+                    .parse()
+                    .with_context(|| format!("Malformed unitig id in {unitig:?}"))?;
+                Ok((id, forwards))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { name, unitigs })
+    }
+}
+
+/// The number of decimal places of the coverage.
+pub const COVERAGE_PRECISION: i32 = 2;
+
+/// An interval of bases with the same coverage, with an exclusive end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageInterval {
+    pub start: usize,
+    pub end: usize,
+    pub coverage: f64,
+}
+
+/// Project the abundances of consecutive unitigs onto the sequence they spell, given as `(sequence, mean abundance)`
+/// in the reference order and orientation. Consecutive unitigs must overlap by `k-1` characters.
+///
+/// Each k-mer of a unitig has the mean abundance of the unitig,
+/// and the coverage of a base is the mean abundance of the k-mers that contain it.
+/// Consecutive bases with the same coverage, rounded to [`COVERAGE_PRECISION`] decimal places, form an interval.
+pub fn coverage_intervals(
+    unitigs: &[(String, f64)],
+    k: usize,
+) -> anyhow::Result<Vec<CoverageInterval>> {
+    let mut kmer_abundances = Vec::new();
+    for (index, (sequence, abundance)) in unitigs.iter().enumerate() {
+        ensure!(
+            sequence.len() >= k,
+            "Unitig {index} of the reference order is shorter than k = {k}"
+        );
+        if let Some((previous, _)) = index.checked_sub(1).map(|previous| &unitigs[previous]) {
+            ensure!(
+                previous[previous.len() - (k - 1)..] == sequence[..k - 1],
+                "Unitigs {} and {index} of the reference order do not overlap by k-1 = {} characters",
+                index - 1,
+                k - 1
+            );
+        }
+        kmer_abundances.extend(std::iter::repeat(*abundance).take(sequence.len() - (k - 1)));
+    }
+    if kmer_abundances.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut prefix_sums = vec![0.0];
+    for abundance in &kmer_abundances {
+        prefix_sums.push(prefix_sums.last().unwrap() + abundance);
+    }
+    let length = kmer_abundances.len() + k - 1;
+    let mut intervals: Vec<CoverageInterval> = Vec::new();
+    for base in 0..length {
+        let first_kmer = (base + 1).saturating_sub(k);
+        let last_kmer = base.min(kmer_abundances.len() - 1);
+        let coverage = (prefix_sums[last_kmer + 1] - prefix_sums[first_kmer])
+            / (last_kmer + 1 - first_kmer) as f64;
+        let coverage = round_coverage(coverage);
+        match intervals.last_mut() {
+            Some(interval) if interval.coverage == coverage => interval.end = base + 1,
+            _ => intervals.push(CoverageInterval {
+                start: base,
+                end: base + 1,
+                coverage,
+            }),
+        }
+    }
+    Ok(intervals)
+}
+
+fn round_coverage(coverage: f64) -> f64 {
+    let factor = 10f64.powi(COVERAGE_PRECISION);
+    (coverage * factor).round() / factor
+}
+
+/// Write the intervals as bedGraph track, with zero-based starts and exclusive ends.
+pub fn write_bedgraph(
+    name: &str,
+    intervals: &[CoverageInterval],
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    writeln!(output, "track type=bedGraph name=\"{name} coverage\"")?;
+    for interval in intervals {
+        writeln!(
+            output,
+            "{name}\t{}\t{}\t{}",
+            interval.start, interval.end, interval.coverage
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReferenceOrder;
+
+    #[test]
+    fn test_parse_reference_order() {
+        let order = ReferenceOrder::parse("chr1".to_owned(), "2+ 0-,\n13+\n").unwrap();
+        assert_eq!(order.unitigs, vec![(2, true), (0, false), (13, true)]);
+        assert!(ReferenceOrder::parse("chr1".to_owned(), "2").is_err());
+    }
+}
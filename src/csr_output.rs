@@ -0,0 +1,208 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use anyhow::ensure;
+use std::io::Write;
+
+/// The first bytes of the CSR format.
+pub const CSR_MAGIC: &[u8; 8] = b"ARCCSR01";
+
+/// Write the graph in a compressed sparse row layout, such that it can be used directly from a memory mapping.
+///
+/// All integers are unsigned 64-bit little-endian, and the layout is:
+///  * the magic bytes [`CSR_MAGIC`],
+///  * the number of nodes `n`, the number of arcs `m` and the number of sequence bytes `s`,
+///  * `n + 1` row offsets, where the arcs leaving node `i` are the arcs `row_offsets[i] .. row_offsets[i + 1]`,
+///  * `m` column indices, i.e. the target nodes of the arcs,
+///  * `m` weights,
+///  * `m + 1` sequence offsets, where the sequence of arc `j` is `sequence_offsets[j] .. sequence_offsets[j + 1]`,
+///  * the `s` bytes of the concatenated sequences.
+///
+/// The arcs are ordered by their source node, and otherwise keep their order.
+/// Mirror arcs, colors and tags are not written.
+pub fn write_csr(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let mut arcs: Vec<_> = graph.arcs.iter().collect();
+    arcs.sort_by_key(|arc| arc.from);
+    if let Some(arc) = arcs.last() {
+        ensure!(
+            arc.from < graph.node_count,
+            "Arc {} -> {} leaves a node that is not below the node count {}",
+            arc.from,
+            arc.to,
+            graph.node_count
+        );
+    }
+    let sequence_bytes: usize = arcs.iter().map(|arc| arc.sequence.len()).sum();
+
+    output.write_all(CSR_MAGIC)?;
+    for value in [graph.node_count, arcs.len(), sequence_bytes] {
+        write_integer(output, value)?;
+    }
+    let mut row_offset = 0;
+    for node in 0..=graph.node_count {
+        write_integer(output, row_offset)?;
+        while arcs.get(row_offset).is_some_and(|arc| arc.from == node) {
+            row_offset += 1;
+        }
+    }
+    for arc in &arcs {
+        write_integer(output, arc.to)?;
+    }
+    for arc in &arcs {
+        write_integer(output, arc.weight)?;
+    }
+    let mut sequence_offset = 0;
+    write_integer(output, sequence_offset)?;
+    for arc in &arcs {
+        sequence_offset += arc.sequence.len();
+        write_integer(output, sequence_offset)?;
+    }
+    for arc in &arcs {
+        output.write_all(arc.sequence.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn write_integer(output: &mut impl Write, value: usize) -> std::io::Result<()> {
+    output.write_all(&(value as u64).to_le_bytes())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::{write_csr, CSR_MAGIC};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::node_to_arc_centric_dbg;
+    use crate::test_fixtures::COMPLEX_FILE;
+    use anyhow::ensure;
+    use std::fs::File;
+    use std::os::unix::io::AsRawFd;
+
+    /// The size in bytes of the integers of the CSR format.
+    const INTEGER_SIZE: usize = 8;
+
+    /// A graph in the CSR format of [`write_csr`], read in place from its bytes, e.g. from a memory mapping.
+    #[derive(Debug, Clone, Copy)]
+    struct CsrView<'bytes> {
+        node_count: usize,
+        arc_count: usize,
+        row_offsets: &'bytes [u8],
+        column_indices: &'bytes [u8],
+        weights: &'bytes [u8],
+        sequence_offsets: &'bytes [u8],
+        sequences: &'bytes [u8],
+    }
+
+    impl<'bytes> CsrView<'bytes> {
+        /// Check the layout of the bytes, without reading the arrays.
+        fn new(bytes: &'bytes [u8]) -> anyhow::Result<Self> {
+            ensure!(
+                bytes.starts_with(CSR_MAGIC),
+                "The CSR format must start with {:?}",
+                String::from_utf8_lossy(CSR_MAGIC)
+            );
+            let mut remaining = &bytes[CSR_MAGIC.len()..];
+            let mut take = |length: usize| {
+                ensure!(
+                    remaining.len() >= length,
+                    "The CSR format is truncated, expected {length} more bytes but found {}",
+                    remaining.len()
+                );
+                let (taken, rest) = remaining.split_at(length);
+                remaining = rest;
+                Ok(taken)
+            };
+            let header = take(3 * INTEGER_SIZE)?;
+            let [node_count, arc_count, sequence_bytes] =
+                [0, 1, 2].map(|index| read_integer(header, index));
+            let view = Self {
+                node_count,
+                arc_count,
+                row_offsets: take((node_count + 1) * INTEGER_SIZE)?,
+                column_indices: take(arc_count * INTEGER_SIZE)?,
+                weights: take(arc_count * INTEGER_SIZE)?,
+                sequence_offsets: take((arc_count + 1) * INTEGER_SIZE)?,
+                sequences: take(sequence_bytes)?,
+            };
+            ensure!(
+                remaining.is_empty(),
+                "The CSR format has {} trailing bytes",
+                remaining.len()
+            );
+            Ok(view)
+        }
+
+        /// The arcs leaving the given node, as `(to, weight, sequence)`.
+        fn arcs(&self, node: usize) -> impl Iterator<Item = (usize, usize, &'bytes [u8])> + '_ {
+            let start = read_integer(self.row_offsets, node);
+            let end = read_integer(self.row_offsets, node + 1);
+            (start..end).map(|arc| {
+                let sequence_start = read_integer(self.sequence_offsets, arc);
+                let sequence_end = read_integer(self.sequence_offsets, arc + 1);
+                (
+                    read_integer(self.column_indices, arc),
+                    read_integer(self.weights, arc),
+                    &self.sequences[sequence_start..sequence_end],
+                )
+            })
+        }
+    }
+
+    /// The integer at the given index of an array of integers.
+    fn read_integer(bytes: &[u8], index: usize) -> usize {
+        let mut integer = [0; INTEGER_SIZE];
+        integer.copy_from_slice(&bytes[index * INTEGER_SIZE..(index + 1) * INTEGER_SIZE]);
+        u64::from_le_bytes(integer) as usize
+    }
+
+    #[test]
+    fn test_csr_round_trip() {
+        let mut text_output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut text_output).unwrap();
+        let graph = read_arc_centric_graph(text_output.as_slice()).unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("graph.csr");
+        write_csr(&graph, &mut File::create(&path).unwrap()).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let length = file.metadata().unwrap().len() as usize;
+        // SAFETY: the file is not modified while it is mapped, and the mapping is only read within its length.
+        let bytes = unsafe {
+            let mapping = libc::mmap(
+                std::ptr::null_mut(),
+                length,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            );
+            assert_ne!(mapping, libc::MAP_FAILED);
+            std::slice::from_raw_parts(mapping.cast::<u8>(), length)
+        };
+        let view = CsrView::new(bytes).unwrap();
+        assert_eq!(view.node_count, graph.node_count);
+        assert_eq!(view.arc_count, graph.arcs.len());
+
+        let mut csr_arcs: Vec<_> = (0..view.node_count)
+            .flat_map(|node| {
+                view.arcs(node).map(move |(to, weight, sequence)| {
+                    (
+                        node,
+                        to,
+                        weight,
+                        String::from_utf8(sequence.to_vec()).unwrap(),
+                    )
+                })
+            })
+            .collect();
+        let mut text_arcs: Vec<_> = graph
+            .arcs
+            .iter()
+            .map(|arc| (arc.from, arc.to, arc.weight, arc.sequence.clone()))
+            .collect();
+        csr_arcs.sort();
+        text_arcs.sort();
+        assert_eq!(csr_arcs, text_arcs);
+
+        // SAFETY: the mapping was created above with this length, and is not used afterwards.
+        assert_eq!(unsafe { libc::munmap(bytes.as_ptr() as *mut _, length) }, 0);
+    }
+}
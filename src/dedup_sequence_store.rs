@@ -0,0 +1,155 @@
+use genome_graph::bigraph::traitgraph::traitsequence::interface::Sequence;
+use genome_graph::compact_genome::implementation::DefaultSequenceStore;
+use genome_graph::compact_genome::interface::alphabet::dna_alphabet::DnaAlphabet;
+use genome_graph::compact_genome::interface::alphabet::AlphabetError;
+use genome_graph::compact_genome::interface::sequence::GenomeSequence;
+use genome_graph::compact_genome::interface::sequence_store::SequenceStore;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+type Handle = <DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::Handle;
+
+/// A sequence store that stores each distinct sequence only once.
+/// Sequences are addressed by the hash of their content, and adding a sequence that is already stored
+/// returns the handle of the stored copy.
+/// The handles are those of a [`DefaultSequenceStore`], so after reading, the store can be unwrapped with
+/// [`DedupSequenceStore::into_inner`].
+#[derive(Default)]
+pub struct DedupSequenceStore {
+    store: DefaultSequenceStore<DnaAlphabet>,
+    handles_by_hash: HashMap<u64, Vec<Handle>>,
+    statistics: DeduplicationStatistics,
+}
+
+/// Counts of the sequences added to a [`DedupSequenceStore`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeduplicationStatistics {
+    /// The number of added sequences.
+    pub total_sequences: usize,
+    /// The number of distinct sequences that were actually stored.
+    pub unique_sequences: usize,
+}
+
+impl DedupSequenceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn statistics(&self) -> DeduplicationStatistics {
+        self.statistics
+    }
+
+    /// Return the underlying sequence store, which can be used with all handles returned by this store.
+    pub fn into_inner(self) -> DefaultSequenceStore<DnaAlphabet> {
+        self.store
+    }
+}
+
+impl DeduplicationStatistics {
+    /// The number of added sequences that were not stored because an identical sequence was stored already.
+    pub fn duplicate_sequences(&self) -> usize {
+        self.total_sequences - self.unique_sequences
+    }
+
+    /// The number of added sequences per stored sequence.
+    pub fn ratio(&self) -> f64 {
+        if self.unique_sequences == 0 {
+            1.0
+        } else {
+            self.total_sequences as f64 / self.unique_sequences as f64
+        }
+    }
+}
+
+impl Display for DeduplicationStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "stored {} unique out of {} sequences (deduplication ratio {:.2})",
+            self.unique_sequences,
+            self.total_sequences,
+            self.ratio()
+        )
+    }
+}
+
+impl SequenceStore<DnaAlphabet> for DedupSequenceStore {
+    type Handle = Handle;
+    type SequenceRef =
+        <DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::SequenceRef;
+
+    fn add<
+        Sequence: GenomeSequence<DnaAlphabet, Subsequence> + ?Sized,
+        Subsequence: GenomeSequence<DnaAlphabet, Subsequence> + ?Sized,
+    >(
+        &mut self,
+        s: &Sequence,
+    ) -> Self::Handle {
+        self.add_from_iter_u8(s.iter().map(|character| (*character).into()))
+            .unwrap()
+    }
+
+    fn add_from_iter_u8<IteratorType: IntoIterator<Item = u8>>(
+        &mut self,
+        iter: IteratorType,
+    ) -> Result<Self::Handle, AlphabetError> {
+        let sequence: Vec<u8> = iter.into_iter().collect();
+        let mut hasher = DefaultHasher::new();
+        sequence.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let candidates = self.handles_by_hash.entry(hash).or_default();
+        // Different sequences may have the same hash, so compare the actual content.
+        if let Some(handle) = candidates.iter().find(|handle| {
+            let stored = self.store.get(handle);
+            stored.len() == sequence.len()
+                && stored
+                    .iter()
+                    .zip(&sequence)
+                    .all(|(character, byte)| u8::from(*character) == *byte)
+        }) {
+            self.statistics.total_sequences += 1;
+            return Ok(*handle);
+        }
+
+        let handle = self.store.add_from_slice_u8(&sequence)?;
+        candidates.push(handle);
+        self.statistics.total_sequences += 1;
+        self.statistics.unique_sequences += 1;
+        Ok(handle)
+    }
+
+    fn get(&self, handle: &Self::Handle) -> &Self::SequenceRef {
+        self.store.get(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DedupSequenceStore, DeduplicationStatistics};
+    use genome_graph::compact_genome::interface::sequence::GenomeSequence;
+    use genome_graph::compact_genome::interface::sequence_store::SequenceStore;
+
+    #[test]
+    fn test_dedup_sequence_store() {
+        let mut store = DedupSequenceStore::new();
+        let acgt = store.add_from_slice_u8(b"ACGT").unwrap();
+        let ac = store.add_from_slice_u8(b"AC").unwrap();
+        assert_ne!(acgt, ac);
+        assert_eq!(store.add_from_slice_u8(b"ACGT").unwrap(), acgt);
+        assert!(store.add_from_slice_u8(b"ACGX").is_err());
+
+        assert_eq!(
+            store.statistics(),
+            DeduplicationStatistics {
+                total_sequences: 3,
+                unique_sequences: 2,
+            }
+        );
+        let store = store.into_inner();
+        assert_eq!(store.get(&acgt).as_string(), "ACGT");
+        assert_eq!(store.get(&ac).as_string(), "AC");
+    }
+}
@@ -0,0 +1,249 @@
+use crate::arc_centric_reader::{
+    normalise_sequence, reverse_complement, ArcCentricGraph, ArcRecord,
+};
+#[cfg(test)]
+use anyhow::{bail, Context};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// An arc together with its reverse complement, identified by the lexicographically smaller of both.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArcPair {
+    pub from: usize,
+    pub to: usize,
+    pub sequence: String,
+}
+
+impl ArcPair {
+    pub fn of(arc: &ArcRecord) -> Self {
+        let sequence = normalise_sequence(&arc.sequence);
+        let mirror_sequence = reverse_complement(&sequence);
+        let (from, to, sequence) =
+            (arc.from, arc.to, sequence).min((arc.mirror_from, arc.mirror_to, mirror_sequence));
+        Self { from, to, sequence }
+    }
+}
+
+impl Display for ArcPair {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {} {}", self.from, self.to, self.sequence)
+    }
+}
+
+/// The differences between two arc-centric graphs, where an arc and its reverse complement are treated as a unit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub only_in_first: Vec<ArcPair>,
+    pub only_in_second: Vec<ArcPair>,
+    /// Pairs present in both graphs, with their total weight in the first and the second graph.
+    pub differing_weights: Vec<(ArcPair, usize, usize)>,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_first.is_empty()
+            && self.only_in_second.is_empty()
+            && self.differing_weights.is_empty()
+    }
+
+    /// One line per difference, prefixed with `<` for the first graph, `>` for the second, and `~` for weights.
+    pub fn details(&self) -> impl Iterator<Item = String> + '_ {
+        self.only_in_first
+            .iter()
+            .map(|pair| format!("< {pair}"))
+            .chain(self.only_in_second.iter().map(|pair| format!("> {pair}")))
+            .chain(
+                self.differing_weights
+                    .iter()
+                    .map(|(pair, first, second)| format!("~ {pair} weight {first} -> {second}")),
+            )
+    }
+}
+
+impl Display for GraphDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} arc pairs only in the first graph, {} only in the second, {} with differing weights",
+            self.only_in_first.len(),
+            self.only_in_second.len(),
+            self.differing_weights.len()
+        )
+    }
+}
+
+/// Sum up the weights of all arcs of each pair.
+fn pair_weights(graph: &ArcCentricGraph) -> BTreeMap<ArcPair, usize> {
+    let mut weights = BTreeMap::new();
+    for arc in &graph.arcs {
+        *weights.entry(ArcPair::of(arc)).or_default() += arc.weight;
+    }
+    weights
+}
+
+/// Compare two arc-centric graphs.
+/// An arc and its reverse complement are compared as a unit, whose weight is the sum of the weights of its arcs.
+/// Hence, merging a pair of reverse complemental arcs into a single arc does not count as difference.
+pub fn diff_graphs(first: &ArcCentricGraph, second: &ArcCentricGraph) -> GraphDiff {
+    let first = pair_weights(first);
+    let mut second = pair_weights(second);
+    let mut diff = GraphDiff::default();
+
+    for (pair, first_weight) in first {
+        match second.remove(&pair) {
+            None => diff.only_in_first.push(pair),
+            Some(second_weight) if second_weight != first_weight => {
+                diff.differing_weights
+                    .push((pair, first_weight, second_weight))
+            }
+            Some(_) => {}
+        }
+    }
+    diff.only_in_second.extend(second.into_keys());
+    diff
+}
+
+/// The changes that turn one arc-centric graph into another, arc by arc.
+///
+/// Unlike [`GraphDiff`], reverse complemental arcs are not treated as a unit, such that applying the patch
+/// with [`apply_patch`] reconstructs all arcs of the new graph, including their tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphPatch {
+    /// The node count of the new graph.
+    pub node_count: usize,
+    pub removed: Vec<ArcRecord>,
+    pub added: Vec<ArcRecord>,
+    /// Arcs of the base graph that only differ in their weight, with their weight in the new graph.
+    pub changed: Vec<(ArcRecord, usize)>,
+}
+
+#[cfg(test)]
+impl GraphPatch {
+    /// Parse a patch as written by its [`Display`] implementation.
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let mut lines = input.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().context("The patch is empty")?;
+        let mut patch = Self {
+            node_count: header
+                .trim()
+                .parse()
+                .with_context(|| format!("Malformed patch header {header:?}"))?,
+            ..Default::default()
+        };
+        for line in lines {
+            let (operation, arc) = line
+                .split_once(' ')
+                .with_context(|| format!("Malformed patch line {line:?}"))?;
+            match operation {
+                "-" => patch.removed.push(arc.parse()?),
+                "+" => patch.added.push(arc.parse()?),
+                "~" => {
+                    let (weight, arc) = arc
+                        .split_once(' ')
+                        .with_context(|| format!("Malformed patch line {line:?}"))?;
+                    let weight = weight
+                        .parse()
+                        .with_context(|| format!("Malformed weight in patch line {line:?}"))?;
+                    patch.changed.push((arc.parse()?, weight));
+                }
+                _ => bail!("Unknown patch operation {operation:?}"),
+            }
+        }
+        Ok(patch)
+    }
+}
+
+/// The node count of the new graph, followed by one line per change.
+/// Removed arcs are prefixed with `-`, added arcs with `+`, and arcs with a changed weight
+/// with `~` and their new weight.
+impl Display for GraphPatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.node_count)?;
+        for arc in &self.removed {
+            writeln!(f, "- {arc}")?;
+        }
+        for arc in &self.added {
+            writeln!(f, "+ {arc}")?;
+        }
+        for (arc, weight) in &self.changed {
+            writeln!(f, "~ {weight} {arc}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An arc line with its weight set to zero, such that arcs that only differ in their weight have the same key.
+fn weightless_key(arc: &ArcRecord) -> String {
+    ArcRecord {
+        weight: 0,
+        ..arc.clone()
+    }
+    .to_string()
+}
+
+/// Compute the patch that turns the `base` graph into the `new` graph.
+pub fn patch_graphs(base: &ArcCentricGraph, new: &ArcCentricGraph) -> GraphPatch {
+    let mut base_arcs: BTreeMap<String, Vec<&ArcRecord>> = BTreeMap::new();
+    for arc in &base.arcs {
+        base_arcs.entry(arc.to_string()).or_default().push(arc);
+    }
+    let mut added = Vec::new();
+    for arc in &new.arcs {
+        match base_arcs.get_mut(&arc.to_string()).and_then(Vec::pop) {
+            Some(_) => {}
+            None => added.push(arc),
+        }
+    }
+
+    let mut removed: BTreeMap<String, Vec<&ArcRecord>> = BTreeMap::new();
+    for arc in base_arcs.into_values().flatten() {
+        removed.entry(weightless_key(arc)).or_default().push(arc);
+    }
+    let mut patch = GraphPatch {
+        node_count: new.node_count,
+        ..Default::default()
+    };
+    for arc in added {
+        match removed.get_mut(&weightless_key(arc)).and_then(Vec::pop) {
+            Some(base_arc) => patch.changed.push((base_arc.clone(), arc.weight)),
+            None => patch.added.push(arc.clone()),
+        }
+    }
+    patch
+        .removed
+        .extend(removed.into_values().flatten().cloned());
+    patch
+}
+
+/// The first slot with the given arc.
+#[cfg(test)]
+fn find_arc<'arcs>(
+    arcs: &'arcs mut [Option<ArcRecord>],
+    arc: &ArcRecord,
+) -> anyhow::Result<&'arcs mut Option<ArcRecord>> {
+    arcs.iter_mut()
+        .find(|candidate| candidate.as_ref() == Some(arc))
+        .with_context(|| format!("The base graph does not contain the arc {arc}"))
+}
+
+#[cfg(test)]
+/// Apply a patch computed with [`patch_graphs`] to its base graph.
+/// The remaining arcs of the base graph keep their order, and the added arcs are appended.
+pub fn apply_patch(base: &ArcCentricGraph, patch: &GraphPatch) -> anyhow::Result<ArcCentricGraph> {
+    let mut arcs: Vec<Option<ArcRecord>> = base.arcs.iter().cloned().map(Some).collect();
+    for arc in &patch.removed {
+        *find_arc(&mut arcs, arc)? = None;
+    }
+    for (arc, weight) in &patch.changed {
+        if let Some(arc) = find_arc(&mut arcs, arc)? {
+            arc.weight = *weight;
+        }
+    }
+
+    let mut graph = ArcCentricGraph {
+        node_count: patch.node_count,
+        arcs: arcs.into_iter().flatten().collect(),
+    };
+    graph.arcs.extend(patch.added.iter().cloned());
+    Ok(graph)
+}
@@ -0,0 +1,54 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use anyhow::bail;
+use std::io::Write;
+
+/// Write the graph as a DIMACS maximum flow problem from `source` to `sink`,
+/// with the weights of the arcs as capacities.
+///
+/// The problem line is `p max <nodes> <arcs>`, followed by the source and sink lines `n <node> s` and `n <node> t`,
+/// and one line `a <from> <to> <capacity>` per arc. The nodes are 1-indexed, as required by the format.
+pub fn write_dimacs(
+    graph: &ArcCentricGraph,
+    source: usize,
+    sink: usize,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    for (name, node) in [("source", source), ("sink", sink)] {
+        if node >= graph.node_count {
+            bail!(
+                "The {name} {node} is out of range for {} nodes",
+                graph.node_count
+            );
+        }
+    }
+    if source == sink {
+        bail!("The source and the sink must be different nodes");
+    }
+
+    writeln!(output, "p max {} {}", graph.node_count, graph.arcs.len())?;
+    writeln!(output, "n {} s", source + 1)?;
+    writeln!(output, "n {} t", sink + 1)?;
+    for arc in &graph.arcs {
+        writeln!(output, "a {} {} {}", arc.from + 1, arc.to + 1, arc.weight)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_dimacs;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_terminals_out_of_range() {
+        let graph = read_arc_centric_graph("2\n0 1 2 0 1 ACGT\n".as_bytes()).unwrap();
+        let mut output = Vec::new();
+        assert!(write_dimacs(&graph, 0, 2, &mut output).is_err());
+        assert!(write_dimacs(&graph, 1, 1, &mut output).is_err());
+        write_dimacs(&graph, 1, 0, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "p max 2 1\nn 2 s\nn 1 t\na 1 2 2\n"
+        );
+    }
+}
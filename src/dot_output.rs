@@ -0,0 +1,124 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::components::weak_components;
+use anyhow::{anyhow, bail};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Write the graph in the Graphviz DOT format, with each arc labelled by its weight.
+/// If `clusters` is set, then the nodes of each weakly connected component are wrapped in a cluster,
+/// such that the components are drawn separately.
+pub fn write_dot(
+    graph: &ArcCentricGraph,
+    clusters: bool,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    writeln!(output, "digraph dbg {{")?;
+    if clusters {
+        let components = weak_components(graph);
+        let component_count = components.iter().max().map_or(0, |max| max + 1);
+        let mut component_nodes = vec![Vec::new(); component_count];
+        for (node, component) in components.into_iter().enumerate() {
+            component_nodes[component].push(node);
+        }
+        for (component, nodes) in component_nodes.into_iter().enumerate() {
+            writeln!(output, "    subgraph cluster_{component} {{")?;
+            for node in nodes {
+                writeln!(output, "        {node};")?;
+            }
+            writeln!(output, "    }}")?;
+        }
+    } else {
+        for node in 0..graph.node_count {
+            writeln!(output, "    {node};")?;
+        }
+    }
+    for arc in &graph.arcs {
+        writeln!(
+            output,
+            "    {} -> {} [label=\"{}\"];",
+            arc.from, arc.to, arc.weight
+        )?;
+    }
+    writeln!(output, "}}")?;
+    Ok(())
+}
+
+/// Render the graph as PNG by piping it to the Graphviz `dot` command.
+/// See [`write_dot`] for `clusters`.
+pub fn render_png(graph: &ArcCentricGraph, clusters: bool, path: &Path) -> anyhow::Result<()> {
+    let mut dot = Command::new("dot")
+        .arg("-Tpng")
+        .arg("-o")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            if error.kind() == ErrorKind::NotFound {
+                anyhow!(
+                    "Could not find the `dot` command, rendering requires Graphviz to be installed"
+                )
+            } else {
+                anyhow!("Could not run `dot`: {error}")
+            }
+        })?;
+
+    let mut stdin = dot.stdin.take().expect("stdin is piped");
+    write_dot(graph, clusters, &mut stdin)?;
+    drop(stdin);
+
+    let status = dot.wait()?;
+    if !status.success() {
+        bail!("`dot` failed with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_dot;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_write_dot() {
+        let graph =
+            read_arc_centric_graph("3\n0 1 5 2 0 ACGT\n2 0 5 0 1 ACGT\n".as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write_dot(&graph, false, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "digraph dbg {
+    0;
+    1;
+    2;
+    0 -> 1 [label=\"5\"];
+    2 -> 0 [label=\"5\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_write_dot_clusters() {
+        let graph =
+            read_arc_centric_graph("4\n0 1 5 1 0 ACGT\n2 3 5 3 2 ACGT\n".as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write_dot(&graph, true, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "digraph dbg {
+    subgraph cluster_0 {
+        0;
+        1;
+    }
+    subgraph cluster_1 {
+        2;
+        3;
+    }
+    0 -> 1 [label=\"5\"];
+    2 -> 3 [label=\"5\"];
+}
+"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::io::{self, Write};
+use std::mem;
+use std::sync::mpsc::{channel, sync_channel, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+/// The number of bytes that are collected before they are passed to the writer thread.
+const BATCH_SIZE: usize = 1 << 20;
+
+/// Writes through a separate thread, such that the next batch of output can be formatted
+/// while the previous batch is written.
+///
+/// The batches are passed through a bounded channel, such that at most one batch waits while another is written.
+/// Written batches are sent back to be reused. The inner writer is returned by [`DoubleBufferedWriter::finish`],
+/// which also reports errors of the writer thread. Errors are also reported by the next write after they happened.
+pub struct DoubleBufferedWriter<W> {
+    batch: Vec<u8>,
+    batches: Option<SyncSender<Vec<u8>>>,
+    free_batches: Receiver<Vec<u8>>,
+    writer: Option<JoinHandle<io::Result<W>>>,
+}
+
+impl<W: Write + Send + 'static> DoubleBufferedWriter<W> {
+    pub fn new(mut inner: W) -> Self {
+        let (batches, received_batches) = sync_channel::<Vec<u8>>(1);
+        let (returned_batches, free_batches) = channel();
+        let writer = thread::spawn(move || {
+            for mut batch in received_batches {
+                inner.write_all(&batch)?;
+                batch.clear();
+                // The receiver is only dropped after this thread finished.
+                let _ = returned_batches.send(batch);
+            }
+            inner.flush()?;
+            Ok(inner)
+        });
+        Self {
+            batch: Vec::with_capacity(BATCH_SIZE),
+            batches: Some(batches),
+            free_batches,
+            writer: Some(writer),
+        }
+    }
+
+    /// Write the remaining output, wait for the writer thread and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.send_batch()?;
+        self.join()
+    }
+
+    fn send_batch(&mut self) -> io::Result<()> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        let next_batch = self
+            .free_batches
+            .try_recv()
+            .unwrap_or_else(|_| Vec::with_capacity(BATCH_SIZE));
+        let batch = mem::replace(&mut self.batch, next_batch);
+        let sent = self
+            .batches
+            .as_ref()
+            .is_some_and(|batches| batches.send(batch).is_ok());
+        if sent {
+            Ok(())
+        } else {
+            // The writer thread stopped early, so it failed.
+            self.join().map(|_| ())
+        }
+    }
+
+    /// Stop the writer thread after it wrote all sent batches.
+    fn join(&mut self) -> io::Result<W> {
+        self.batches = None;
+        match self.writer.take() {
+            Some(writer) => writer.join().unwrap_or_else(|_| {
+                Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "The output writer thread panicked",
+                ))
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The output writer thread already stopped",
+            )),
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> Write for DoubleBufferedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.batch.extend_from_slice(buf);
+        if self.batch.len() >= BATCH_SIZE {
+            self.send_batch()?;
+        }
+        Ok(buf.len())
+    }
+
+    /// Pass the current batch to the writer thread, without waiting for it to be written.
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_batch()
+    }
+}
+
+impl<W> Drop for DoubleBufferedWriter<W> {
+    fn drop(&mut self) {
+        // Errors cannot be reported here, but the thread should not outlive the writer.
+        self.batches = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DoubleBufferedWriter, BATCH_SIZE};
+    use std::io::{self, Write};
+
+    #[test]
+    fn test_batches() {
+        let data: Vec<u8> = (0..3 * BATCH_SIZE + 17)
+            .map(|index| b'A' + (index % 26) as u8)
+            .collect();
+        let mut writer = DoubleBufferedWriter::new(Vec::new());
+        for chunk in data.chunks(1000) {
+            writer.write_all(chunk).unwrap();
+        }
+        assert_eq!(writer.finish().unwrap(), data);
+    }
+
+    #[derive(Debug)]
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_writer_error() {
+        let mut writer = DoubleBufferedWriter::new(FailingWriter);
+        writer.write_all(b"ACGT").unwrap();
+        assert_eq!(writer.finish().unwrap_err().to_string(), "disk full");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::components::bidirected_components;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// Whether the arcs of a graph can be traversed in a single walk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EulerianFeasibility {
+    /// The number of nodes whose in-degree differs from their out-degree.
+    pub unbalanced_nodes: usize,
+    /// The number of nodes whose total degree is odd, i.e. that are odd when ignoring the directions of the arcs.
+    pub odd_degree_nodes: usize,
+    /// The number of connected components that contain at least one arc,
+    /// where both strands of a component count as one.
+    pub components: usize,
+    /// True if there is a closed walk that traverses each arc exactly once.
+    pub has_circuit: bool,
+    /// True if there is a walk that traverses each arc exactly once.
+    pub has_path: bool,
+}
+
+/// Check if the arc-centric graph has an Eulerian circuit or path, based on the degrees and connectivity of the nodes.
+///
+/// The walk is a walk in the bidirected graph, i.e. it traverses each unitig exactly once, either as its arc
+/// or as its reverse complemental mirror arc. Hence a node and its reverse complement count as one node,
+/// and a single unitig has an Eulerian path. Nodes without arcs are ignored. A graph without arcs has a trivial circuit.
+pub fn check_eulerian(graph: &ArcCentricGraph) -> EulerianFeasibility {
+    let mut in_degrees = vec![0usize; graph.node_count];
+    let mut out_degrees = vec![0usize; graph.node_count];
+    for arc in &graph.arcs {
+        out_degrees[arc.from] += 1;
+        in_degrees[arc.to] += 1;
+    }
+
+    let mut unbalanced_nodes = 0;
+    let mut odd_degree_nodes = 0;
+    let mut imbalance = 0;
+    for (&in_degree, &out_degree) in in_degrees.iter().zip(&out_degrees) {
+        unbalanced_nodes += usize::from(in_degree != out_degree);
+        odd_degree_nodes += (in_degree + out_degree) % 2;
+        imbalance += in_degree.abs_diff(out_degree);
+    }
+
+    let component_indices = bidirected_components(graph);
+    let components = graph
+        .arcs
+        .iter()
+        .map(|arc| component_indices[arc.from])
+        .collect::<HashSet<_>>()
+        .len();
+
+    // Since the arcs of a node are mirrored by the arcs of its reverse complement, the imbalance of a node
+    // is mirrored with the opposite sign. A walk that does not return to its start leaves one more arc than it
+    // enters at its start, and enters one more arc than it leaves at its end, on each strand.
+    let is_connected = components <= 1;
+    EulerianFeasibility {
+        unbalanced_nodes,
+        odd_degree_nodes,
+        components,
+        has_circuit: is_connected && imbalance == 0,
+        has_path: is_connected && imbalance <= 4,
+    }
+}
+
+impl Display for EulerianFeasibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} unbalanced nodes, {} odd-degree nodes, {} connected components with arcs",
+            self.unbalanced_nodes, self.odd_degree_nodes, self.components
+        )?;
+        write!(
+            f,
+            "Eulerian circuit: {}, Eulerian path: {}",
+            if self.has_circuit { "yes" } else { "no" },
+            if self.has_path { "yes" } else { "no" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_eulerian, EulerianFeasibility};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_eulerian_circuit() {
+        // Two reverse complemental cycles that share a node.
+        let graph = read_arc_centric_graph(
+            "3\n0 1 1 1 0 AC\n1 0 1 0 1 GT\n0 2 1 2 0 AG\n2 0 1 0 2 CT\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            check_eulerian(&graph),
+            EulerianFeasibility {
+                unbalanced_nodes: 0,
+                odd_degree_nodes: 0,
+                components: 1,
+                has_circuit: true,
+                has_path: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_not_eulerian() {
+        // A path, and a separate cycle.
+        let graph = read_arc_centric_graph(
+            "5\n0 1 1 1 2 AC\n1 2 1 0 1 CG\n3 4 1 4 3 AT\n4 3 1 3 4 TA\n".as_bytes(),
+        )
+        .unwrap();
+        let feasibility = check_eulerian(&graph);
+        assert_eq!(feasibility.unbalanced_nodes, 2);
+        assert_eq!(feasibility.odd_degree_nodes, 2);
+        assert_eq!(feasibility.components, 2);
+        assert!(!feasibility.has_circuit);
+        assert!(!feasibility.has_path);
+
+        // Without the cycle, the path is an Eulerian path.
+        let graph = read_arc_centric_graph("3\n0 1 1 1 2 AC\n1 2 1 0 1 CG\n".as_bytes()).unwrap();
+        let feasibility = check_eulerian(&graph);
+        assert!(!feasibility.has_circuit);
+        assert!(feasibility.has_path);
+    }
+
+    #[test]
+    fn test_single_unitig() {
+        // The output of the unitig AACCGT with k = 3, whose strands are on separate nodes.
+        let graph =
+            read_arc_centric_graph("4\n0 2 1 3 1 AACCGT\n3 1 1 0 2 ACGGTT\n".as_bytes()).unwrap();
+        assert_eq!(
+            check_eulerian(&graph),
+            EulerianFeasibility {
+                unbalanced_nodes: 4,
+                odd_degree_nodes: 4,
+                components: 1,
+                has_circuit: false,
+                has_path: true,
+            }
+        );
+    }
+}
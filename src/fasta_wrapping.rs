@@ -0,0 +1,74 @@
+use std::io::Write;
+
+/// A writer adapter that wraps the sequence lines of a fasta file after every `width` characters.
+/// Header lines, i.e. lines starting with `>`, are not wrapped.
+pub struct FastaLineWrapper<W> {
+    inner: W,
+    width: usize,
+    buffer: Vec<u8>,
+    is_line_start: bool,
+    is_header: bool,
+    column: usize,
+}
+
+impl<W: Write> FastaLineWrapper<W> {
+    pub fn new(inner: W, width: usize) -> Self {
+        assert!(width > 0, "the line width must be positive");
+        Self {
+            inner,
+            width,
+            buffer: Vec::new(),
+            is_line_start: true,
+            is_header: false,
+            column: 0,
+        }
+    }
+}
+
+impl<W: Write> Write for FastaLineWrapper<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.clear();
+        for &byte in buf {
+            if self.is_line_start {
+                self.is_header = byte == b'>';
+                self.is_line_start = false;
+                self.column = 0;
+            }
+            if byte == b'\n' {
+                self.is_line_start = true;
+            } else if !self.is_header {
+                if self.column == self.width {
+                    self.buffer.push(b'\n');
+                    self.column = 0;
+                }
+                self.column += 1;
+            }
+            self.buffer.push(byte);
+        }
+        self.inner.write_all(&self.buffer)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FastaLineWrapper;
+    use std::io::Write;
+
+    #[test]
+    fn test_wrapping() {
+        let mut output = Vec::new();
+        let mut wrapper = FastaLineWrapper::new(&mut output, 3);
+        // Split writes continue the current line.
+        wrapper.write_all(b">0 LN:i:7 KC:i:5\nACGT").unwrap();
+        wrapper.write_all(b"ACG\n>1 LN:i:3\nTTT\n").unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:7 KC:i:5\nACG\nTAC\nG\n>1 LN:i:3\nTTT\n"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// The versions of the layout of the arc-centric output format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+pub enum FormatVersion {
+    /// Only the columns `n1 n2 weight mirror_n1 mirror_n2 sequence`, without tags and comments.
+    #[value(name = "1")]
+    #[serde(rename = "1")]
+    V1,
+    /// The current layout, with additional columns and comments from the enabled options.
+    #[default]
+    #[value(name = "2")]
+    #[serde(rename = "2")]
+    V2,
+}
+
+/// The number of columns of an arc in version 1 of the format.
+const VERSION_1_COLUMNS: usize = 6;
+
+/// Restricts the arc-centric output written through it to version 1 of the format,
+/// by dropping comment lines and all columns after the sequence.
+///
+/// Lines are buffered until they are complete, so [`Version1Writer::finish`] has to be called
+/// to write a final line without a line break.
+pub struct Version1Writer<W> {
+    inner: W,
+    line: Vec<u8>,
+}
+
+impl<W: Write> Version1Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            line: Vec::new(),
+        }
+    }
+
+    /// Write the buffered incomplete line, if any.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.write_line()?;
+        self.inner.flush()
+    }
+
+    fn write_line(&mut self) -> io::Result<()> {
+        if self.line.is_empty() || self.line.starts_with(b"#") {
+            self.line.clear();
+            return Ok(());
+        }
+        let has_line_break = self.line.ends_with(b"\n");
+        let content = if has_line_break {
+            &self.line[..self.line.len() - 1]
+        } else {
+            &self.line[..]
+        };
+        let end = content
+            .iter()
+            .enumerate()
+            .filter(|(_, character)| **character == b' ')
+            .nth(VERSION_1_COLUMNS - 1)
+            .map_or(content.len(), |(index, _)| index);
+        self.inner.write_all(&content[..end])?;
+        if has_line_break {
+            self.inner.write_all(b"\n")?;
+        }
+        self.line.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Version1Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut remaining = buf;
+        while let Some(position) = remaining.iter().position(|character| *character == b'\n') {
+            self.line.extend_from_slice(&remaining[..=position]);
+            self.write_line()?;
+            remaining = &remaining[position + 1..];
+        }
+        self.line.extend_from_slice(remaining);
+        Ok(buf.len())
+    }
+
+    /// Incomplete lines stay buffered, since their columns cannot be restricted yet.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Version1Writer;
+    use std::io::Write;
+
+    #[test]
+    fn test_version_1_writer() {
+        let mut output = Vec::new();
+        let mut writer = Version1Writer::new(&mut output);
+        writer
+            .write_all(b"3\n# node 0 out=1\n0 1 4 1 2 ACG")
+            .unwrap();
+        writer.write_all(b"T OR:A:+ OV:i:2\n1 2 3 0 1 CC").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(output, b"3\n0 1 4 1 2 ACGT\n1 2 3 0 1 CC");
+    }
+}
@@ -0,0 +1,246 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use crate::diff::ArcPair;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+/// A segment of the GFA graph together with the orientation it is traversed in.
+type OrientedSegment = (usize, bool);
+
+/// Write the graph in GFA1 format.
+///
+/// The header has the tags `VN:Z:1.0` and `PG:Z:<program> <version>`.
+/// Each arc and its reverse complement become a segment, and arcs that follow each other become links
+/// with an overlap of `k-1`.
+/// If `emit_paths` is set, then each maximal walk whose inner nodes have exactly one incoming and one outgoing arc
+/// is written as path.
+pub fn write_gfa1(
+    graph: &ArcCentricGraph,
+    k: usize,
+    emit_paths: bool,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    write_gfa(graph, k, emit_paths, &GfaDialect::STANDARD, output)
+}
+
+/// Write the graph in the GFA1 layout written by Bifrost 1.3 (`Bifrost build --output <prefix>`).
+///
+/// Like [`write_gfa1`], but the segments are numbered from one and have no `LN` tag, and the header has no `PG` tag.
+/// Bifrost stores colors in a separate binary file, so they are not written.
+pub fn write_bifrost_gfa(
+    graph: &ArcCentricGraph,
+    k: usize,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    write_gfa(graph, k, false, &GfaDialect::BIFROST, output)
+}
+
+/// Write the graph in the GFA1 layout read by SPAligner and PathRacer of SPAdes 3.15 (`spaligner -g <graph>`).
+///
+/// Like [`write_gfa1`] with paths, but the segments have a `KC` tag with their k-mer count, i.e. the weight
+/// times the number of k-mers, from which SPAdes computes the coverage, instead of an `LN` tag.
+/// The header has no `PG` tag.
+pub fn write_spaligner_gfa(
+    graph: &ArcCentricGraph,
+    k: usize,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    write_gfa(graph, k, true, &GfaDialect::SPALIGNER, output)
+}
+
+/// The differences between the GFA1 files written by different tools.
+struct GfaDialect {
+    /// The name of the first segment, the following segments are numbered consecutively.
+    first_segment_name: usize,
+    /// Whether to write an `LN` tag with the length of each segment.
+    length_tags: bool,
+    /// Whether to write a `PG` tag with the name and version of this program in the header.
+    program_tag: bool,
+    /// Whether to write a `KC` tag with the k-mer count of each segment.
+    kmer_count_tags: bool,
+}
+
+impl GfaDialect {
+    const STANDARD: Self = Self {
+        first_segment_name: 0,
+        length_tags: true,
+        program_tag: true,
+        kmer_count_tags: false,
+    };
+    const BIFROST: Self = Self {
+        first_segment_name: 1,
+        length_tags: false,
+        program_tag: false,
+        kmer_count_tags: false,
+    };
+    const SPALIGNER: Self = Self {
+        first_segment_name: 0,
+        length_tags: false,
+        program_tag: false,
+        kmer_count_tags: true,
+    };
+}
+
+fn write_gfa(
+    graph: &ArcCentricGraph,
+    k: usize,
+    emit_paths: bool,
+    dialect: &GfaDialect,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    write!(output, "H\tVN:Z:1.0")?;
+    if dialect.program_tag {
+        write!(
+            output,
+            "\tPG:Z:{} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        )?;
+    }
+    writeln!(output)?;
+    let name = |segment_id: usize| segment_id + dialect.first_segment_name;
+
+    let mut segment_ids: HashMap<ArcPair, usize> = HashMap::new();
+    let mut arc_segments = Vec::with_capacity(graph.arcs.len());
+    for arc in &graph.arcs {
+        let pair = ArcPair::of(arc);
+        let forwards = is_forwards(arc, &pair);
+        let segment_id = match segment_ids.get(&pair) {
+            Some(&segment_id) => segment_id,
+            None => {
+                let segment_id = segment_ids.len();
+                write!(output, "S\t{}\t{}", name(segment_id), pair.sequence)?;
+                if dialect.length_tags {
+                    write!(output, "\tLN:i:{}", pair.sequence.len())?;
+                }
+                if dialect.kmer_count_tags {
+                    let kmer_count = pair.sequence.len() - (k - 1);
+                    write!(output, "\tKC:i:{}", arc.weight * kmer_count)?;
+                }
+                writeln!(output)?;
+                segment_ids.insert(pair, segment_id);
+                segment_id
+            }
+        };
+        arc_segments.push((segment_id, forwards));
+    }
+
+    let mut in_arcs = vec![Vec::new(); graph.node_count];
+    let mut out_arcs = vec![Vec::new(); graph.node_count];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        in_arcs[arc.to].push(index);
+        out_arcs[arc.from].push(index);
+    }
+
+    // Each link is found once for each of its two directions, so only write the smaller one.
+    let overlap = k - 1;
+    let mut written_links = HashSet::new();
+    for node in 0..graph.node_count {
+        for &in_arc in &in_arcs[node] {
+            for &out_arc in &out_arcs[node] {
+                let link = [arc_segments[in_arc], arc_segments[out_arc]];
+                let link = link.min(mirror_walk(&link).try_into().unwrap());
+                if written_links.insert(link) {
+                    let [(from, from_forwards), (to, to_forwards)] = link;
+                    writeln!(
+                        output,
+                        "L\t{}\t{}\t{}\t{}\t{overlap}M",
+                        name(from),
+                        orientation(from_forwards),
+                        name(to),
+                        orientation(to_forwards)
+                    )?;
+                }
+            }
+        }
+    }
+
+    if emit_paths {
+        let mut written_paths = HashSet::new();
+        for chain in simple_chains(graph, &in_arcs, &out_arcs) {
+            let path: Vec<_> = chain.iter().map(|&arc| arc_segments[arc]).collect();
+            let path = path.clone().min(mirror_walk(&path));
+            if !written_paths.insert(path.clone()) {
+                continue;
+            }
+
+            let segments: Vec<_> = path
+                .iter()
+                .map(|&(segment, forwards)| format!("{}{}", name(segment), orientation(forwards)))
+                .collect();
+            let overlaps = if path.len() > 1 {
+                vec![format!("{overlap}M"); path.len() - 1].join(",")
+            } else {
+                "*".to_owned()
+            };
+            writeln!(
+                output,
+                "P\tpath{}\t{}\t{overlaps}",
+                written_paths.len() - 1,
+                segments.join(",")
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the maximal walks whose inner nodes have exactly one incoming and one outgoing arc.
+/// Walks are given as sequences of arc indices.
+/// Cycles of such nodes are broken up at an arbitrary arc.
+fn simple_chains(
+    graph: &ArcCentricGraph,
+    in_arcs: &[Vec<usize>],
+    out_arcs: &[Vec<usize>],
+) -> Vec<Vec<usize>> {
+    let is_inner = |node: usize| in_arcs[node].len() == 1 && out_arcs[node].len() == 1;
+    let mut visited = vec![false; graph.arcs.len()];
+    let mut chains = Vec::new();
+
+    // First visit the chains that start at a branching node, then the remaining cycles.
+    let starts: Vec<_> = (0..graph.arcs.len())
+        .filter(|&arc| !is_inner(graph.arcs[arc].from))
+        .chain(0..graph.arcs.len())
+        .collect();
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+
+        let mut chain = vec![start];
+        visited[start] = true;
+        let mut node = graph.arcs[start].to;
+        while is_inner(node) {
+            let next = out_arcs[node][0];
+            if visited[next] {
+                break;
+            }
+            chain.push(next);
+            visited[next] = true;
+            node = graph.arcs[next].to;
+        }
+        chains.push(chain);
+    }
+
+    chains
+}
+
+/// The walk that spells the reverse complement of the given walk.
+fn mirror_walk(walk: &[OrientedSegment]) -> Vec<OrientedSegment> {
+    walk.iter()
+        .rev()
+        .map(|&(segment, forwards)| (segment, !forwards))
+        .collect()
+}
+
+/// Returns true if the arc spells the segment sequence of its pair, as opposed to its reverse complement.
+fn is_forwards(arc: &ArcRecord, pair: &ArcPair) -> bool {
+    (arc.from, arc.to) == (pair.from, pair.to) && arc.sequence.eq_ignore_ascii_case(&pair.sequence)
+}
+
+fn orientation(forwards: bool) -> char {
+    if forwards {
+        '+'
+    } else {
+        '-'
+    }
+}
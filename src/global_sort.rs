@@ -0,0 +1,274 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::id_encoding::IdEncoding;
+
+/// Sort the arc lines of the given arc-centric output by their first node, second node and sequence.
+///
+/// Nodes are decoded with the given encoding and compared numerically, or as strings if they are not numbers,
+/// as with `--preserve-original-ids`. The header stays the first line.
+pub fn sort_arc_lines(output: &str, id_encoding: IdEncoding) -> String {
+    let (header, arcs) = output.split_once('\n').unwrap_or((output, ""));
+    let mut arcs: Vec<_> = arcs
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    arcs.sort_by_cached_key(|line| sort_key(line, id_encoding));
+
+    let mut sorted = String::with_capacity(output.len());
+    sorted.push_str(header);
+    sorted.push('\n');
+    for arc in arcs {
+        sorted.push_str(arc);
+        sorted.push('\n');
+    }
+    sorted
+}
+
+/// A node compared numerically if possible, and as string otherwise.
+type NodeKey<'line> = (Option<usize>, &'line str);
+
+/// The key by which [`sort_arc_lines`] sorts an arc line.
+fn sort_key(line: &str, id_encoding: IdEncoding) -> (NodeKey<'_>, NodeKey<'_>, &str) {
+    let columns: Vec<_> = line.split_whitespace().collect();
+    let node = |column: usize| {
+        let node = columns.get(column).copied().unwrap_or_default();
+        (id_encoding.decode(node), node)
+    };
+    (
+        node(0),
+        node(1),
+        columns.get(5).copied().unwrap_or_default(),
+    )
+}
+
+/// Sorts arc-centric output written into it like [`sort_arc_lines`], but with an external merge sort.
+///
+/// The arc lines are buffered until they exceed the memory budget, and are then sorted and spilled
+/// into a temporary file in the given directory. [`ExternalSorter::finish`] then merges these sorted runs.
+/// The temporary files are removed when the sorter is dropped.
+pub struct ExternalSorter {
+    directory: PathBuf,
+    memory_budget: usize,
+    id_encoding: IdEncoding,
+    header: Option<String>,
+    /// The bytes after the last complete line.
+    pending: Vec<u8>,
+    lines: Vec<String>,
+    line_bytes: usize,
+    runs: Vec<PathBuf>,
+}
+
+impl ExternalSorter {
+    /// Create a sorter that buffers up to `memory_budget` bytes of arc lines before spilling them into `directory`.
+    /// Nodes are decoded with `id_encoding`.
+    pub fn new(directory: &Path, memory_budget: usize, id_encoding: IdEncoding) -> Self {
+        Self {
+            directory: directory.to_owned(),
+            memory_budget,
+            id_encoding,
+            header: None,
+            pending: Vec::new(),
+            lines: Vec::new(),
+            line_bytes: 0,
+            runs: Vec::new(),
+        }
+    }
+
+    /// The number of sorted runs spilled so far.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Write the header followed by all arc lines in sorted order.
+    pub fn finish(mut self, output: &mut impl Write) -> io::Result<()> {
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            self.push_line(&String::from_utf8(pending).map_err(invalid_data)?)?;
+        }
+        if let Some(header) = &self.header {
+            writeln!(output, "{header}")?;
+        }
+
+        if self.runs.is_empty() {
+            for line in sorted(&self.lines, self.id_encoding) {
+                writeln!(output, "{line}")?;
+            }
+            return Ok(());
+        }
+
+        if !self.lines.is_empty() {
+            self.spill()?;
+        }
+        let mut runs = Vec::with_capacity(self.runs.len());
+        for path in &self.runs {
+            runs.push(BufReader::new(File::open(path)?).lines());
+        }
+        let mut heap = BinaryHeap::with_capacity(runs.len());
+        for (run, lines) in runs.iter_mut().enumerate() {
+            if let Some(line) = lines.next() {
+                heap.push(Reverse(MergeEntry {
+                    line: line?,
+                    run,
+                    id_encoding: self.id_encoding,
+                }));
+            }
+        }
+        while let Some(Reverse(MergeEntry { line, run, .. })) = heap.pop() {
+            writeln!(output, "{line}")?;
+            if let Some(line) = runs[run].next() {
+                heap.push(Reverse(MergeEntry {
+                    line: line?,
+                    run,
+                    id_encoding: self.id_encoding,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    fn push_line(&mut self, line: &str) -> io::Result<()> {
+        if self.header.is_none() {
+            self.header = Some(line.to_owned());
+        } else if !line.trim().is_empty() {
+            self.line_bytes += line.len();
+            self.lines.push(line.to_owned());
+            if self.line_bytes > self.memory_budget {
+                self.spill()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        let path = self.directory.join(format!(
+            "node-to-arc-centric-dbg-sort-{}-{}.txt",
+            std::process::id(),
+            self.runs.len()
+        ));
+        self.runs.push(path.clone());
+        let mut run = BufWriter::new(File::create(path)?);
+        for line in sorted(&self.lines, self.id_encoding) {
+            writeln!(run, "{line}")?;
+        }
+        run.flush()?;
+        self.lines.clear();
+        self.line_bytes = 0;
+        Ok(())
+    }
+}
+
+impl Write for ExternalSorter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            let complete: Vec<_> = self.pending.drain(..=end).collect();
+            for line in String::from_utf8(complete).map_err(invalid_data)?.lines() {
+                self.push_line(line)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for ExternalSorter {
+    fn drop(&mut self) {
+        // The runs are temporary, so failing to remove them is not worth an error.
+        for path in &self.runs {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// The lines in the order of [`sort_arc_lines`].
+fn sorted(lines: &[String], id_encoding: IdEncoding) -> Vec<&str> {
+    let mut keyed: Vec<_> = lines
+        .iter()
+        .map(|line| (sort_key(line, id_encoding), line))
+        .collect();
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, line)| line.as_str()).collect()
+}
+
+fn invalid_data(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// The next line of a sorted run, ordered by its sort key and then by its run to keep the merge stable.
+#[derive(PartialEq, Eq)]
+struct MergeEntry {
+    line: String,
+    run: usize,
+    id_encoding: IdEncoding,
+}
+
+impl Ord for MergeEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sort_key(&self.line, self.id_encoding)
+            .cmp(&sort_key(&other.line, other.id_encoding))
+            .then(self.run.cmp(&other.run))
+    }
+}
+
+impl PartialOrd for MergeEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_arc_lines, ExternalSorter};
+    use crate::id_encoding::IdEncoding;
+    use std::io::Write;
+
+    #[test]
+    fn test_sort_arc_lines() {
+        assert_eq!(
+            sort_arc_lines(
+                "11\n10 2 1 2 10 AC\n2 10 1 10 2 GT\n2 3 1 3 2 TT\n2 3 1 3 2 AA\n",
+                IdEncoding::Decimal
+            ),
+            "11\n2 3 1 3 2 AA\n2 3 1 3 2 TT\n2 10 1 10 2 GT\n10 2 1 2 10 AC\n"
+        );
+        assert_eq!(
+            sort_arc_lines(
+                "11\na 2 1 2 a AC\n2 a 1 a 2 GT\n9 3 1 3 9 TT\n",
+                IdEncoding::Hex
+            ),
+            "11\n2 a 1 a 2 GT\n9 3 1 3 9 TT\na 2 1 2 a AC\n"
+        );
+    }
+
+    #[test]
+    fn test_external_sort() {
+        let input: String = std::iter::once("100\n".to_owned())
+            .chain((0..100).map(|index| {
+                let from = (index * 37) % 100;
+                format!("{from} {} 1 0 0 ACGT\n", 99 - index)
+            }))
+            .collect();
+        let directory = tempfile::tempdir().unwrap();
+        let mut sorter = ExternalSorter::new(directory.path(), 64, IdEncoding::Decimal);
+        // Write in chunks that split lines, as a buffered writer would.
+        for chunk in input.as_bytes().chunks(7) {
+            sorter.write_all(chunk).unwrap();
+        }
+        assert!(sorter.run_count() > 1);
+
+        let mut output = Vec::new();
+        sorter.finish(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            sort_arc_lines(&input, IdEncoding::Decimal)
+        );
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 0);
+    }
+}
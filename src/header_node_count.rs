@@ -0,0 +1,84 @@
+use anyhow::{bail, Context};
+use clap::ValueEnum;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// How the node count in the header of the arc-centric output is determined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HeaderNodeCount {
+    /// The number of nodes of the graph, including nodes that are not referenced by any written arc.
+    #[default]
+    Graph,
+    /// The number of distinct nodes referenced by the written arcs, including their mirror columns.
+    /// The nodes are not renumbered, so the node indices may not be below this count.
+    Referenced,
+    /// One more than the largest node index referenced by the written arcs.
+    MaxIndexPlusOne,
+}
+
+/// Replace the node count in the header of the given arc-centric output with the count determined by `mode`.
+pub fn rewrite_header_node_count(output: &str, mode: HeaderNodeCount) -> anyhow::Result<String> {
+    let Some((header, arcs)) = output.split_once('\n') else {
+        bail!("missing node count header");
+    };
+    if mode == HeaderNodeCount::Graph {
+        return Ok(output.to_owned());
+    }
+
+    let mut nodes = HashSet::new();
+    for line in arcs.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let columns: Vec<_> = line.split_whitespace().collect();
+        for column in [0, 1, 3, 4] {
+            let node = columns
+                .get(column)
+                .with_context(|| format!("missing column in {line:?}"))?;
+            let node: usize = node
+                .parse()
+                .with_context(|| format!("node {node:?} is not a number"))?;
+            nodes.insert(node);
+        }
+    }
+
+    let node_count = match mode {
+        HeaderNodeCount::Graph => unreachable!(),
+        HeaderNodeCount::Referenced => nodes.len(),
+        HeaderNodeCount::MaxIndexPlusOne => nodes.iter().max().map_or(0, |node| node + 1),
+    };
+    let header_node_count: usize = header
+        .trim()
+        .parse()
+        .with_context(|| format!("malformed node count header {header:?}"))?;
+    if node_count != header_node_count {
+        info!("Replacing the node count {header_node_count} in the header by {node_count}");
+    }
+    Ok(format!("{node_count}\n{arcs}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite_header_node_count, HeaderNodeCount};
+
+    #[test]
+    fn test_header_node_count() {
+        // Nodes 0, 1, 3, 4, 6 and 7 have no arcs, e.g. because their arcs were filtered.
+        let output = "8\n2 5 1 5 2 ACGT\n# node 5 out=1\n5 2 1 2 5 ACGT\n";
+        let header = |mode| {
+            rewrite_header_node_count(output, mode)
+                .unwrap()
+                .split_once('\n')
+                .map(|(header, arcs)| {
+                    assert_eq!(arcs, &output[2..]);
+                    header.to_owned()
+                })
+                .unwrap()
+        };
+        assert_eq!(header(HeaderNodeCount::Graph), "8");
+        assert_eq!(header(HeaderNodeCount::Referenced), "2");
+        assert_eq!(header(HeaderNodeCount::MaxIndexPlusOne), "6");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::node_names::NodeName;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// How node indices are written in the arc-centric output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdEncoding {
+    /// Decimal numbers.
+    #[default]
+    Decimal,
+    /// Base 36 numbers with the digits `0-9a-z`.
+    Base36,
+    /// Hexadecimal numbers with the digits `0-9a-f`.
+    Hex,
+}
+
+impl IdEncoding {
+    fn radix(self) -> usize {
+        match self {
+            Self::Decimal => 10,
+            Self::Base36 => 36,
+            Self::Hex => 16,
+        }
+    }
+
+    /// Display the node name in this encoding. Labels are displayed as they are.
+    pub fn encode_name(self, name: NodeName<'_>) -> EncodedName<'_> {
+        EncodedName {
+            name,
+            encoding: self,
+        }
+    }
+
+    /// Parse a node index in this encoding. Upper case digits are accepted as well.
+    pub fn decode(self, id: &str) -> Option<usize> {
+        usize::from_str_radix(id, self.radix() as u32).ok()
+    }
+}
+
+/// A node name displayed in an [`IdEncoding`], as returned by [`IdEncoding::encode_name`].
+pub struct EncodedName<'a> {
+    name: NodeName<'a>,
+    encoding: IdEncoding,
+}
+
+impl Display for EncodedName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let NodeName::Index(mut index) = self.name else {
+            return write!(f, "{}", self.name);
+        };
+        match self.encoding {
+            IdEncoding::Decimal => write!(f, "{index}"),
+            IdEncoding::Hex => write!(f, "{index:x}"),
+            IdEncoding::Base36 => {
+                const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+                // The largest usize has 13 digits in base 36.
+                let mut buffer = [0; 13];
+                let mut start = buffer.len();
+                loop {
+                    start -= 1;
+                    buffer[start] = DIGITS[index % 36];
+                    index /= 36;
+                    if index == 0 {
+                        break;
+                    }
+                }
+                // The digits are ASCII, so the buffer is valid UTF-8.
+                f.write_str(std::str::from_utf8(&buffer[start..]).unwrap())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdEncoding;
+    use crate::node_names::NodeName;
+
+    #[test]
+    fn test_round_trip() {
+        for encoding in [IdEncoding::Decimal, IdEncoding::Base36, IdEncoding::Hex] {
+            for index in [0, 9, 10, 35, 36, 255, 1_000_000_007, usize::MAX] {
+                let encoded = encoding.encode_name(NodeName::Index(index)).to_string();
+                assert_eq!(encoding.decode(&encoded), Some(index));
+            }
+        }
+        let encode =
+            |encoding: IdEncoding, index| encoding.encode_name(NodeName::Index(index)).to_string();
+        assert_eq!(encode(IdEncoding::Base36, 1295), "zz");
+        assert_eq!(encode(IdEncoding::Hex, 255), "ff");
+        assert_eq!(
+            IdEncoding::Base36
+                .encode_name(NodeName::Label("0+s"))
+                .to_string(),
+            "0+s"
+        );
+    }
+}
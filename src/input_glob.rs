@@ -0,0 +1,98 @@
+use anyhow::{bail, Context};
+use std::path::{Path, PathBuf};
+
+/// The files matching a pattern whose file name may contain the wildcards `*` for any sequence of characters
+/// and `?` for a single character. The directory part of the pattern must not contain wildcards.
+/// The matches are sorted by path.
+pub fn glob_paths(pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let pattern = Path::new(pattern);
+    let Some(name_pattern) = pattern.file_name().and_then(|name| name.to_str()) else {
+        bail!("The input pattern {pattern:?} has no file name");
+    };
+    let directory = match pattern.parent() {
+        Some(directory) if directory != Path::new("") => directory,
+        _ => Path::new("."),
+    };
+    if directory.to_string_lossy().contains(['*', '?']) {
+        bail!("Wildcards are only supported in the file name of the input pattern {pattern:?}");
+    }
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(directory)
+        .with_context(|| format!("Could not read the directory {directory:?}"))?
+    {
+        let entry = entry?;
+        let is_match = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| matches_wildcards(name_pattern, name));
+        if is_match && entry.file_type()?.is_file() {
+            paths.push(directory.join(entry.file_name()));
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Returns true if the name matches the pattern with the wildcards `*` and `?`.
+fn matches_wildcards(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<_> = pattern.chars().collect();
+    let name: Vec<_> = name.chars().collect();
+    // The position after the last `*` and the position in the name it currently matches up to,
+    // to backtrack to if the rest does not match.
+    let mut backtrack = None;
+    let (mut pattern_index, mut name_index) = (0, 0);
+    while name_index < name.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                pattern_index += 1;
+                backtrack = Some((pattern_index, name_index));
+            }
+            Some(&character) if character == '?' || character == name[name_index] => {
+                pattern_index += 1;
+                name_index += 1;
+            }
+            _ => {
+                let Some((star_pattern_index, star_name_index)) = backtrack else {
+                    return false;
+                };
+                pattern_index = star_pattern_index;
+                name_index = star_name_index + 1;
+                backtrack = Some((star_pattern_index, name_index));
+            }
+        }
+    }
+    pattern[pattern_index..]
+        .iter()
+        .all(|&character| character == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_paths, matches_wildcards};
+
+    #[test]
+    fn test_matches_wildcards() {
+        assert!(matches_wildcards("*.bcalm2", "sample.bcalm2"));
+        assert!(matches_wildcards("*.bcalm2", ".bcalm2"));
+        assert!(!matches_wildcards("*.bcalm2", "sample.bcalm2.gz"));
+        assert!(matches_wildcards("s?mple*", "sample.fa"));
+        assert!(!matches_wildcards("s?mple*", "smple.fa"));
+        assert!(matches_wildcards("*a*b*", "xaybzb"));
+        assert!(!matches_wildcards("*a*b*", "xbya"));
+        assert!(matches_wildcards("exact", "exact"));
+    }
+
+    #[test]
+    fn test_glob_paths() {
+        let directory = tempfile::tempdir().unwrap();
+        for name in ["b.fa", "a.fa", "c.txt"] {
+            std::fs::write(directory.path().join(name), "").unwrap();
+        }
+        let pattern = directory.path().join("*.fa");
+        assert_eq!(
+            glob_paths(pattern.to_str().unwrap()).unwrap(),
+            vec![directory.path().join("a.fa"), directory.path().join("b.fa")]
+        );
+    }
+}
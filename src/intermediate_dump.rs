@@ -0,0 +1,43 @@
+use crate::arc_centric_reader::reverse_complement;
+use crate::Graph;
+use genome_graph::bigraph::traitgraph::index::GraphIndex;
+use genome_graph::bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer};
+use genome_graph::compact_genome::implementation::DefaultSequenceStore;
+use genome_graph::compact_genome::interface::alphabet::dna_alphabet::DnaAlphabet;
+use genome_graph::compact_genome::interface::sequence::GenomeSequence;
+use genome_graph::compact_genome::interface::sequence_store::SequenceStore;
+use std::io::Write;
+
+/// Write the edges of the graph as read from the input, before any arcs are merged or skipped.
+///
+/// The first line is `<nodes> <edges>`, followed by one line `<from> <to> <unitig> <orientation> <abundance> <sequence>`
+/// per edge in the order of the edge indices, where the orientation is `+` for forwards edges and `-` otherwise,
+/// the abundance is the total abundance of the unitig, and the sequence is the sequence of the edge,
+/// i.e. the reverse complement of the unitig for reverse edges.
+pub fn write_intermediate_graph(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    writeln!(output, "{} {}", graph.node_count(), graph.edge_count())?;
+    for edge_id in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+        let edge_data = graph.edge_data(edge_id);
+        let sequence = sequence_store.get(&edge_data.sequence_handle).as_string();
+        let sequence = if edge_data.forwards {
+            sequence
+        } else {
+            reverse_complement(&sequence)
+        };
+        writeln!(
+            output,
+            "{} {} {} {} {} {sequence}",
+            from_node.as_usize(),
+            to_node.as_usize(),
+            edge_data.id,
+            if edge_data.forwards { '+' } else { '-' },
+            edge_data.total_abundance,
+        )?;
+    }
+    Ok(())
+}
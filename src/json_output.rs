@@ -0,0 +1,68 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::manifest::json_string;
+use std::io::Write;
+
+/// Write the graph as a single JSON object `{"node_count": <nodes>, "arcs": [<arcs>]}`,
+/// where each arc is an object with the columns of the arc-centric format and its tags, on its own line.
+/// Colors are not written.
+///
+/// The arcs are written one by one, so the output is never buffered as a whole.
+pub fn write_json(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    write!(
+        output,
+        "{{\"node_count\": {}, \"arcs\": [",
+        graph.node_count
+    )?;
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        // The separator precedes every arc but the first, such that no arc is followed by a trailing comma.
+        let separator = if index == 0 { "" } else { "," };
+        let tags: Vec<_> = arc.tags.iter().map(|tag| json_string(tag)).collect();
+        write!(
+            output,
+            "{separator}\n{{\"from\": {}, \"to\": {}, \"weight\": {}, \"mirror_from\": {}, \"mirror_to\": {}, \"sequence\": {}, \"tags\": [{}]}}",
+            arc.from,
+            arc.to,
+            arc.weight,
+            arc.mirror_from,
+            arc.mirror_to,
+            json_string(&arc.sequence),
+            tags.join(", "),
+        )?;
+    }
+    if !graph.arcs.is_empty() {
+        writeln!(output)?;
+    }
+    writeln!(output, "]}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_json;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    fn convert(input: &str) -> String {
+        let graph = read_arc_centric_graph(input.as_bytes()).unwrap();
+        let mut output = Vec::new();
+        write_json(&graph, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(convert("3\n"), "{\"node_count\": 3, \"arcs\": []}\n");
+        assert_eq!(
+            convert("2\n0 1 4 0 1 ACGT\n"),
+            "{\"node_count\": 2, \"arcs\": [\n\
+            {\"from\": 0, \"to\": 1, \"weight\": 4, \"mirror_from\": 0, \"mirror_to\": 1, \"sequence\": \"ACGT\", \"tags\": []}\n\
+            ]}\n"
+        );
+        assert_eq!(
+            convert("4\n0 1 2 2 3 AAC\n2 3 2 0 1 GTT FL:Z:a\n"),
+            "{\"node_count\": 4, \"arcs\": [\n\
+            {\"from\": 0, \"to\": 1, \"weight\": 2, \"mirror_from\": 2, \"mirror_to\": 3, \"sequence\": \"AAC\", \"tags\": []},\n\
+            {\"from\": 2, \"to\": 3, \"weight\": 2, \"mirror_from\": 0, \"mirror_to\": 1, \"sequence\": \"GTT\", \"tags\": [\"FL:Z:a\"]}\n\
+            ]}\n"
+        );
+    }
+}
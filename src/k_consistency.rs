@@ -0,0 +1,96 @@
+use crate::arc_centric_reader::reverse_complement;
+use crate::bcalm2_header::Bcalm2Header;
+use crate::link_verification::LinkMismatch;
+use crate::record_transformer::FastaRecord;
+use std::collections::HashMap;
+
+/// The links and the ends of the sequence of a bcalm2 record, which determine the overlaps its links imply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordEnds {
+    pub header: Bcalm2Header,
+    /// The length of the sequence.
+    length: usize,
+    /// The first k-1 characters of the sequence, or the whole sequence if it is shorter.
+    prefix: String,
+    /// The last k-1 characters of the sequence, or the whole sequence if it is shorter.
+    suffix: String,
+}
+
+impl RecordEnds {
+    /// The ends of the given record, or `None` if its header cannot be parsed.
+    /// Malformed records are rejected by the graph reader, so there is no need to report details here.
+    pub fn new(record: &FastaRecord, k: usize) -> Option<Self> {
+        let header = Bcalm2Header::parse(&record.header)?;
+        let sequence = String::from_utf8_lossy(&record.sequence).to_ascii_uppercase();
+        let overlap = (k - 1).min(sequence.len());
+        Some(Self {
+            header,
+            length: sequence.len(),
+            prefix: sequence[..overlap].to_owned(),
+            suffix: sequence[sequence.len() - overlap..].to_owned(),
+        })
+    }
+
+    /// The first k-1 characters of the record in the given orientation.
+    fn start(&self, forwards: bool) -> String {
+        if forwards {
+            self.prefix.clone()
+        } else {
+            reverse_complement(&self.suffix)
+        }
+    }
+
+    /// The last k-1 characters of the record in the given orientation.
+    fn end(&self, forwards: bool) -> String {
+        if forwards {
+            self.suffix.clone()
+        } else {
+            reverse_complement(&self.prefix)
+        }
+    }
+}
+
+/// Check that each link `L:<s1>:<id>:<s2>` of the records implies an overlap of `k-1` characters,
+/// i.e. that the last `k-1` characters of the record in orientation `s1` are
+/// the first `k-1` characters of record `id` in orientation `s2`.
+///
+/// Links to records that do not exist are ignored, since `--verify-links` reports them.
+pub fn find_overlap_mismatches(records: &[RecordEnds], k: usize) -> Vec<LinkMismatch> {
+    let records_by_id: HashMap<_, _> = records
+        .iter()
+        .map(|record| (record.header.id, record))
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for record in records {
+        if record.length < k - 1 {
+            mismatches.push(LinkMismatch {
+                record_id: record.header.id,
+                description: format!(
+                    "the sequence of length {} is shorter than the overlap k-1 = {}",
+                    record.length,
+                    k - 1
+                ),
+            });
+            continue;
+        }
+        for link in &record.header.links {
+            let Some(target) = records_by_id.get(&link.to_id) else {
+                continue;
+            };
+            let end = record.end(link.from_forwards);
+            let start = target.start(link.to_forwards);
+            if target.length >= k - 1 && end != start {
+                mismatches.push(LinkMismatch {
+                    record_id: record.header.id,
+                    description: format!(
+                        "{link} to record >{} does not overlap by k-1 = {} characters: the source ends with {end} but the target starts with {start}",
+                        link.to_id,
+                        k - 1
+                    ),
+                });
+            }
+        }
+    }
+    mismatches
+}
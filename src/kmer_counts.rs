@@ -0,0 +1,52 @@
+use crate::arc_centric_reader::reverse_complement;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The multiplicity of each canonical k-mer of a graph, where the canonical k-mer is the lexicographically smaller
+/// of a k-mer and its reverse complement.
+/// The multiplicity of the k-mers of a unitig is its mean abundance, rounded down like the weights,
+/// and the multiplicities of a k-mer in several unitigs are summed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KmerCounts {
+    pub multiplicities: BTreeMap<String, usize>,
+}
+
+impl KmerCounts {
+    pub fn record_unitig(&mut self, sequence: &str, total_abundance: usize, k: usize) {
+        let kmer_count = sequence.len() - (k - 1);
+        let multiplicity = total_abundance / kmer_count;
+        for offset in 0..kmer_count {
+            let kmer = &sequence[offset..offset + k];
+            let reverse_kmer = reverse_complement(kmer);
+            let canonical_kmer = if reverse_kmer.as_str() < kmer {
+                reverse_kmer
+            } else {
+                kmer.to_owned()
+            };
+            *self.multiplicities.entry(canonical_kmer).or_default() += multiplicity;
+        }
+    }
+}
+
+/// Writes lines `<k-mer> <multiplicity>` in lexicographic order of the k-mers.
+impl Display for KmerCounts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (kmer, multiplicity) in &self.multiplicities {
+            writeln!(f, "{kmer} {multiplicity}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KmerCounts;
+
+    #[test]
+    fn test_record_unitig() {
+        let mut counts = KmerCounts::default();
+        counts.record_unitig("ACGTT", 7, 4);
+        counts.record_unitig("AACG", 3, 4);
+        assert_eq!(counts.to_string(), "AACG 6\nACGT 3\n");
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The k-mer spectrum of a graph, i.e. the number of k-mers with each multiplicity.
+/// The multiplicity of the k-mers of a unitig is its mean abundance, rounded down like the weights.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KmerSpectrum {
+    pub kmer_counts: BTreeMap<usize, usize>,
+}
+
+impl KmerSpectrum {
+    pub fn record_unitig(&mut self, total_abundance: usize, kmer_count: usize) {
+        *self
+            .kmer_counts
+            .entry(total_abundance / kmer_count)
+            .or_default() += kmer_count;
+    }
+
+    pub fn kmer_count(&self) -> usize {
+        self.kmer_counts.values().sum()
+    }
+}
+
+/// Writes lines `<multiplicity> <k-mer count>` in order of ascending multiplicity.
+impl Display for KmerSpectrum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (multiplicity, kmer_count) in &self.kmer_counts {
+            writeln!(f, "{multiplicity} {kmer_count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KmerSpectrum;
+
+    #[test]
+    fn test_record_unitig() {
+        let mut spectrum = KmerSpectrum::default();
+        spectrum.record_unitig(12, 4);
+        spectrum.record_unitig(7, 2);
+        spectrum.record_unitig(1, 1);
+        assert_eq!(spectrum.kmer_count(), 7);
+        assert_eq!(spectrum.to_string(), "1 1\n3 6\n");
+    }
+}
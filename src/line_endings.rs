@@ -0,0 +1,103 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Read};
+
+/// The line endings of the input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputEncoding {
+    /// Lines end with `\n`.
+    Lf,
+    /// Lines end with `\r\n`, as in files written on Windows.
+    Crlf,
+    /// Use `crlf` if the first line ends with `\r\n`, and `lf` otherwise.
+    #[default]
+    Auto,
+}
+
+impl InputEncoding {
+    /// Resolve [`InputEncoding::Auto`] by inspecting the buffered beginning of the input.
+    pub fn detect(self, input: &mut impl BufRead) -> std::io::Result<Self> {
+        if self != Self::Auto {
+            return Ok(self);
+        }
+        let buffer = input.fill_buf()?;
+        Ok(match buffer.iter().position(|&byte| byte == b'\n') {
+            Some(position) if position > 0 && buffer[position - 1] == b'\r' => Self::Crlf,
+            _ => Self::Lf,
+        })
+    }
+}
+
+/// A [`BufRead`] adapter that converts `\r\n` line endings to `\n`.
+/// Carriage returns that are not followed by a line feed are kept.
+pub struct CrlfNormalizer<R> {
+    inner: R,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: BufRead> CrlfNormalizer<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Read for CrlfNormalizer<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead> BufRead for CrlfNormalizer<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+            self.inner.read_until(b'\n', &mut self.buffer)?;
+            if self.buffer.ends_with(b"\r\n") {
+                self.buffer.remove(self.buffer.len() - 2);
+            }
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.buffer.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CrlfNormalizer, InputEncoding};
+    use std::io::Read;
+
+    #[test]
+    fn test_crlf_normalizer() {
+        let mut output = String::new();
+        CrlfNormalizer::new(">0 LN:i:4\r\nAC\rGT\r\n>1\r\nACGT".as_bytes())
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, ">0 LN:i:4\nAC\rGT\n>1\nACGT");
+    }
+
+    #[test]
+    fn test_detect() {
+        let detect = |input: &str| InputEncoding::Auto.detect(&mut input.as_bytes()).unwrap();
+        assert_eq!(detect(">0\r\nACGT\r\n"), InputEncoding::Crlf);
+        assert_eq!(detect(">0\nACGT\n"), InputEncoding::Lf);
+        assert_eq!(detect(""), InputEncoding::Lf);
+        assert_eq!(
+            InputEncoding::Lf.detect(&mut ">0\r\n".as_bytes()).unwrap(),
+            InputEncoding::Lf
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use crate::mirror_index::find_mirror_arcs;
+use anyhow::bail;
+
+/// The default maximum number of arcs of the line graph.
+pub const DEFAULT_MAX_ARCS: usize = 10_000_000;
+
+/// Construct the line graph of the arc-centric graph, whose nodes are the arcs of the graph,
+/// with an arc from `a` to `b` for each pair of arcs where `a` ends in the node where `b` starts.
+///
+/// The nodes are numbered by the indices of the arcs. The sequence of an arc of the line graph is the
+/// `(k+1)`-mer made of the last k-mer of `a` and the first k-mer of `b`, and its weight is the smaller
+/// of their weights. Its mirror arc goes from the mirror of `b` to the mirror of `a`.
+///
+/// Since the line graph has an arc for each pair of an incoming and an outgoing arc of each node,
+/// it can be much larger than the graph. Fails if it would have more than `max_arcs` arcs.
+pub fn line_graph(
+    graph: &ArcCentricGraph,
+    k: usize,
+    max_arcs: usize,
+) -> anyhow::Result<ArcCentricGraph> {
+    let mut incoming = vec![Vec::new(); graph.node_count];
+    let mut outgoing = vec![Vec::new(); graph.node_count];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        outgoing[arc.from].push(index);
+        incoming[arc.to].push(index);
+    }
+    let arc_count: usize = incoming
+        .iter()
+        .zip(&outgoing)
+        .map(|(incoming, outgoing)| incoming.len() * outgoing.len())
+        .sum();
+    if arc_count > max_arcs {
+        bail!("The line graph has {arc_count} arcs, which is more than the {max_arcs} allowed");
+    }
+    let mirrors = find_mirror_arcs(graph)?;
+
+    let mut arcs = Vec::with_capacity(arc_count);
+    for (incoming, outgoing) in incoming.iter().zip(&outgoing) {
+        for &first in incoming {
+            for &second in outgoing {
+                let first_arc = &graph.arcs[first];
+                let second_arc = &graph.arcs[second];
+                let mut sequence = first_arc.sequence[first_arc.sequence.len() - k..].to_owned();
+                sequence.push_str(&second_arc.sequence[k - 1..k]);
+                arcs.push(ArcRecord {
+                    from: first,
+                    to: second,
+                    weight: first_arc.weight.min(second_arc.weight),
+                    mirror_from: mirrors[second],
+                    mirror_to: mirrors[first],
+                    sequence,
+                    colors: Vec::new(),
+                    tags: Vec::new(),
+                });
+            }
+        }
+    }
+    Ok(ArcCentricGraph {
+        node_count: graph.arcs.len(),
+        arcs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::line_graph;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::output_validation::find_arcs_without_mirror;
+
+    #[test]
+    fn test_line_graph() {
+        // Two consecutive unitigs AACTGA with k = 3 and their reverse complements.
+        let graph = read_arc_centric_graph(
+            "6\n0 1 4 4 5 AACT\n1 2 2 3 4 CTGA\n3 4 2 1 2 TCAG\n4 5 4 0 1 AGTT\n".as_bytes(),
+        )
+        .unwrap();
+        let line_graph = line_graph(&graph, 3, 10).unwrap();
+        assert_eq!(line_graph.node_count, 4);
+        assert_eq!(line_graph.arcs.len(), 2);
+        assert!(find_arcs_without_mirror(&line_graph).is_empty());
+        let arcs: Vec<_> = line_graph
+            .arcs
+            .iter()
+            .map(|arc| (arc.from, arc.to, arc.weight, arc.sequence.as_str()))
+            .collect();
+        assert_eq!(arcs, vec![(0, 1, 2, "ACTG"), (2, 3, 2, "CAGT")]);
+
+        assert!(super::line_graph(&graph, 3, 1).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::bcalm2_header::Bcalm2Header;
+use crate::Graph;
+use genome_graph::bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
+use genome_graph::bigraph::traitgraph::index::GraphIndex;
+use genome_graph::bigraph::traitgraph::interface::{Edge, GraphBase, ImmutableGraphContainer};
+use std::collections::HashMap;
+
+type EdgeIndex = <Graph as GraphBase>::EdgeIndex;
+type NodeIndex = <Graph as GraphBase>::NodeIndex;
+
+/// An inconsistency between the bcalm2 records and the graph built from them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkMismatch {
+    /// The bcalm2 id of the record the mismatch was found at.
+    pub record_id: usize,
+    /// A description of the mismatch.
+    pub description: String,
+}
+
+impl std::fmt::Display for LinkMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "record >{}: {}", self.record_id, self.description)
+    }
+}
+
+/// The forward and reverse complement edges created for a bcalm2 record.
+#[derive(Default)]
+struct RecordEdges {
+    forwards: Vec<EdgeIndex>,
+    backwards: Vec<EdgeIndex>,
+}
+
+/// Cross-check the `L:` links of the bcalm2 records against the edges of the graph that was built from them.
+///
+/// Each record must be represented by exactly one forwards and one backwards edge that are mirrors of each other.
+/// Each link `L:<s1>:<id>:<s2>` must connect the end of the record in orientation `s1`
+/// with the start of record `id` in orientation `s2`.
+pub fn verify_links(graph: &Graph, headers: &[Bcalm2Header]) -> Vec<LinkMismatch> {
+    let mut record_edges: HashMap<usize, RecordEdges> = HashMap::new();
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        let edges = record_edges.entry(edge_data.id).or_default();
+        if edge_data.forwards {
+            edges.forwards.push(edge_id);
+        } else {
+            edges.backwards.push(edge_id);
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    let mut mismatch = |record_id, description| {
+        mismatches.push(LinkMismatch {
+            record_id,
+            description,
+        })
+    };
+
+    // The edge of a record in the given orientation, if the record is represented consistently.
+    let mut oriented_edges: HashMap<(usize, bool), EdgeIndex> = HashMap::new();
+    for header in headers {
+        let Some(edges) = record_edges.get(&header.id) else {
+            mismatch(header.id, "record has no edges in the graph".to_owned());
+            continue;
+        };
+        if edges.forwards.len() != 1 || edges.backwards.len() != 1 {
+            mismatch(
+                header.id,
+                format!(
+                    "record has {} forwards and {} backwards edges, expected one each",
+                    edges.forwards.len(),
+                    edges.backwards.len()
+                ),
+            );
+            continue;
+        }
+
+        let (forwards, backwards) = (edges.forwards[0], edges.backwards[0]);
+        if graph.mirror_edge_edge_centric(forwards) != Some(backwards) {
+            mismatch(
+                header.id,
+                "the forwards edge is not mirrored by the backwards edge".to_owned(),
+            );
+            continue;
+        }
+        oriented_edges.insert((header.id, true), forwards);
+        oriented_edges.insert((header.id, false), backwards);
+    }
+
+    let endpoints = |edge_id| -> Edge<NodeIndex> { graph.edge_endpoints(edge_id) };
+    for header in headers {
+        for link in &header.links {
+            let (Some(&from_edge), Some(&to_edge)) = (
+                oriented_edges.get(&(header.id, link.from_forwards)),
+                oriented_edges.get(&(link.to_id, link.to_forwards)),
+            ) else {
+                if !record_edges.contains_key(&link.to_id) {
+                    mismatch(
+                        header.id,
+                        format!("{link} points to a record that does not exist"),
+                    );
+                }
+                continue;
+            };
+
+            let from_node = endpoints(from_edge).to_node;
+            let to_node = endpoints(to_edge).from_node;
+            if from_node != to_node {
+                mismatch(
+                    header.id,
+                    format!(
+                        "{link} is not represented in the graph: the source edge ends in node {} but the target edge starts in node {}",
+                        from_node.as_usize(),
+                        to_node.as_usize()
+                    ),
+                );
+            }
+        }
+    }
+
+    mismatches
+}
@@ -1,427 +1,8024 @@
+use crate::abundance_histogram::HistogramScale;
+use crate::abundance_outliers::{AbundanceOutliers, Outlier};
+use crate::abundance_tag::apply_abundance_tag;
+use crate::adjacency_list_output::write_adjacency_list;
+use crate::alphabet::{translate_character, Alphabet, AlphabetDetector, SequenceCase};
+use crate::arc_centric_reader::{
+    read_arc_centric_graph, read_arc_centric_graph_with_node_indexing, reverse_complement,
+    write_arc_centric_graph, ArcCentricGraph,
+};
+use crate::arc_limit::ArcLimit;
+use crate::ascii_preview::{write_ascii_preview, ASCII_PREVIEW_MAX_NODES};
+use crate::bcalm2_header::{count_records, Bcalm2Header, HeaderInspector};
+use crate::bubbles::{find_bubbles, pop_bubbles};
+use crate::byte_counter::ByteCounter;
+use crate::colors::{extract_record_colors, format_colors, union_colors, ColorTable, COLOR_TAG};
+use crate::components::{largest_component, spanning_forest};
+use crate::coverage_track::{coverage_intervals, write_bedgraph, ReferenceOrder};
+use crate::csr_output::write_csr;
+use crate::dedup_sequence_store::DedupSequenceStore;
+use crate::diff::{diff_graphs, patch_graphs};
+use crate::dimacs_output::write_dimacs;
+use crate::dot_output::{render_png, write_dot};
+use crate::double_buffering::DoubleBufferedWriter;
+use crate::eulerian::check_eulerian;
+use crate::fasta_wrapping::FastaLineWrapper;
+use crate::format_version::{FormatVersion, Version1Writer};
+use crate::gfa_output::{write_bifrost_gfa, write_gfa1, write_spaligner_gfa};
+use crate::global_sort::{sort_arc_lines, ExternalSorter};
+use crate::header_node_count::{rewrite_header_node_count, HeaderNodeCount};
+use crate::id_encoding::IdEncoding;
+use crate::input_glob::glob_paths;
+use crate::intermediate_dump::write_intermediate_graph;
+use crate::json_output::write_json;
+use crate::k_consistency::{find_overlap_mismatches, RecordEnds};
+use crate::kmer_counts::KmerCounts;
+use crate::kmer_spectrum::KmerSpectrum;
+use crate::line_endings::{CrlfNormalizer, InputEncoding};
+use crate::line_graph::line_graph;
+use crate::manifest::{write_manifest, ManifestEntry};
+use crate::matrix_output::write_matrix;
 use crate::memory_meter::MemoryMeter;
-use clap::Parser;
+use crate::meta_sidecar::resolve_k;
+use crate::metis_output::write_metis;
+use crate::mirror_index::write_mirror_index;
+use crate::mmap_output::MmapWriter;
+use crate::newick_output::write_newick;
+use crate::node_deduplication::deduplicate_nodes_by_sequence;
+use crate::node_labels::{read_node_labels, FROM_LABEL_TAG, TO_LABEL_TAG};
+use crate::node_names::{NodeName, NodeNames};
+use crate::parquet_output::write_parquet;
+use crate::path_compaction::collapse_linear_paths;
+use crate::preallocation::{preallocate, truncate_to_position};
+use crate::process_input::ProcessInput;
+use crate::progress::ProgressReporter;
+use crate::protobuf_output::{read_protobuf, write_protobuf};
+use crate::record_transformer::{
+    compress_record_homopolymers, strip_record_tags, RecordTransformer,
+};
+use crate::record_validation::check_record;
+use crate::report::ConversionReport;
+use crate::round_trip::check_merge_stability;
+use crate::sampling::{make_rng, sample_arcs};
+use crate::self_complemental_report::{write_self_complemental_report, SelfComplementalArc};
+use crate::sequence_lengths::write_sequence_lengths;
+use crate::sequence_splitting::{split_long_arcs, SOURCE_OFFSET_TAG};
+use crate::snapshot_request::SnapshotRequest;
+use crate::split_output::{
+    part_path, shard_path, write_sharded_output, write_split_output, write_strand_output,
+};
+use crate::statistics::GraphStatistics;
+use crate::timeout::{Timeout, TimeoutError, TIMEOUT_EXIT_CODE};
+use crate::transpose::transpose;
+use crate::weight_metric::WeightMetric;
+use anyhow::{anyhow, bail, Context};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
 use genome_graph::bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
 use genome_graph::bigraph::traitgraph::index::GraphIndex;
 use genome_graph::bigraph::traitgraph::interface::{
-    Edge, ImmutableGraphContainer, NavigableGraph, Neighbor,
+    Edge, GraphBase, ImmutableGraphContainer, MutableGraphContainer, NavigableGraph, Neighbor,
 };
 use genome_graph::bigraph::traitgraph::traitsequence::interface::Sequence;
 use genome_graph::compact_genome::implementation::DefaultSequenceStore;
-use genome_graph::compact_genome::interface::alphabet::dna_alphabet::DnaAlphabet;
+use genome_graph::compact_genome::interface::alphabet::dna_alphabet::{DnaAlphabet, DnaCharacter};
 use genome_graph::compact_genome::interface::sequence::GenomeSequence;
 use genome_graph::compact_genome::interface::sequence_store::SequenceStore;
-use genome_graph::io::bcalm2::read_bigraph_from_bcalm2_as_edge_centric;
+use genome_graph::io::bcalm2::{
+    read_bigraph_from_bcalm2_as_edge_centric, write_edge_centric_bigraph_to_bcalm2, PlainBCalm2Edge,
+};
 use genome_graph::types::PetBCalm2EdgeGraph;
-use log::{info, warn, LevelFilter};
+use log::{debug, info, warn, LevelFilter};
+use rand::seq::SliceRandom;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use simplelog::{ColorChoice, CombinedLogger, Config, TermLogger, TerminalMode};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
-use std::io::{BufRead, BufReader, BufWriter};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, BufWriter, Cursor};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+mod abundance_histogram;
+mod abundance_outliers;
+mod abundance_tag;
+mod adjacency_list_output;
+mod alphabet;
+mod arc_centric_reader;
+mod arc_limit;
+mod ascii_preview;
+mod balance;
+mod bcalm2_header;
+mod bubbles;
+mod byte_counter;
+mod colors;
+mod components;
+mod compression;
+mod coverage_track;
+mod csr_output;
+mod dedup_sequence_store;
+mod diff;
+mod dimacs_output;
+mod dot_output;
+mod double_buffering;
+mod eulerian;
+mod fasta_wrapping;
+mod format_version;
+mod gfa_output;
+mod global_sort;
+mod header_node_count;
+mod id_encoding;
+mod input_glob;
+mod intermediate_dump;
+mod json_output;
+mod k_consistency;
+mod kmer_counts;
+mod kmer_spectrum;
+mod line_endings;
+mod line_graph;
+mod link_verification;
+mod manifest;
+mod matrix_output;
 mod memory_meter;
+mod meta_sidecar;
+mod metis_output;
+mod mirror_index;
+mod mmap_output;
+mod newick_output;
+mod node_deduplication;
+mod node_labels;
+mod node_names;
+mod output_validation;
+mod parquet_output;
+mod path_compaction;
+mod preallocation;
+mod process_input;
+mod progress;
+mod protobuf_output;
+mod record_transformer;
+mod record_validation;
+mod report;
+mod round_trip;
+mod sampling;
+mod self_complemental_report;
+mod sequence_lengths;
+mod sequence_splitting;
+mod snapshot_request;
+mod split_output;
+mod statistics;
+#[cfg(test)]
+mod test_fixtures;
+#[cfg(test)]
+mod test_logger;
+mod timeout;
+mod transpose;
+mod weight_metric;
 
-#[derive(Parser, Debug)]
+type SequenceHandle = <DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::Handle;
+type Graph = PetBCalm2EdgeGraph<SequenceHandle>;
+
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[clap(group(
+    ArgGroup::new("strict_checks")
+        .multiple(true)
+        .args(["verify_input_sorted", "validate_k_consistency"])
+))]
+#[serde(rename_all = "kebab-case")]
 struct Cli {
+    #[clap(subcommand)]
+    #[serde(skip)]
+    command: Option<Command>,
+
     /// The input file containing a node-centric de Bruijn graph.
     /// The file should be in bcalm2 format.
-    #[clap(long)]
-    input: PathBuf,
+    /// Use `-` to read from standard input.
+    /// Gzip and bgzip compressed inputs are decompressed automatically.
+    #[clap(long, required_unless_present_any = ["input_glob", "input_from_process"])]
+    input: Option<PathBuf>,
+
+    /// Read the input from the standard output of this command instead of a file, e.g. to stream the output of bcalm2.
+    /// The command is run with `sh -c`, and the conversion fails if the command fails.
+    /// Requires `-k`, since there is no metadata file.
+    #[clap(long, requires = "k", conflicts_with_all = [
+        "input", "input_glob", "two_pass", "parse_only", "count_kmers_only", "self_test",
+    ])]
+    input_from_process: Option<String>,
 
     /// The k-mer size used to generate the de Bruijn graph.
+    /// If omitted, it is read from the metadata file `<input>.meta`, which contains a line like `k=31`.
     #[clap(short)]
-    k: usize,
+    k: Option<usize>,
 
     /// The output file where the arc-centric de Bruijn graph should be written to.
+    #[clap(long, required_unless_present_any = ["parse_only", "input_glob", "count_kmers_only", "self_test"])]
+    output: Option<PathBuf>,
+
+    /// Convert each input file matching this pattern, instead of a single input file.
+    /// The wildcards `*` and `?` are supported in the file name, e.g. `data/*.bcalm2`.
+    /// The files are converted one after the other, and the output of each is written to `--output-template`.
+    #[clap(long, requires = "output_template", conflicts_with_all = [
+        "input", "output", "parse_only", "max_output_size", "shards", "resume_from_node", "atomic_output", "mmap_output",
+        "validate_output", "strict_bigraph_symmetry", "mirror_index", "lengths_file", "emit_spectrum", "manifest",
+        "config_out", "render_png", "count_kmers_only", "self_test", "stats", "benchmark", "preallocate",
+    ])]
+    input_glob: Option<String>,
+
+    /// The output path of each input file of `--input-glob`,
+    /// where `{name}` is replaced by the file name of the input without its last extension.
+    /// The template must contain `{name}` if more than one file matches.
+    #[clap(long, requires = "input_glob")]
+    output_template: Option<String>,
+
+    /// Abort converting the files of `--input-glob` at the first error,
+    /// instead of continuing with the remaining files.
+    #[clap(long, requires = "input_glob")]
+    fail_fast: bool,
+
+    /// The desired log level. Defaults to `Info`.
+    /// Can also be set via the environment variable `NODE_TO_ARC_LOG`, which is ignored if this option is given,
+    /// unless its value is prefixed with `force:`, e.g. `NODE_TO_ARC_LOG=force:debug`.
     #[clap(long)]
-    output: PathBuf,
+    log_level: Option<LevelFilter>,
 
-    /// The desired log level.
-    #[clap(long, default_value = "Info")]
-    log_level: LevelFilter,
-}
+    /// The format of the output file.
+    #[clap(long, value_enum, default_value_t)]
+    output_format: OutputFormat,
 
-pub fn initialise_logging(log_level: LevelFilter) {
-    CombinedLogger::init(vec![TermLogger::new(
-        log_level,
-        Config::default(),
-        TerminalMode::Mixed,
-        ColorChoice::Auto,
-    )])
-    .unwrap();
+    /// The version of the layout of the arc-centric format.
+    /// Version 1 always consists of only the lines `<node count>` and `n1 n2 weight mirror_n1 mirror_n2 sequence`,
+    /// even if options are given that add columns or comments, e.g. for tools that rely on this layout.
+    /// Options that change the values of the columns, e.g. `--float-weights`, are still applied.
+    #[clap(long, value_enum, default_value_t)]
+    format_version: FormatVersion,
 
-    info!("Logging initialised successfully");
-}
+    /// Wrap the sequences of the bcalm2 format after this many characters, or write each on a single line if zero.
+    #[clap(long, default_value_t = 0)]
+    fasta_line_width: usize,
 
-pub fn node_to_arc_centric_dbg(k: usize, input: &mut impl BufRead, output: &mut impl Write) {
-    node_to_arc_centric_dbg_with_memory_meter(k, input, output, None)
-}
+    /// Cross-check the `L:` links of the input records against the edges of the constructed graph,
+    /// and abort if any of them is inconsistent.
+    #[clap(long)]
+    verify_links: bool,
 
-fn node_to_arc_centric_dbg_with_memory_meter(
-    k: usize,
-    input: &mut impl BufRead,
-    output: &mut impl Write,
-    meter: Option<&mut MemoryMeter>,
-) {
-    info!("Reading graph");
-    let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::new();
-    let graph: PetBCalm2EdgeGraph<_> =
-        read_bigraph_from_bcalm2_as_edge_centric(input, &mut sequence_store, k).unwrap();
-    info!(
-        "Finished graph reading: {} nodes and {} edges",
-        graph.node_count(),
-        graph.edge_count()
-    );
+    /// Check that the ids of the input records are increasing, and warn about records that are out of order.
+    #[clap(long)]
+    verify_input_sorted: bool,
 
-    if let Some(meter) = meter {
-        meter.report();
-    }
+    /// Check that the `L:` links of the input records imply an overlap of k-1 characters,
+    /// i.e. that the linked records end and start with the same k-1 characters,
+    /// and warn about inconsistent links, which indicate a wrong k or a corrupt input.
+    /// The links are checked before building the graph, so the input is buffered in memory.
+    #[clap(long)]
+    validate_k_consistency: bool,
 
-    info!("Writing graph...");
-    output_arc_centric_dbg(&graph, &sequence_store, k, output);
-}
+    /// Write the edges of the graph to this file right after reading it, before any arcs are merged or skipped,
+    /// e.g. to tell apart problems of reading and writing the graph.
+    /// Each line is `<from> <to> <unitig> <orientation> <abundance> <sequence>`, after a header `<nodes> <edges>`.
+    #[clap(long)]
+    dump_intermediate_graph: Option<PathBuf>,
 
-fn output_arc_centric_dbg(
-    graph: &PetBCalm2EdgeGraph<
-        <DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::Handle,
-    >,
-    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
-    k: usize,
-    output: &mut impl Write,
-) {
-    writeln!(output, "{}", graph.node_count()).unwrap();
-    for n1 in graph.node_indices() {
-        let mut neighbors: Vec<_> = graph.out_neighbors(n1).collect();
-        neighbors.sort_unstable_by_key(|neighbor| neighbor.node_id);
+    /// Write the coverage of the sequence spelled by the unitigs of `--reference-order` to this file
+    /// as bedGraph track, e.g. for genome browsers.
+    /// Each k-mer has the mean abundance of its unitig, and the coverage of a base is the mean abundance
+    /// of the k-mers that contain it.
+    #[clap(long, requires = "reference_order")]
+    emit_read_coverage_wig: Option<PathBuf>,
 
-        let mut n2_iterator = neighbors.iter().peekable();
-        while let Some(Neighbor {
-            node_id: n2,
-            edge_id,
-        }) = n2_iterator.next().cloned()
-        {
-            let edge_data = graph.edge_data(edge_id);
+    /// A linear order of oriented unitigs for `--emit-read-coverage-wig`, as bcalm2 ids with orientations like `3+ 5-`,
+    /// separated by whitespace or commas. Consecutive unitigs must overlap by k-1 characters.
+    /// The file name without extension is the name of the reference sequence in the track.
+    #[clap(long, requires = "emit_read_coverage_wig")]
+    reference_order: Option<PathBuf>,
 
-            // if there is a pair of reverse complemental edges with a self-complemental label,
-            // then we merge them, as they represent the same sequence.
-            let weight_multiplier = if let Some(Neighbor {
-                node_id: next_n2,
-                edge_id: next_edge_id,
-            }) = n2_iterator.peek()
-            {
-                let next_edge_data = graph.edge_data(*next_edge_id);
-                if n2 == *next_n2
-                    && graph.mirror_edge_edge_centric(edge_id).unwrap() == *next_edge_id
-                {
-                    if edge_data.forwards == next_edge_data.forwards {
-                        if sequence_store.get(&edge_data.sequence_handle)
-                            == sequence_store.get(&next_edge_data.sequence_handle)
-                        {
-                            n2_iterator.next().unwrap();
-                            2
-                        } else {
-                            1
-                        }
-                    } else if sequence_store
-                        .get(&edge_data.sequence_handle)
-                        .iter()
-                        .copied()
-                        .zip(
-                            sequence_store
-                                .get(&next_edge_data.sequence_handle)
-                                .reverse_complement_iter(),
-                        )
-                        .all(|(c1, c2)| c1 == c2)
-                    {
-                        n2_iterator.next().unwrap();
-                        2
-                    } else {
-                        1
-                    }
-                } else {
-                    1
-                }
-            } else {
-                1
-            };
+    /// Abort instead of warning if `--verify-input-sorted` finds records that are out of order,
+    /// or if `--validate-k-consistency` finds inconsistent links.
+    #[clap(long, requires = "strict_checks")]
+    strict: bool,
 
-            let kmer_count = edge_data.length - (k - 1);
-            if edge_data.total_abundance % kmer_count != 0 {
-                let sequence = sequence_store.get(&edge_data.sequence_handle);
-                let sequence = &sequence[..(k + 10).min(sequence.len())];
-                warn!(
-                    "Found edge with non-integer average abundance: {}",
-                    sequence.as_string()
-                );
-            }
+    /// Emit unitigs that are their own reverse complement only once, marked with a `PA:i:1` tag.
+    #[clap(long)]
+    collapse_palindromic_unitigs: bool,
 
-            let mirror_edge = graph.mirror_edge_edge_centric(edge_id).unwrap();
-            let Edge {
-                from_node: mirror_n1,
-                to_node: mirror_n2,
-            } = graph.edge_endpoints(mirror_edge);
-            let n1 = n1.as_usize();
-            let n2 = n2.as_usize();
-            let mirror_n1 = mirror_n1.as_usize();
-            let mirror_n2 = mirror_n2.as_usize();
-            let weight = edge_data.total_abundance / kmer_count * weight_multiplier;
-            write!(output, "{n1} {n2} {weight} {mirror_n1} {mirror_n2} ").unwrap();
+    /// Skip unitigs that are shorter than k instead of aborting.
+    #[clap(long)]
+    skip_short: bool,
 
-            let sequence = sequence_store.get(&edge_data.sequence_handle);
-            if edge_data.forwards {
-                for character in sequence.iter() {
-                    write!(output, "{}", character).unwrap();
-                }
-            } else {
-                for character in sequence.reverse_complement_iter() {
-                    write!(output, "{}", character).unwrap();
-                }
-            }
-            writeln!(output).unwrap();
-        }
-    }
-}
+    /// Exit with an error if no arcs are written, e.g. because all unitigs were skipped.
+    /// The header of the empty graph is still written.
+    #[clap(long)]
+    require_nonempty: bool,
 
-fn main() {
-    let mut meter = MemoryMeter::new();
-    let cli = Cli::parse();
-    initialise_logging(cli.log_level);
+    /// Skip unitigs with less than this many k-mers, i.e. shorter than this plus k - 1 characters, and their mirror arcs.
+    #[clap(long, default_value_t = 0)]
+    min_unitig_kmers: usize,
 
-    meter.report();
+    /// Scan the input once for record counts before reading the graph.
+    /// Not possible when reading from standard input.
+    #[clap(long)]
+    two_pass: bool,
 
-    info!(
-        "Loading graph from {:?} with k = {} and writing to {:?}",
-        cli.input, cli.k, cli.output
-    );
-    let mut input = BufReader::new(File::open(&cli.input).unwrap());
-    let mut output = BufWriter::new(File::create(&cli.output).unwrap());
-    node_to_arc_centric_dbg_with_memory_meter(cli.k, &mut input, &mut output, Some(&mut meter));
+    /// Name nodes by the ends of the input unitigs they are attached to, instead of by their index.
+    /// For example, `3+e` is the node at the end of unitig `3`, and `3-s` is the node at the start of its reverse complement.
+    #[clap(long)]
+    preserve_original_ids: bool,
 
-    meter.report();
+    /// Collapse runs of identical characters in the unitig sequences before constructing the graph.
+    /// The graph is then constructed in homopolymer-compressed space, i.e. k refers to compressed k-mers,
+    /// and the abundances are averaged over the compressed k-mers.
+    #[clap(long)]
+    compress_homopolymers: bool,
 
-    info!("Success!");
-}
+    /// Log and skip input records that the bcalm2 reader would reject, instead of failing,
+    /// e.g. to salvage a partially corrupt file. Links to skipped records are ignored.
+    #[clap(long)]
+    skip_bad_records: bool,
 
-#[cfg(test)]
-mod tests {
-    use crate::node_to_arc_centric_dbg;
-    use std::io::BufReader;
+    /// Remove all header tags except for `LN`, `KC`, `km` and `L` before reading the input.
+    /// This allows to convert files of bcalm2-like tools that add nonstandard tags.
+    #[clap(long)]
+    strip_tags: bool,
 
-    #[test]
-    fn test_complex_file() {
-        let mut file = BufReader::new(
-            ">0 LN:i:14 KC:i:21 km:f:21.0   L:-:2:+  L:+:2:+
-ATCGATCGATCGAT
->1 LN:i:14 KC:i:20 km:f:20.0   L:-:2:-  L:+:2:-
-CGATCGATCGATCG
->2 LN:i:14 KC:i:43 km:f:43.0   L:+:1:+ L:+:1:- L:+:3:+  L:-:0:+ L:-:0:-
-TCGATCGATCGATC
->3 LN:i:16 KC:i:3 km:f:1.0   L:-:2:-
-CGATCGATCGATCAGT"
-                .as_bytes(),
-        );
+    /// Read the mean abundance of each unitig from the header tag with this name instead of the `KC` and `km` tags,
+    /// e.g. `ab` for tags like `ab:f:12.5`.
+    /// The k-mer count is computed from the mean abundance and the length of the unitig.
+    #[clap(long)]
+    abundance_tag: Option<String>,
 
-        let expected = "6
-0 1 42 0 1 ATCGATCGATCGAT
-1 2 43 3 0 TCGATCGATCGATC
-2 3 40 2 3 CGATCGATCGATCG
-2 4 1 5 3 CGATCGATCGATCAGT
-3 0 43 1 2 GATCGATCGATCGA
-5 3 1 2 4 ACTGATCGATCGATCG
-";
+    /// Experimental: reduce the memory held while writing.
+    /// The bcalm2 reader needs the whole input to resolve the nodes of the graph, so reading and writing cannot be interleaved.
+    /// Instead, the link records stored with each edge are released before writing.
+    #[clap(long)]
+    streaming: bool,
 
-        let mut output = Vec::new();
-        node_to_arc_centric_dbg(14, &mut file, &mut output);
-        println!("{}", String::from_utf8(output.clone()).unwrap());
+    /// Debug mode: check the reverse complement of each arc that is written in reverse direction
+    /// against an independently computed reverse complement, and fail if they differ.
+    #[clap(long)]
+    verify_revcomp: bool,
 
-        assert_eq!(expected.as_bytes(), output);
-    }
+    /// After writing, read the output back and check that it parses
+    /// and that the mirror columns of each arc point to a reciprocal arc.
+    #[clap(long)]
+    validate_output: bool,
 
-    #[test]
-    fn test_complex_circularised_file() {
-        let mut file = BufReader::new(
-            ">0 LN:i:14 KC:i:20 km:f:20.0   L:-:1:-  L:+:1:-
-CGATCGATCGATCG
->1 LN:i:14 KC:i:43 km:f:43.0   L:+:0:+ L:+:0:- L:+:4:+ L:+:5:+  L:-:2:+ L:-:2:- L:-:5:-
-TCGATCGATCGATC
->2 LN:i:14 KC:i:21 km:f:21.0   L:-:1:+  L:+:1:+
-ATCGATCGATCGAT
->3 LN:i:27 KC:i:14 km:f:1.0   L:-:4:-  L:+:4:-
-GATCGATCGATCAGTGATCGATCGATC
->4 LN:i:14 KC:i:2 km:f:2.0   L:-:1:-  L:+:3:+ L:+:3:-
-CGATCGATCGATCA
->5 LN:i:26 KC:i:13 km:f:1.0   L:-:1:-  L:+:1:+
-CGATCGATCGATCTCGATCGATCGAT"
-                .as_bytes(),
-        );
+    /// After writing, read the output back and check that it is a proper bidirected graph:
+    /// each arc that is not its own mirror has exactly one reciprocal arc, and reciprocal arcs have the same weight.
+    /// This is stricter than `--validate-output`.
+    #[clap(long)]
+    strict_bigraph_symmetry: bool,
 
-        let expected = "6
-0 1 40 0 1 CGATCGATCGATCG
-0 2 1 3 1 CGATCGATCGATCTCGATCGATCGAT
-0 4 2 5 1 CGATCGATCGATCA
-1 3 43 2 0 GATCGATCGATCGA
-2 0 43 1 3 TCGATCGATCGATC
-3 1 1 0 2 ATCGATCGATCGAGATCGATCGATCG
-3 2 42 3 2 ATCGATCGATCGAT
-4 5 1 4 5 GATCGATCGATCACTGATCGATCGATC
-4 5 1 4 5 GATCGATCGATCAGTGATCGATCGATC
-5 1 2 0 4 TGATCGATCGATCG
-";
+    /// After writing, read the output back and write the line number of the mirror arc of each arc to this file,
+    /// as lines `<arc line> <mirror line>`, where the header is the first line of the output.
+    /// Self-complemental arcs are their own mirror.
+    #[clap(long, conflicts_with_all = [
+        "max_output_size", "shards", "resume_from_node", "preserve_original_ids", "float_weights", "trim_overlap",
+        "annotate_nodes",
+    ])]
+    mirror_index: Option<PathBuf>,
 
-        let mut output = Vec::new();
-        node_to_arc_centric_dbg(14, &mut file, &mut output);
-        println!("{}", String::from_utf8(output.clone()).unwrap());
+    /// Write the k-mer spectrum of the input graph to this file, as lines `<multiplicity> <k-mer count>`.
+    /// The multiplicity of the k-mers of a unitig is its mean abundance, rounded down.
+    /// Each unitig is counted once, independent of how it is written.
+    #[clap(long)]
+    emit_spectrum: Option<PathBuf>,
 
-        assert_eq!(expected.as_bytes(), output);
-    }
+    /// After writing, read the output back and write the length of the sequence of each arc to this file,
+    /// one per line, in the same order as the arcs in the output.
+    #[clap(long, conflicts_with_all = [
+        "max_output_size", "shards", "resume_from_node", "preserve_original_ids", "float_weights",
+    ])]
+    lengths_file: Option<PathBuf>,
 
-    #[test]
-    fn test_pseudo_reverse_complemental_arc() {
-        let mut file = BufReader::new(
-            ">0 LN:i:30 KC:i:16 km:f:1.0   L:-:1:-  L:+:1:-
-ATATATATATATGGCACCATATATATATAT
->1 LN:i:16 KC:i:4 km:f:2.0   L:-:1:+ L:-:2:+  L:+:0:+ L:+:0:-
-ATATATATATATATGG
->2 LN:i:15 KC:i:1 km:f:1.0   L:+:2:- L:+:4:+  L:-:1:+ L:-:2:+
-ATATATATATATATA
->3 LN:i:19 KC:i:5 km:f:1.0    L:+:4:-
-ACGGGGGGGGGGACACACA
->4 LN:i:38 KC:i:29 km:f:1.2   L:-:2:- L:-:4:+  L:+:3:- L:+:5:-
-TATATATATATATAAAAACAACCGTGTGTGTCCCCCCC
->5 LN:i:19 KC:i:5 km:f:1.0    L:+:4:-
-ATGCTGGGGGGGACACACA
-"
-            .as_bytes(),
-        );
+    /// Read the colors of each unitig from a `CL:Z:<bitmask>` tag in the input, e.g. `CL:Z:101`,
+    /// and write them as a tag of the same form after each arc.
+    /// Merged reverse complemental arcs get the union of their colors.
+    #[clap(long)]
+    colors: bool,
 
-        let expected = "10
-0 1 1 0 1 ATATATATATATGGTGCCATATATATATAT
-0 1 1 0 1 ATATATATATATGGCACCATATATATATAT
-1 2 2 2 0 CCATATATATATATAT
-2 0 2 1 2 ATATATATATATATGG
-2 3 1 3 2 ATATATATATATATA
-3 2 1 2 3 TATATATATATATAT
-3 7 1 6 3 TATATATATATATAAAAACAACCGTGTGTGTCCCCCCC
-4 6 1 7 5 ACGGGGGGGGGGACACACA
-6 3 1 3 7 GGGGGGGACACACACGGTTGTTTTTATATATATATATA
-7 5 1 4 6 TGTGTGTCCCCCCCCCCGT
-7 9 1 8 6 TGTGTGTCCCCCCCAGCAT
-8 6 1 7 9 ATGCTGGGGGGGACACACA
-";
+    /// Log a progress message every this many nodes while writing. Disabled if zero.
+    #[clap(long, default_value_t = 0)]
+    progress_interval: usize,
 
-        let mut output = Vec::new();
-        node_to_arc_centric_dbg(15, &mut file, &mut output);
-        println!("{}", String::from_utf8(output.clone()).unwrap());
+    /// Split the output into parts of at most this many bytes.
+    /// The parts are named like `out.part0.txt` for an output file `out.txt`, and each repeats the node count header.
+    /// Reverse complemental arcs are always written into the same part.
+    /// The whole output is buffered in memory before splitting.
+    /// Not possible with `--trim-overlap`, since trimmed reverse complemental arcs cannot be paired by their sequence.
+    #[clap(long, conflicts_with = "trim_overlap")]
+    max_output_size: Option<usize>,
 
-        assert_eq!(expected.as_bytes(), output);
-    }
+    /// Partition the output into this many shards by hashing the nodes of each arc.
+    /// The shards are named like `out.shard0.txt` for an output file `out.txt`, and each repeats the node count header.
+    /// Reverse complemental arcs are always written into the same shard,
+    /// and the hash function is fixed, so an arc is in the same shard on every run.
+    /// The whole output is buffered in memory before sharding.
+    #[clap(long, conflicts_with_all = ["max_output_size", "resume_from_node", "annotate_nodes"])]
+    shards: Option<usize>,
 
-    #[test]
-    fn test_bcalm2_multiplicities() {
-        let strings = [
-            "ACTGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATC",
-            "GATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGA",
-        ];
-        let strings: Vec<_> = strings
-            .iter()
-            .flat_map(|&string| {
-                [
-                    string.to_owned(),
-                    string
-                        .chars()
-                        .rev()
-                        .map(|c| match c {
-                            'A' => 'T',
-                            'C' => 'G',
-                            'G' => 'C',
-                            'T' => 'A',
-                            c => panic!("{c}"),
-                        })
-                        .collect(),
-                ]
-            })
-            .collect();
+    /// Write the arcs that spell their unitig into `--forward-out`, and the arcs that spell its reverse complement
+    /// into `--reverse-out`. Merged self-complemental arcs belong to both strands, so they are written into the output.
+    /// Each file repeats the node count header. The whole output is buffered in memory before splitting.
+    /// Since each strand file holds only one arc of each mirror pair, the output cannot be validated or indexed.
+    #[clap(
+        long,
+        requires_all = ["forward_out", "reverse_out"],
+        conflicts_with_all = [
+            "max_output_size", "shards", "resume_from_node", "annotate_nodes", "atomic_output", "mmap_output",
+            "preallocate", "trim_overlap", "base", "validate_output", "strict_bigraph_symmetry", "mirror_index",
+            "lengths_file",
+        ],
+    )]
+    split_by_strand: bool,
 
-        let requirements = [
-            ("ATCGATCGATCGAT", 42),
-            ("TCGATCGATCGATC", 43),
-            ("CGATCGATCGATCG", 40),
-            ("CGATCGATCGATCAGT", 1),
-        ];
+    /// The file of the arcs that spell their unitig, see `--split-by-strand`.
+    #[clap(long, requires = "split_by_strand")]
+    forward_out: Option<PathBuf>,
 
-        for (kmer, count) in requirements {
-            for offset in 0..kmer.len() - 13 {
-                let kmer = &kmer[offset..offset + 14];
-                let mut actual_count = 0;
-                for string in &strings {
-                    let mut string = string.as_str();
-                    while let Some(index) = string.find(kmer) {
-                        actual_count += 1;
-                        string = &string[(index + 1).min(string.len())..];
-                    }
-                }
-                assert_eq!(
-                    count, actual_count,
-                    "kmer {kmer} should match {count} times but matches {actual_count} times"
-                );
-            }
-        }
-    }
+    /// The file of the arcs that spell the reverse complement of their unitig, see `--split-by-strand`.
+    #[clap(long, requires = "split_by_strand")]
+    reverse_out: Option<PathBuf>,
 
-    #[test]
-    fn test_bcalm2_circularised_multiplicities() {
-        let strings = [
-            "ACTGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCACTGATCGATCGA",
-            "GATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGAGATCGATCGATCG",
-        ];
-        let strings: Vec<_> = strings
-            .iter()
-            .flat_map(|&string| {
-                [
-                    string.to_owned(),
-                    string
-                        .chars()
-                        .rev()
-                        .map(|c| match c {
-                            'A' => 'T',
-                            'C' => 'G',
-                            'G' => 'C',
-                            'T' => 'A',
-                            c => panic!("{c}"),
-                        })
-                        .collect(),
-                ]
-            })
-            .collect();
-        for string in &strings {
-            println!("{string}");
-        }
+    /// The maximum number of nodes of a graph written with `--output-format matrix`,
+    /// since the size of the matrix is quadratic in the number of nodes.
+    #[clap(long, default_value_t = 1000)]
+    matrix_max_nodes: usize,
 
-        let requirements = [
-            ("ATCGATCGATCGAT", 42),
-            ("CGATCGATCGATCG", 40),
-            ("TCGATCGATCGATC", 43),
-            ("CGATCGATCGATCA", 2),
-            ("GATCGATCGATCAGTGATCGATCGATC", 1),
-            ("CGATCGATCGATCTCGATCGATCGAT", 1),
-        ];
+    /// Store identical unitig sequences only once while reading.
+    #[clap(long)]
+    dedup_sequences: bool,
 
-        for (kmer, count) in requirements {
-            for offset in 0..kmer.len() - 13 {
-                let kmer = &kmer[offset..offset + 14];
-                let mut actual_count = 0;
-                for string in &strings {
-                    let mut string = string.as_str();
-                    while let Some(index) = string.find(kmer) {
-                        actual_count += 1;
-                        string = &string[(index + 1).min(string.len())..];
-                    }
+    /// Write each arc and its reverse complement as separate records,
+    /// even if they are parallel arcs with the same sequence that would otherwise be merged.
+    /// Then a self-complemental pair is written as two arcs with the weight of the unitig,
+    /// instead of one arc with twice the weight, which is why this is also available as `--split-palindromes`.
+    #[clap(
+        long,
+        alias = "split-palindromes",
+        conflicts_with = "collapse_palindromic_unitigs"
+    )]
+    materialize_mirrors: bool,
+
+    /// Also merge a parallel arc with its reverse complement if their sequences differ in at most this many positions.
+    /// The arc with the lexicographically smaller sequence is written.
+    /// Merged arcs with differing sequences do not pass `--validate-output`.
+    #[clap(long, default_value_t = 0, conflicts_with = "materialize_mirrors")]
+    merge_threshold: usize,
+
+    /// Also merge consecutive parallel arcs with equal sequences in the written orientation, adding their weights,
+    /// even if they are not reverse complements of each other, e.g. for duplicate unitigs in the input.
+    #[clap(long, conflicts_with = "float_weights")]
+    merge_by_sequence_only: bool,
+
+    /// Omit the first k-1 characters of each arc sequence, which overlap with the sequences of its predecessors.
+    /// Then appending the sequences of the arcs of a walk to the full sequence of its first arc spells the walk.
+    /// The number of omitted characters is written as `TR:i:<k-1>` tag.
+    #[clap(long)]
+    trim_overlap: bool,
+
+    /// Check that each edge of the constructed bigraph has a mirror edge, and abort if not.
+    #[clap(long)]
+    assert_balanced: bool,
+
+    /// Check that the two edges of each unitig in the constructed bigraph have the same total abundance,
+    /// and abort if not.
+    #[clap(long)]
+    verify_abundance_conservation: bool,
+
+    /// Log whether the converted arc-centric graph has an Eulerian circuit or path,
+    /// along with its numbers of unbalanced and odd-degree nodes and of connected components.
+    #[clap(long)]
+    check_eulerian: bool,
+
+    /// Print a textual layout of the nodes and their outgoing arcs to standard error, for inspecting toy examples.
+    /// Graphs with more than 32 nodes are not previewed.
+    #[clap(long)]
+    ascii_preview: bool,
+
+    /// Renumber the nodes such that nodes without arcs are left out, e.g. because all their arcs were skipped.
+    /// The header then contains the reduced number of nodes.
+    #[clap(
+        long,
+        overrides_with = "keep_isolated_nodes",
+        conflicts_with = "preserve_original_ids"
+    )]
+    drop_isolated_nodes: bool,
+
+    /// Keep nodes without arcs, such that the node indices are those of the bigraph. This is the default.
+    #[clap(long, overrides_with = "drop_isolated_nodes")]
+    keep_isolated_nodes: bool,
+
+    /// How to compute the weight of an arc from the abundance of its unitig.
+    /// The weights of merged arcs are doubled with any metric.
+    #[clap(long, value_enum, default_value_t = WeightMetric::MeanAbundance)]
+    weight: WeightMetric,
+
+    /// Write weights as the exact mean abundance instead of rounding it down to an integer.
+    #[clap(long)]
+    float_weights: bool,
+
+    /// Compute the weight of an arc as the total abundance of its unitig divided by the length of the unitig,
+    /// i.e. the abundance per base instead of per k-mer. This is the same as `--weight mean-abundance-per-base`.
+    /// Requires `--float-weights`, since the abundance per base is rarely an integer.
+    #[clap(long, conflicts_with = "weight", requires = "float_weights")]
+    normalize_abundance_by_length: bool,
+
+    /// The number of decimal places of weights written with `--float-weights`.
+    #[clap(long, default_value_t = 2, requires = "float_weights")]
+    weight_precision: usize,
+
+    /// Divide the weights written with `--float-weights` by the maximum weight of all written arcs,
+    /// such that they are between zero and one.
+    /// The maximum is found in an additional pass over the arcs of the graph before writing.
+    /// Since the graph is in memory anyway, this neither buffers the output nor reads the input twice.
+    #[clap(long, requires = "float_weights")]
+    normalize_weights: bool,
+
+    /// Write each weight `w` as `round(log2(w + 1) * scale)` to compress its dynamic range,
+    /// where the weight includes the doubling of merged arcs, and the scale is given by `--log-weight-scale`.
+    #[clap(long, conflicts_with = "float_weights")]
+    log_weights: bool,
+
+    /// The factor of the weights written with `--log-weights`.
+    #[clap(long, default_value_t = 1.0, requires = "log_weights")]
+    log_weight_scale: f64,
+
+    /// Resume a partially written output, by appending the arcs of all nodes from this node index onwards.
+    /// The nodes are always processed in the same order, so this continues where the previous run stopped,
+    /// if the previous run wrote all arcs of the nodes before this index.
+    /// The header is not written again.
+    #[clap(long, conflicts_with = "max_output_size")]
+    resume_from_node: Option<usize>,
+
+    /// Write the arcs of the nodes in descending order of node index.
+    /// The order of the arcs of each node stays the same.
+    #[clap(long, conflicts_with = "resume_from_node")]
+    reverse_order: bool,
+
+    /// Write the arcs of the nodes in a random order of the nodes, which is reproducible with `--seed`,
+    /// e.g. to test the robustness of downstream tools to the order of the arcs.
+    /// The order of the arcs of each node stays the same.
+    #[clap(long, conflicts_with_all = ["resume_from_node", "reverse_order"])]
+    shuffle_nodes: bool,
+
+    /// Write this node count into the header instead of the number of nodes of the graph.
+    /// This is a workaround for downstream tools that expect a different node count.
+    /// Fails if an arc has a node index that is not below the given node count.
+    #[clap(long, conflicts_with_all = [
+        "preserve_original_ids", "resume_from_node", "max_sequence_length", "expand_kmers",
+    ])]
+    node_count: Option<usize>,
+
+    /// How to determine the node count in the header of the arc-centric output.
+    /// Filtering arcs may leave nodes without arcs, which are still counted by `graph`.
+    #[clap(long, value_enum, default_value_t = HeaderNodeCount::Graph)]
+    header_node_count: HeaderNodeCount,
+
+    /// How to write the node indices in the arc-centric output.
+    /// The non-decimal encodings make the output smaller for large graphs,
+    /// but break tools that expect the node indices to be integers.
+    #[clap(long, value_enum, default_value_t = IdEncoding::Decimal)]
+    id_encoding: IdEncoding,
+
+    /// Write node indices starting at one instead of zero, as expected by e.g. GFA segment names and METIS.
+    /// The header still contains the node count, which is then the largest node index.
+    #[clap(long)]
+    one_based: bool,
+
+    /// With `--output-format gfa1`, write each maximal non-branching walk as GFA path.
+    #[clap(long)]
+    gfa_emit_paths: bool,
+
+    /// Write only arcs that represent both an arc and its reverse complement,
+    /// i.e. merged reverse complemental arcs and collapsed palindromic unitigs.
+    /// Their weight is doubled. The node count in the header is not changed.
+    #[clap(long, conflicts_with = "materialize_mirrors")]
+    only_merged: bool,
+
+    /// Write at most this many outgoing arcs per node, keeping those with the highest weights, e.g. to prune hubs.
+    /// The mirror arcs of dropped arcs are dropped as well, so nodes may keep fewer arcs.
+    /// Two arcs that may be merged with each other count as one.
+    #[clap(long)]
+    max_out_degree: Option<usize>,
+
+    /// Write a comment line `# node <node> out=<number of arcs>` before the arcs of each node with outgoing arcs.
+    #[clap(long, conflicts_with = "max_output_size")]
+    annotate_nodes: bool,
+
+    /// The alphabet of the input sequences.
+    /// RNA sequences are written with `U` in the arc-centric output.
+    #[clap(long, value_enum, default_value_t = Alphabet::Dna)]
+    alphabet: Alphabet,
+
+    /// Additionally render the graph as PNG to this path, using the `dot` command of Graphviz.
+    #[clap(long)]
+    render_png: Option<PathBuf>,
+
+    /// With `--output-format dot` or `--render-png`, wrap each weakly connected component in a cluster,
+    /// such that the components are drawn separately.
+    #[clap(long)]
+    dot_clusters: bool,
+
+    /// The maximum number of nodes of a graph rendered with `--render-png`,
+    /// since rendering large graphs takes very long and produces unreadable images.
+    #[clap(long, default_value_t = 1000)]
+    render_max_nodes: usize,
+
+    /// Log the throughput of the conversion after it finished,
+    /// as arcs per second over the whole conversion, and megabytes per second of reading and writing.
+    #[clap(long)]
+    benchmark: bool,
+
+    /// Write only the arcs that start or end in one of these comma-separated node indices, and their mirror arcs.
+    /// The nodes are renumbered, leaving out nodes without written arcs.
+    #[clap(long, value_delimiter = ',', conflicts_with = "only_nodes_file")]
+    only_nodes: Option<Vec<usize>>,
+
+    /// Log the details of the arcs between these two node indices of the graph at debug level when writing them,
+    /// including the merge decision. The node indices are those before renumbering.
+    #[clap(long, num_args = 2, value_names = ["FROM", "TO"])]
+    trace_edge: Option<Vec<usize>>,
+
+    /// Like `--only-nodes`, but read the node indices from a file, separated by whitespace or commas.
+    #[clap(long)]
+    only_nodes_file: Option<PathBuf>,
+
+    /// Write only the arcs that cross between the nodes below this index and the other nodes, and their mirror arcs.
+    /// The nodes are not renumbered, and the number of written arcs is logged as size of the cut.
+    /// With `--one-based`, the index is one-based as well.
+    #[clap(long, conflicts_with = "preserve_original_ids")]
+    cut_at: Option<usize>,
+
+    /// Log this many arcs with the highest and with the lowest weights after writing, e.g. to spot sequencing artifacts.
+    /// The weights are reported before log-scaling, with the start of the arc sequences.
+    #[clap(long)]
+    abundance_outliers: Option<usize>,
+
+    /// Write the arcs that are their own reverse complement to this file, one `<from> <to> <sequence>` line per arc.
+    /// These are the palindromic arcs that are merged with their mirror arc, and hence get twice the weight.
+    #[clap(long)]
+    self_complemental_report: Option<PathBuf>,
+
+    /// Write only the arcs whose sequence matches this regular expression, and their mirror arcs.
+    /// The nodes are not renumbered.
+    /// The pattern is matched against the upper case DNA sequence of each arc and its reverse complement,
+    /// before trimming overlaps or translating the alphabet.
+    /// Since each sequence and its reverse complement are copied out of the sequence store for each match,
+    /// this slows down writing considerably for large graphs.
+    #[clap(long)]
+    filter_sequence_regex: Option<String>,
+
+    /// The source node of the maximum flow problem written with `--output-format dimacs`.
+    #[clap(long, requires = "sink")]
+    source: Option<usize>,
+
+    /// The sink node of the maximum flow problem written with `--output-format dimacs`.
+    #[clap(long, requires = "source")]
+    sink: Option<usize>,
+
+    /// Annotate each arc with the fraction of `G` and `C` in its written sequence, as tag `GC:f:<fraction>`.
+    #[clap(long)]
+    emit_gc: bool,
+
+    /// Annotate each arc with a hash of the lexicographically smaller of its sequence and its reverse complement,
+    /// as tag `AH:Z:<hex>`. The hash is the 64-bit FNV-1a hash, so it is the same for the same unitig in any file,
+    /// and the same for an arc and its reverse complemental mirror arc.
+    #[clap(long)]
+    emit_arc_hash: bool,
+
+    /// The line endings of the input. Carriage returns before line feeds are removed with `crlf`.
+    #[clap(long, value_enum, default_value_t = InputEncoding::Auto)]
+    input_encoding: InputEncoding,
+
+    /// Log histograms of the out-degrees of the nodes and of the sequence lengths and weights of the arcs,
+    /// and the numbers of self-complemental, palindromic and merged arcs.
+    /// Only available for the arc-centric format.
+    #[clap(long)]
+    stats: bool,
+
+    /// The maximum number of bins of the histogram of the arc weights logged with `--stats`.
+    #[clap(long, default_value_t = 10, requires = "stats")]
+    abundance_histogram_bins: usize,
+
+    /// How to divide the range of the arc weights into the bins of the histogram logged with `--stats`.
+    #[clap(long, value_enum, default_value_t = HistogramScale::Linear, requires = "stats")]
+    abundance_histogram_scale: HistogramScale,
+
+    /// Abort if the memory usage exceeds this many MiB.
+    /// The memory usage is checked only at a few points during the conversion,
+    /// and only on Linux with the `memory-metering` feature.
+    #[clap(long)]
+    memory_limit: Option<usize>,
+
+    /// Abort with exit code 124 if the conversion takes longer than this many seconds.
+    /// The elapsed time is checked after reading the graph and after each node while writing.
+    /// Without `--atomic-output`, the output written so far is kept.
+    #[clap(long)]
+    timeout: Option<f64>,
+
+    /// While writing the arc-centric format, report the memory usage when the number of processed nodes
+    /// reaches each of these comma-separated counts, e.g. to relate the memory usage to the progress.
+    #[clap(long, value_delimiter = ',')]
+    report_at: Option<Vec<usize>>,
+
+    /// Annotate arcs with external node labels from a file with lines `<node><TAB><label>`,
+    /// where the nodes are the node indices of the output without renumbering.
+    /// The labels of the first and second node of an arc are written as tags `FL:Z:<label>` and `TL:Z:<label>`,
+    /// and left out for nodes without label.
+    #[clap(long)]
+    include_node_labels: Option<PathBuf>,
+
+    /// Name the nodes by external names from a file with lines `<node><TAB><name>`,
+    /// where the nodes are the node indices of the output without renumbering.
+    /// Fails if a node has no name, unless `--allow-unmapped` is given.
+    #[clap(long, conflicts_with_all = ["preserve_original_ids", "drop_isolated_nodes", "id_encoding", "one_based", "validate_output"])]
+    relabel_from_mapping: Option<PathBuf>,
+
+    /// Name nodes without a name in `--relabel-from-mapping` by their index.
+    #[clap(long, requires = "relabel_from_mapping")]
+    allow_unmapped: bool,
+
+    /// Do not warn about non-integer average abundances of the unitigs with the sequences listed in this file,
+    /// separated by whitespace. A unitig is also matched by its reverse complement.
+    #[clap(long)]
+    suppress_warning_sequences: Option<PathBuf>,
+
+    /// The case of the written sequences.
+    #[clap(long, value_enum, default_value_t = SequenceCase::Preserve)]
+    sequence_case: SequenceCase,
+
+    /// Annotate each arc with the length of the overlap with its successors, as tag `OV:i:<length>`.
+    /// In a de Bruijn graph, this is always `k-1`.
+    /// Together with `--trim-overlap`, the overlap is still the overlap of the untrimmed sequences.
+    #[clap(long)]
+    emit_overlap: bool,
+
+    /// Annotate each arc with the orientation of its sequence relative to the unitig in the input,
+    /// as tag `OR:A:+` if it is the unitig sequence and `OR:A:-` if it is the reverse complement.
+    #[clap(long)]
+    emit_orientation: bool,
+
+    /// Annotate each arc with the offset of its sequence in the sequence of the unsplit arc, as tag `SO:i:<offset>`.
+    /// The offset is zero unless the arc is a piece of a longer arc split by `--max-sequence-length`.
+    #[clap(long)]
+    emit_source_offset: bool,
+
+    /// Write the two nodes of each arc in increasing order, e.g. to collapse the arcs into undirected edges.
+    /// The direction is written as tag `DI:A:+` if the arc goes from the first to the second node,
+    /// and `DI:A:-` if it goes from the second to the first node.
+    #[clap(long, conflicts_with_all = ["preserve_original_ids", "relabel_from_mapping"])]
+    canonical_node_pairs: bool,
+
+    /// Only check that the input can be read with the given k, without converting it or writing anything.
+    #[clap(long)]
+    parse_only: bool,
+
+    /// Only write the multiplicity of each canonical k-mer of the input to this file, instead of converting it,
+    /// as lines `<k-mer> <multiplicity>` in lexicographic order.
+    /// The multiplicity of the k-mers of a unitig is its mean abundance, rounded down.
+    #[clap(long, conflicts_with_all = ["output", "parse_only"])]
+    count_kmers_only: Option<PathBuf>,
+
+    /// Only check that merging reverse complemental arcs is stable, instead of writing the converted graph.
+    /// The input is converted with the default options, and once more with `--materialize-mirrors`,
+    /// and the outputs are read back. Merging the materialized arcs must result in the merged output,
+    /// which must not contain arcs that can be merged again.
+    #[clap(long, conflicts_with_all = ["output", "parse_only", "count_kmers_only"])]
+    self_test: bool,
+
+    /// Split each arc with a sequence longer than this into a chain of arcs with sequences of at most this length,
+    /// overlapping by `k-1` characters.
+    /// The intermediate nodes of the chains get new node indices after the existing nodes.
+    /// The pieces keep the weight and the tags of the split arc.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "emit_gc", "emit_arc_hash", "resume_from_node",
+        "annotate_nodes",
+    ])]
+    max_sequence_length: Option<usize>,
+
+    /// Collapse each chain of arcs through nodes with exactly one incoming and one outgoing arc into a single arc,
+    /// e.g. if the input unitigs are not maximal. The sequences are concatenated, overlapping by `k-1` characters,
+    /// and the weight is the average weight of the chain, weighted by the number of k-mers of each arc.
+    /// The remaining nodes get new consecutive node indices.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "emit_gc", "emit_arc_hash", "resume_from_node",
+        "annotate_nodes",
+    ])]
+    collapse_linear_paths: bool,
+
+    /// Merge all nodes that are the same (k-1)-mer, e.g. if the input contains duplicate unitig records
+    /// that are not linked to the same neighbors. The arcs are kept with their endpoints remapped,
+    /// and the remaining nodes get new consecutive node indices.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    deduplicate_nodes_by_sequence: bool,
+
+    /// Expand each arc into a chain of arcs with one k-mer each, such that the output is the arc-centric graph
+    /// of the k-mers instead of the unitigs. This is `--max-sequence-length k`,
+    /// but fails if the expanded graph may have more than `--expand-kmers-max-nodes` nodes.
+    #[clap(long, conflicts_with_all = [
+        "max_sequence_length", "float_weights", "preserve_original_ids", "trim_overlap", "emit_gc", "emit_arc_hash",
+        "resume_from_node", "annotate_nodes",
+    ])]
+    expand_kmers: bool,
+
+    /// Leave out the arc sequences in the adjacency-list format.
+    #[clap(long)]
+    no_sequences: bool,
+
+    /// Abort if the line graph has more than this many arcs.
+    /// The line graph has an arc for each pair of an incoming and an outgoing arc of each node.
+    #[clap(long, default_value_t = line_graph::DEFAULT_MAX_ARCS)]
+    line_graph_max_arcs: usize,
+
+    /// The maximum number of nodes of a graph expanded with `--expand-kmers`,
+    /// since each unitig with `n` k-mers gets `n - 1` new nodes.
+    #[clap(long, default_value_t = 1_000_000)]
+    expand_kmers_max_nodes: usize,
+
+    /// Write only the first this many arcs, for quick experiments on a sample of the graph.
+    /// The mirror arcs of the written arcs are written as well, even if they come later,
+    /// such that no pair of mirror arcs is cut in half. Therefore, one arc more than the limit may be written.
+    /// The node count in the header is not changed. The conversion stops once all these arcs are written,
+    /// and transformations of the output like `--sample-arcs` are applied to the written arcs.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    limit_arcs: Option<usize>,
+
+    /// Write each arc together with its mirror arc with this probability, for experiments on a random sample of the graph.
+    /// The node count in the header is not changed.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    sample_arcs: Option<f64>,
+
+    /// Write the transpose graph, where each arc goes in the opposite direction and has the reverse complement sequence.
+    /// The node count in the header is not changed.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    transpose: bool,
+
+    /// Instead of the graph, write a patch against this previous arc-centric output, e.g. to track the graph across
+    /// assembly iterations. The first line of the patch is the new node count, followed by one line per changed arc:
+    /// `- <arc>` for removed arcs, `+ <arc>` for added arcs, and `~ <weight> <arc>` for arcs of the previous output
+    /// that only differ in their weight.
+    #[clap(long, conflicts_with_all = [
+        "validate_output", "strict_bigraph_symmetry", "preserve_original_ids", "shards", "max_output_size",
+        "split_by_strand", "mirror_index", "lengths_file", "global_sort", "buffered_sort",
+    ])]
+    base: Option<PathBuf>,
+
+    /// Write only the weakly connected component with the most arcs, e.g. to filter out contamination.
+    /// The component includes its reverse complemental strand, and its nodes get new consecutive node indices.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    largest_component_only: bool,
+
+    /// Write only the arcs that join two connected components when joining the arcs in order,
+    /// together with their mirror arcs, e.g. for a minimal view of the connectivity.
+    /// A node and its reverse complement count as one node, so this is a spanning forest of the bidirected graph.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    spanning_tree_only: bool,
+
+    /// Log the simple bubbles of the graph, i.e. parallel arcs between the same pair of nodes,
+    /// with the weights of their branches. The bubbles are searched after `--collapse-linear-paths`.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    report_bubbles: bool,
+
+    /// Remove all branches but the one with the highest weight from each simple bubble,
+    /// together with their mirror arcs. The bubbles are popped after `--collapse-linear-paths`.
+    #[clap(long, conflicts_with_all = [
+        "float_weights", "preserve_original_ids", "trim_overlap", "resume_from_node", "annotate_nodes",
+    ])]
+    pop_bubbles: bool,
+
+    /// The seed for all random sampling. If not given, then a random seed is generated and logged.
+    #[clap(long)]
+    seed: Option<u64>,
+
+    /// Sort all arcs by their first node, second node and sequence, instead of writing them grouped by node.
+    /// This buffers the whole output in memory, which needs about as much memory as the size of the output.
+    #[clap(long, conflicts_with_all = ["annotate_nodes", "resume_from_node"])]
+    global_sort: bool,
+
+    /// Sort the arcs like `--global-sort`, but with an external merge sort that buffers
+    /// at most `--sort-memory-budget` bytes of arcs, and spills sorted runs into `--sort-tmp-dir`.
+    #[clap(long, requires = "sort_tmp_dir", conflicts_with_all = ["global_sort", "annotate_nodes", "resume_from_node"])]
+    buffered_sort: bool,
+
+    /// The directory for the temporary files of `--buffered-sort`.
+    #[clap(long)]
+    sort_tmp_dir: Option<PathBuf>,
+
+    /// The number of bytes of arcs that `--buffered-sort` buffers before spilling them into a temporary file.
+    #[clap(long, default_value_t = 256 * 1024 * 1024)]
+    sort_memory_budget: usize,
+
+    /// On receiving `SIGUSR1`, log the number of nodes written so far and the memory usage, without stopping.
+    /// Only supported on Unix.
+    #[clap(long)]
+    report_on_signal: bool,
+
+    /// Reserve this many bytes for the output file before writing, to reduce fragmentation.
+    /// The file is truncated to the written size afterwards, so the estimate does not need to be exact.
+    #[clap(long, conflicts_with_all = ["max_output_size", "shards", "resume_from_node"])]
+    preallocate: Option<u64>,
+
+    /// Write the output to `<output>.tmp` first, and rename it to the output path only after writing succeeded,
+    /// such that a partial output is never mistaken for a complete one. On failure, the temporary file is removed.
+    #[clap(long, conflicts_with_all = ["max_output_size", "shards", "resume_from_node"])]
+    atomic_output: bool,
+
+    /// Write the output through a memory mapping of the output file instead of buffered writes.
+    /// The file is mapped with the size given by `--preallocate`, and mapped again with twice the size when it is full.
+    /// Only supported on Linux.
+    #[clap(long, conflicts_with_all = ["max_output_size", "shards", "resume_from_node"])]
+    mmap_output: bool,
+
+    /// Write the output in a separate thread, such that the next batch of output is formatted
+    /// while the previous batch is written to disk.
+    #[clap(long, conflicts_with_all = [
+        "mmap_output", "max_output_size", "shards", "split_by_strand", "input_glob",
+    ])]
+    parallel_io: bool,
+
+    /// Write the resolved configuration to this file in the TOML format,
+    /// with the keys named like the options and the defaults, the k-mer size and the log level filled in.
+    /// The configuration is logged in any case.
+    #[clap(long)]
+    config_out: Option<PathBuf>,
+
+    /// Write a JSON manifest to this file, listing each produced file with its role, size and CRC32 checksum,
+    /// e.g. to find all parts or shards of the output.
+    #[clap(long)]
+    manifest: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Compare two graphs in the arc-centric format.
+    /// Arcs and their reverse complements are compared as a unit.
+    /// Prints a summary, and with `--details` one line per difference.
+    /// Graphs in files ending in `.pb` are read in the protobuf format.
+    Diff {
+        first: PathBuf,
+        second: PathBuf,
+
+        /// Print each difference.
+        #[clap(long)]
+        details: bool,
+
+        /// Read node indices starting at one, as written with `--one-based`.
+        #[clap(long)]
+        one_based: bool,
+    },
+    /// Convert a graph in the arc-centric format into another output format, without going back to bcalm2.
+    /// Graphs in files ending in `.pb` are read in the protobuf format.
+    Reformat {
+        input: PathBuf,
+        output: PathBuf,
+
+        /// The format of the output.
+        /// The bcalm2 format is not supported, since it cannot be reconstructed from the arcs.
+        #[clap(long, value_enum, default_value_t = OutputFormat::ArcCentric)]
+        output_format: OutputFormat,
+
+        /// The k-mer size of the graph, required for the formats that write the overlaps between arcs.
+        #[clap(short)]
+        k: Option<usize>,
+
+        /// Read node indices starting at one, as written with `--one-based`.
+        /// The output is always written with zero-based node indices.
+        #[clap(long)]
+        one_based: bool,
+    },
+}
+
+/// The formats in which the converted graph can be written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// An edge list preceded by the number of nodes, as described in the README.
+    #[default]
+    ArcCentric,
+    /// The bcalm2 fasta format, where each pair of reverse complemental arcs becomes a unitig record,
+    /// and `L:` links connect it to the arcs that follow it in the arc-centric graph.
+    Bcalm2,
+    /// A square matrix of arc weights, see `--matrix-max-nodes`.
+    Matrix,
+    /// GFA version 1, with a segment for each pair of reverse complemental arcs.
+    Gfa1,
+    /// The Graphviz DOT format, with each arc labelled by its weight.
+    Dot,
+    /// Length-delimited protobuf messages as defined in `proto/arc_record.proto`,
+    /// a header with the node count followed by one message per arc.
+    Protobuf,
+    /// The METIS graph format for graph partitioners, as undirected graph with summed arc weights as edge weights.
+    Metis,
+    /// The GFA1 layout written by Bifrost 1.3, with segments numbered from one.
+    Bifrost,
+    /// The GFA1 layout read by SPAligner and PathRacer of SPAdes 3.15, with k-mer counts and simple paths.
+    Spaligner,
+    /// The Newick format, with one tree per line, if the graph is a forest when ignoring the directions of the arcs.
+    Newick,
+    /// A DIMACS maximum flow problem with the arc weights as capacities, see `--source` and `--sink`.
+    Dimacs,
+    /// A parquet file with one row per arc and the columns of the arc-centric format, without tags and colors.
+    Parquet,
+    /// A single JSON object with the node count and an array of arcs with the columns of the arc-centric format and tags.
+    Json,
+    /// One line `<node>: <neighbor> <weight> <sequence> ...` per node, listing the arcs leaving it.
+    /// The sequences are left out with `--no-sequences`.
+    AdjacencyList,
+    /// The line graph in the arc-centric format, with a node for each arc, see `--line-graph-max-arcs`.
+    LineGraph,
+    /// A binary compressed sparse row layout for memory mapping, with the weights and sequences of the arcs,
+    /// but without mirror arcs, colors and tags. The layout is documented in `src/csr_output.rs`.
+    Csr,
+}
+
+/// Options that modify how a graph is converted.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionOptions {
+    /// The format of the output.
+    pub output_format: OutputFormat,
+    /// The version of the layout of the arc-centric format.
+    pub format_version: FormatVersion,
+    /// Cross-check the `L:` links of the input records against the edges of the constructed graph.
+    pub verify_links: bool,
+    /// Check that the links of the input records imply overlaps of k-1 characters.
+    pub validate_k_consistency: bool,
+    /// Check that the ids of the input records are increasing.
+    pub verify_input_sorted: bool,
+    /// Write the edges of the graph to this file right after reading it.
+    pub dump_intermediate_graph: Option<PathBuf>,
+    /// Write the coverage along the reference order of unitigs as bedGraph track to the path.
+    pub coverage_track: Option<(PathBuf, ReferenceOrder)>,
+    /// Abort instead of warning about input records that are out of order.
+    pub strict: bool,
+    /// Emit palindromic unitigs only once, regardless of the order of their arcs.
+    pub collapse_palindromic_unitigs: bool,
+    /// Skip unitigs shorter than k instead of reporting an error.
+    pub skip_short: bool,
+    /// Report an error if no arcs are written.
+    pub require_nonempty: bool,
+    /// Skip unitigs with less than this many k-mers.
+    pub min_unitig_kmers: usize,
+    /// Name nodes by the ends of the input unitigs they are attached to.
+    pub preserve_original_ids: bool,
+    /// Collapse runs of identical characters in the unitig sequences before constructing the graph.
+    pub compress_homopolymers: bool,
+    /// Log and skip input records that the bcalm2 reader would reject.
+    pub skip_bad_records: bool,
+    /// Remove nonstandard header tags from the input.
+    pub strip_tags: bool,
+    /// The name of the header tag with the mean abundance.
+    pub abundance_tag: Option<String>,
+    /// Release data that is not needed for writing before writing.
+    pub streaming: bool,
+    /// Check the reverse complements of the sequences when writing.
+    pub verify_revcomp: bool,
+    /// Carry the colors of multi-sample coloured de Bruijn graphs from the input to the output.
+    pub colors: bool,
+    /// Log a progress message every this many nodes while writing. Disabled if zero.
+    pub progress_interval: usize,
+    /// The maximum number of nodes of a graph written as matrix. Unlimited if `None`.
+    pub matrix_max_nodes: Option<usize>,
+    /// Store identical unitig sequences only once while reading.
+    pub dedup_sequences: bool,
+    /// Never merge an arc with its reverse complement, such that each arc has a separate reciprocal record.
+    pub materialize_mirrors: bool,
+    /// Wrap the sequences of the bcalm2 format after this many characters, if positive.
+    pub fasta_line_width: usize,
+    /// The maximum number of mismatches between the sequences of merged reverse complemental arcs.
+    pub merge_threshold: usize,
+    /// Merge parallel arcs with equal written sequences, adding their weights.
+    pub merge_by_sequence_only: bool,
+    /// Omit the first k-1 characters of each arc sequence.
+    pub trim_overlap: bool,
+    /// Check that each edge of the constructed bigraph has a mirror edge.
+    pub assert_balanced: bool,
+    /// Check that the two edges of each unitig have the same total abundance.
+    pub verify_abundance_conservation: bool,
+    /// Log whether the converted graph has an Eulerian circuit or path.
+    pub check_eulerian: bool,
+    /// Print the nodes and arcs of small graphs to standard error.
+    pub ascii_preview: bool,
+    /// Renumber the nodes, leaving out nodes without arcs.
+    pub drop_isolated_nodes: bool,
+    /// How to compute the weight of an arc from the abundance of its unitig.
+    pub weight_metric: WeightMetric,
+    /// Write weights as floats with the given number of decimal places instead of rounding them down to integers.
+    pub float_weights: Option<usize>,
+    /// Divide the float weights by the maximum weight of all written arcs.
+    pub normalize_weights: bool,
+    /// Write weights log-scaled with the given factor.
+    pub log_weight_scale: Option<f64>,
+    /// Skip the header and the arcs of all nodes before this node index.
+    pub resume_from_node: Option<usize>,
+    /// Process the nodes in descending order of node index.
+    pub reverse_order: bool,
+    /// Write the arcs of the nodes in a random order of the nodes, seeded by `seed`.
+    pub shuffle_nodes: bool,
+    /// Report the memory usage when the number of processed nodes reaches one of these counts.
+    pub report_at: HashSet<usize>,
+    /// Write this node count into the header.
+    pub node_count: Option<usize>,
+    /// How to determine the node count in the header.
+    pub header_node_count: HeaderNodeCount,
+    /// How to write the node indices.
+    pub id_encoding: IdEncoding,
+    /// Write node indices starting at one.
+    pub one_based: bool,
+    /// Write maximal non-branching walks as paths in the GFA output.
+    pub gfa_emit_paths: bool,
+    /// Write only arcs that were merged with their reverse complement.
+    pub only_merged: bool,
+    /// Write at most this many outgoing arcs per node with the highest weights, dropping the others and their mirrors.
+    pub max_out_degree: Option<usize>,
+    /// Write a comment line with the number of outgoing arcs before the arcs of each node.
+    pub annotate_nodes: bool,
+    /// The alphabet of the input sequences.
+    /// With [`Alphabet::Auto`], it is detected while reading.
+    pub alphabet: Alphabet,
+    /// Render the graph as PNG to this path.
+    pub render_png: Option<PathBuf>,
+    /// Wrap each weakly connected component in a cluster in the DOT output.
+    pub dot_clusters: bool,
+    /// The maximum number of nodes of a graph rendered as PNG. Unlimited if `None`.
+    pub render_max_nodes: Option<usize>,
+    /// Write only the arcs incident to these nodes, and their mirror arcs, renumbering the nodes.
+    pub only_nodes: Option<HashSet<usize>>,
+    /// Log the details of the arcs between these nodes when writing them.
+    pub trace_edge: Option<(usize, usize)>,
+    /// Write only the arcs crossing between the nodes below this index and the other nodes, and their mirror arcs.
+    /// The index is zero-based, even if `one_based` is set.
+    pub cut_at: Option<usize>,
+    /// Log this many arcs with the highest and with the lowest weights after writing.
+    pub abundance_outliers: Option<usize>,
+    /// Write the palindromic arcs that are merged with their mirror arc to this file.
+    pub self_complemental_report: Option<PathBuf>,
+    /// Write only the arcs whose sequence or reverse complement matches this pattern, and their mirror arcs.
+    pub sequence_regex: Option<Regex>,
+    /// The source and sink nodes of the maximum flow problem of the dimacs format.
+    pub flow_terminals: Option<(usize, usize)>,
+    /// Annotate each arc with the GC content of its sequence.
+    pub emit_gc: bool,
+    /// Annotate each arc with a hash of its canonical sequence.
+    pub emit_arc_hash: bool,
+    /// The line endings of the input.
+    pub input_encoding: InputEncoding,
+    /// External labels of nodes, indexed by node index.
+    pub node_labels: Option<HashMap<usize, String>>,
+    /// External names of nodes that replace their indices, indexed by node index.
+    pub node_mapping: Option<HashMap<usize, String>>,
+    /// Name nodes without external name by their index instead of failing.
+    pub allow_unmapped: bool,
+    /// The sequences of unitigs for which no warning about non-integer average abundances is logged.
+    pub suppressed_warning_sequences: Option<HashSet<String>>,
+    /// The case of the written sequences.
+    pub sequence_case: SequenceCase,
+    /// Annotate each arc with the length of its overlap with its successors.
+    pub emit_overlap: bool,
+    /// Annotate each arc with whether its sequence is the forwards or reverse complemental unitig sequence.
+    pub emit_orientation: bool,
+    /// Annotate each arc with the offset of its sequence in the sequence of the unsplit arc.
+    pub emit_source_offset: bool,
+    /// Write the nodes of each arc in increasing order, with the direction as tag.
+    pub canonical_node_pairs: bool,
+    /// Split arcs with longer sequences into chains of arcs.
+    pub max_sequence_length: Option<usize>,
+    /// Collapse chains of arcs through nodes with one incoming and one outgoing arc.
+    pub collapse_linear_paths: bool,
+    /// Merge all nodes that are the same (k-1)-mer.
+    pub deduplicate_nodes_by_sequence: bool,
+    /// Split all arcs into arcs with one k-mer each.
+    pub expand_kmers: bool,
+    /// The maximum number of nodes of the graph after expanding the k-mers.
+    pub expand_kmers_max_nodes: usize,
+    /// Leave out the arc sequences in the adjacency-list format.
+    pub no_sequences: bool,
+    /// The maximum number of arcs of the line graph.
+    pub line_graph_max_arcs: usize,
+    /// Write only the first arcs and their mirrors.
+    pub limit_arcs: Option<usize>,
+    /// Write each pair of mirror arcs with this probability.
+    pub sample_arcs: Option<f64>,
+    /// Reverse the direction of all arcs.
+    pub transpose: bool,
+    /// Write a patch against this graph instead of the graph.
+    pub base: Option<ArcCentricGraph>,
+    /// Write only the weakly connected component with the most arcs.
+    pub largest_component_only: bool,
+    /// Keep only the arcs of a spanning forest, together with their mirror arcs.
+    pub spanning_tree_only: bool,
+    /// Log the simple bubbles of the graph.
+    pub report_bubbles: bool,
+    /// Remove all but the heaviest branch of each simple bubble.
+    pub pop_bubbles: bool,
+    /// The seed for random sampling.
+    pub seed: Option<u64>,
+    /// Sort all arcs globally instead of grouping them by node.
+    pub global_sort: bool,
+    /// Sort the arcs globally with an external merge sort, spilling sorted runs into this directory.
+    pub buffered_sort: Option<PathBuf>,
+    /// The number of bytes of arcs buffered before spilling a sorted run.
+    pub sort_memory_budget: usize,
+    /// Log a snapshot of the progress and the memory usage while writing, whenever this is triggered.
+    pub snapshot_request: Option<SnapshotRequest>,
+    /// Abort the conversion when this timeout is exceeded.
+    pub timeout: Option<Timeout>,
+    /// Count the k-mer spectrum of the input graph into the report.
+    pub kmer_spectrum: bool,
+}
+
+impl ConversionOptions {
+    /// True if the arc-centric output is read back and transformed before it is written.
+    fn is_post_processed(&self) -> bool {
+        self.deduplicate_nodes_by_sequence
+            || self.collapse_linear_paths
+            || self.max_sequence_length.is_some()
+            || self.sample_arcs.is_some()
+            || self.transpose
+            || self.largest_component_only
+            || self.spanning_tree_only
+            || self.report_bubbles
+            || self.pop_bubbles
+    }
+}
+
+impl From<&Cli> for ConversionOptions {
+    fn from(cli: &Cli) -> Self {
+        Self {
+            output_format: cli.output_format,
+            format_version: cli.format_version,
+            verify_links: cli.verify_links,
+            validate_k_consistency: cli.validate_k_consistency,
+            verify_input_sorted: cli.verify_input_sorted,
+            dump_intermediate_graph: cli.dump_intermediate_graph.clone(),
+            coverage_track: None,
+            strict: cli.strict,
+            collapse_palindromic_unitigs: cli.collapse_palindromic_unitigs,
+            skip_short: cli.skip_short,
+            require_nonempty: cli.require_nonempty,
+            min_unitig_kmers: cli.min_unitig_kmers,
+            preserve_original_ids: cli.preserve_original_ids,
+            compress_homopolymers: cli.compress_homopolymers,
+            skip_bad_records: cli.skip_bad_records,
+            strip_tags: cli.strip_tags,
+            abundance_tag: cli.abundance_tag.clone(),
+            streaming: cli.streaming,
+            verify_revcomp: cli.verify_revcomp,
+            colors: cli.colors,
+            progress_interval: cli.progress_interval,
+            matrix_max_nodes: Some(cli.matrix_max_nodes),
+            dedup_sequences: cli.dedup_sequences,
+            materialize_mirrors: cli.materialize_mirrors,
+            fasta_line_width: cli.fasta_line_width,
+            merge_threshold: cli.merge_threshold,
+            merge_by_sequence_only: cli.merge_by_sequence_only,
+            trim_overlap: cli.trim_overlap,
+            assert_balanced: cli.assert_balanced,
+            verify_abundance_conservation: cli.verify_abundance_conservation,
+            check_eulerian: cli.check_eulerian,
+            ascii_preview: cli.ascii_preview,
+            drop_isolated_nodes: cli.drop_isolated_nodes,
+            weight_metric: if cli.normalize_abundance_by_length {
+                WeightMetric::MeanAbundancePerBase
+            } else {
+                cli.weight
+            },
+            float_weights: cli.float_weights.then_some(cli.weight_precision),
+            normalize_weights: cli.normalize_weights,
+            log_weight_scale: cli.log_weights.then_some(cli.log_weight_scale),
+            resume_from_node: cli.resume_from_node,
+            reverse_order: cli.reverse_order,
+            shuffle_nodes: cli.shuffle_nodes,
+            report_at: cli.report_at.iter().flatten().copied().collect(),
+            node_count: cli.node_count,
+            header_node_count: cli.header_node_count,
+            id_encoding: cli.id_encoding,
+            one_based: cli.one_based,
+            gfa_emit_paths: cli.gfa_emit_paths,
+            only_merged: cli.only_merged,
+            max_out_degree: cli.max_out_degree,
+            annotate_nodes: cli.annotate_nodes,
+            alphabet: cli.alphabet,
+            render_png: cli.render_png.clone(),
+            dot_clusters: cli.dot_clusters,
+            render_max_nodes: Some(cli.render_max_nodes),
+            only_nodes: cli
+                .only_nodes
+                .as_ref()
+                .map(|nodes| nodes.iter().copied().collect()),
+            trace_edge: cli.trace_edge.as_ref().map(|nodes| (nodes[0], nodes[1])),
+            cut_at: cli
+                .cut_at
+                .map(|cut_at| cut_at.saturating_sub(usize::from(cli.one_based))),
+            abundance_outliers: cli.abundance_outliers,
+            self_complemental_report: cli.self_complemental_report.clone(),
+            flow_terminals: cli.source.zip(cli.sink),
+            emit_gc: cli.emit_gc,
+            emit_arc_hash: cli.emit_arc_hash,
+            input_encoding: cli.input_encoding,
+            node_labels: None,
+            node_mapping: None,
+            allow_unmapped: cli.allow_unmapped,
+            suppressed_warning_sequences: None,
+            sequence_regex: None,
+            sequence_case: cli.sequence_case,
+            emit_overlap: cli.emit_overlap,
+            emit_orientation: cli.emit_orientation,
+            emit_source_offset: cli.emit_source_offset,
+            canonical_node_pairs: cli.canonical_node_pairs,
+            max_sequence_length: cli.max_sequence_length,
+            collapse_linear_paths: cli.collapse_linear_paths,
+            deduplicate_nodes_by_sequence: cli.deduplicate_nodes_by_sequence,
+            expand_kmers: cli.expand_kmers,
+            expand_kmers_max_nodes: cli.expand_kmers_max_nodes,
+            no_sequences: cli.no_sequences,
+            line_graph_max_arcs: cli.line_graph_max_arcs,
+            limit_arcs: cli.limit_arcs,
+            transpose: cli.transpose,
+            base: None,
+            largest_component_only: cli.largest_component_only,
+            spanning_tree_only: cli.spanning_tree_only,
+            report_bubbles: cli.report_bubbles,
+            pop_bubbles: cli.pop_bubbles,
+            sample_arcs: cli.sample_arcs,
+            seed: cli.seed,
+            global_sort: cli.global_sort,
+            buffered_sort: cli
+                .buffered_sort
+                .then(|| cli.sort_tmp_dir.clone())
+                .flatten(),
+            sort_memory_budget: cli.sort_memory_budget,
+            snapshot_request: None,
+            timeout: None,
+            kmer_spectrum: cli.emit_spectrum.is_some(),
+        }
+    }
+}
+
+/// The environment variable that can set the log level.
+pub const LOG_LEVEL_VARIABLE: &str = "NODE_TO_ARC_LOG";
+
+/// Initialise logging with the given log level, or the one from the environment variable [`LOG_LEVEL_VARIABLE`],
+/// see [`resolve_log_level`].
+pub fn initialise_logging(log_level: Option<LevelFilter>) {
+    let resolved_log_level = log_level_from_environment(log_level);
+    let effective_log_level = match &resolved_log_level {
+        Ok(log_level) => *log_level,
+        Err(_) => log_level.unwrap_or(LevelFilter::Info),
+    };
+    CombinedLogger::init(vec![TermLogger::new(
+        effective_log_level,
+        Config::default(),
+        TerminalMode::Mixed,
+        ColorChoice::Auto,
+    )])
+    .unwrap();
+
+    info!("Logging initialised successfully");
+    if let Err(error) = resolved_log_level {
+        warn!("Ignoring environment variable {LOG_LEVEL_VARIABLE}: {error}");
+    }
+}
+
+fn log_level_from_environment(log_level: Option<LevelFilter>) -> anyhow::Result<LevelFilter> {
+    resolve_log_level(log_level, std::env::var(LOG_LEVEL_VARIABLE).ok().as_deref())
+}
+
+/// Resolve the log level from the command line and the environment.
+/// The command line takes precedence over the environment, unless the environment value is prefixed with `force:`.
+/// If neither is given, the log level is `Info`.
+pub fn resolve_log_level(
+    command_line: Option<LevelFilter>,
+    environment: Option<&str>,
+) -> anyhow::Result<LevelFilter> {
+    let Some(environment) = environment else {
+        return Ok(command_line.unwrap_or(LevelFilter::Info));
+    };
+    let (force, environment) = match environment.strip_prefix("force:") {
+        Some(environment) => (true, environment),
+        None => (false, environment),
+    };
+    let environment: LevelFilter = environment
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("unknown log level {environment:?}"))?;
+
+    Ok(match command_line {
+        Some(command_line) if !force => command_line,
+        _ => environment,
+    })
+}
+
+pub fn node_to_arc_centric_dbg(
+    k: usize,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<ConversionReport> {
+    node_to_arc_centric_dbg_with_options(k, &ConversionOptions::default(), input, output)
+}
+
+pub fn node_to_arc_centric_dbg_with_options(
+    k: usize,
+    options: &ConversionOptions,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<ConversionReport> {
+    node_to_arc_centric_dbg_with_memory_meter(k, options, input, output, None)
+}
+
+fn node_to_arc_centric_dbg_with_memory_meter(
+    k: usize,
+    options: &ConversionOptions,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    meter: Option<&mut MemoryMeter>,
+) -> anyhow::Result<ConversionReport> {
+    if let Some(base) = &options.base {
+        let mut new_output = Vec::new();
+        let report = convert_graph(k, options, input, &mut new_output, meter)?;
+        let patch = patch_graphs(base, &read_arc_centric_graph(new_output.as_slice())?);
+        info!(
+            "Writing a patch with {} removed, {} added and {} changed arcs",
+            patch.removed.len(),
+            patch.added.len(),
+            patch.changed.len()
+        );
+        write!(output, "{patch}")?;
+        return Ok(report);
+    }
+    if options.format_version == FormatVersion::V1
+        && options.output_format == OutputFormat::ArcCentric
+    {
+        let mut output = Version1Writer::new(output);
+        let report = convert_graph(k, options, input, &mut output, meter)?;
+        output.finish()?;
+        Ok(report)
+    } else {
+        convert_graph(k, options, input, output, meter)
+    }
+}
+
+fn convert_graph(
+    k: usize,
+    options: &ConversionOptions,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    mut meter: Option<&mut MemoryMeter>,
+) -> anyhow::Result<ConversionReport> {
+    let start_time = Instant::now();
+
+    info!("Reading graph");
+    let mut headers = Vec::new();
+    let mut colors = ColorTable::new();
+    let mut malformed_colors = Vec::new();
+    let mut malformed_abundances = Vec::new();
+    let mut previous_id = None;
+    let mut unsorted_ids = Vec::new();
+    let mut skipped_records = 0;
+    let mut alphabet_detector = AlphabetDetector::new(options.alphabet);
+    let mut input_counter = ByteCounter::new(input);
+    let input_encoding = options.input_encoding.detect(&mut input_counter)?;
+    let mut input: Box<dyn BufRead + '_> = Box::new(&mut input_counter);
+    if input_encoding == InputEncoding::Crlf {
+        info!("Converting CRLF line endings");
+        input = Box::new(CrlfNormalizer::new(input));
+    }
+    if options.alphabet != Alphabet::Dna {
+        input = Box::new(RecordTransformer::new(input, |record| {
+            alphabet_detector.transform_record(record)
+        }));
+    }
+    if options.colors {
+        input = Box::new(RecordTransformer::new(input, |record| {
+            extract_record_colors(record, &mut colors, &mut malformed_colors)
+        }));
+    }
+    if let Some(abundance_tag) = &options.abundance_tag {
+        input = Box::new(RecordTransformer::new(input, |record| {
+            apply_abundance_tag(record, abundance_tag, k, &mut malformed_abundances)
+        }));
+    }
+    if options.strip_tags {
+        input = Box::new(RecordTransformer::new(input, strip_record_tags));
+    }
+    if options.compress_homopolymers {
+        input = Box::new(RecordTransformer::new(input, compress_record_homopolymers));
+    }
+    if options.skip_bad_records {
+        input = Box::new(RecordTransformer::new(input, |record| {
+            if let Err(problem) = check_record(record) {
+                warn!("Skipping malformed record >{}: {problem}", record.header);
+                skipped_records += 1;
+                false
+            } else {
+                true
+            }
+        }));
+    }
+    if options.validate_k_consistency {
+        // Inconsistent links can make reading the graph fail, so they are checked before.
+        let mut buffer = Vec::new();
+        input.read_to_end(&mut buffer)?;
+        let mut record_ends = Vec::new();
+        std::io::copy(
+            &mut RecordTransformer::new(buffer.as_slice(), |record| {
+                record_ends.extend(RecordEnds::new(record, k));
+                true
+            }),
+            &mut std::io::sink(),
+        )?;
+        validate_k_consistency(&record_ends, k, options.strict)?;
+        input = Box::new(Cursor::new(buffer));
+    }
+    if options.verify_links {
+        input = Box::new(HeaderInspector::new(input, |header| {
+            headers.extend(Bcalm2Header::parse(header))
+        }));
+    }
+    if options.verify_input_sorted {
+        input = Box::new(HeaderInspector::new(input, |header| {
+            if let Some(Bcalm2Header { id, .. }) = Bcalm2Header::parse(header) {
+                if let Some(previous_id) = previous_id.filter(|&previous_id| previous_id >= id) {
+                    unsorted_ids.push((previous_id, id));
                 }
-                assert_eq!(
-                    count, actual_count,
-                    "kmer {kmer} should match {count} times but matches {actual_count} times"
-                );
+                previous_id = Some(id);
             }
+        }));
+    }
+    let mut deduplicated_sequences = 0;
+    let (mut graph, sequence_store) = if options.dedup_sequences {
+        let mut sequence_store = DedupSequenceStore::new();
+        let graph = read_graph(input, &mut sequence_store, k)?;
+        let statistics = sequence_store.statistics();
+        deduplicated_sequences = statistics.duplicate_sequences();
+        if let Some(meter) = meter.as_mut() {
+            meter.report_deduplication(&statistics)?;
+        } else {
+            info!("Sequence deduplication {statistics}");
+        }
+        (graph, sequence_store.into_inner())
+    } else {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::new();
+        let graph = read_graph(input, &mut sequence_store, k)?;
+        (graph, sequence_store)
+    };
+    if let Some(timeout) = &options.timeout {
+        timeout.check()?;
+    }
+    if let Some(path) = &options.dump_intermediate_graph {
+        info!("Writing the graph as read to {path:?}");
+        let file = File::create(path).with_context(|| format!("Could not create {path:?}"))?;
+        let mut output = BufWriter::new(file);
+        write_intermediate_graph(&graph, &sequence_store, &mut output)?;
+        output.flush()?;
+    }
+    if let Some((path, reference_order)) = &options.coverage_track {
+        info!(
+            "Writing the coverage along {} unitigs to {path:?}",
+            reference_order.unitigs.len()
+        );
+        let file = File::create(path).with_context(|| format!("Could not create {path:?}"))?;
+        let mut output = BufWriter::new(file);
+        write_coverage_track(&graph, &sequence_store, k, reference_order, &mut output)?;
+        output.flush()?;
+    }
+    if let Some(header) = malformed_colors.first() {
+        bail!(
+            "Found {} records with malformed colors, e.g. >{header}",
+            malformed_colors.len()
+        );
+    }
+    if let Some((previous_id, id)) = unsorted_ids.first() {
+        let message = format!(
+            "Found {} input records whose id is not larger than the id of the previous record, e.g. {id} after {previous_id}",
+            unsorted_ids.len()
+        );
+        if options.strict {
+            bail!("{message}");
+        }
+        warn!("{message}");
+    }
+    if skipped_records > 0 {
+        warn!("Skipped {skipped_records} malformed records");
+    }
+    if let Some(header) = malformed_abundances.first() {
+        bail!(
+            "Found {} records without a valid abundance tag {:?}, e.g. >{header}",
+            malformed_abundances.len(),
+            options.abundance_tag.as_deref().unwrap_or_default()
+        );
+    }
+    let alphabet = alphabet_detector.finish()?;
+    if options.alphabet == Alphabet::Auto {
+        info!("Detected alphabet {alphabet:?}");
+    }
+    let options = &ConversionOptions {
+        alphabet,
+        max_sequence_length: if options.expand_kmers {
+            Some(k)
+        } else {
+            options.max_sequence_length
+        },
+        ..options.clone()
+    };
+
+    if options.verify_links {
+        info!("Verifying links of {} records", headers.len());
+        let mismatches = link_verification::verify_links(&graph, &headers);
+        for mismatch in &mismatches {
+            warn!("Inconsistent link: {mismatch}");
+        }
+        if !mismatches.is_empty() {
+            bail!("Found {} inconsistent links", mismatches.len());
+        }
+    }
+
+    if options.assert_balanced {
+        assert_balanced(&graph)?;
+    }
+    if options.verify_abundance_conservation {
+        verify_abundance_conservation(&graph)?;
+    }
+    let read_duration = start_time.elapsed();
+    info!(
+        "Finished graph reading in {:.2?}: {} nodes and {} edges",
+        read_duration,
+        graph.node_count(),
+        graph.edge_count()
+    );
+
+    if options.resume_from_node.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only resume output in the arc-centric format");
+    }
+    if options.node_mapping.is_some() {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only relabel nodes in the arc-centric format");
+        }
+        if options.preserve_original_ids
+            || options.drop_isolated_nodes
+            || options.only_nodes.is_some()
+            || options.cut_at.is_some()
+        {
+            bail!("Cannot relabel nodes when preserving original ids or renumbering nodes");
+        }
+        if options.id_encoding != IdEncoding::Decimal
+            || options.one_based
+            || options.is_post_processed()
+            || options.global_sort
+            || options.header_node_count != HeaderNodeCount::Graph
+        {
+            bail!("Cannot relabel nodes when encoding node indices or post-processing the output");
+        }
+    }
+    if options.drop_isolated_nodes && options.preserve_original_ids {
+        bail!("Cannot drop isolated nodes when preserving original ids");
+    }
+    if let Some(only_nodes) = &options.only_nodes {
+        if options.preserve_original_ids {
+            bail!("Cannot select nodes when preserving original ids");
+        }
+        if options.output_format == OutputFormat::Bcalm2 {
+            bail!("Cannot select nodes in the bcalm2 format");
+        }
+        if let Some(node) = only_nodes.iter().find(|&&node| node >= graph.node_count()) {
+            bail!(
+                "Selected node {node} is out of range for {} nodes",
+                graph.node_count()
+            );
+        }
+    }
+    if options.cut_at.is_some() {
+        if options.preserve_original_ids {
+            bail!("Cannot cut the graph when preserving original ids");
+        }
+        if options.output_format == OutputFormat::Bcalm2 {
+            bail!("Cannot cut the graph in the bcalm2 format");
+        }
+    }
+    if options.abundance_outliers.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only report abundance outliers in the arc-centric format");
+    }
+    if options.base.is_some() {
+        if options.output_format != OutputFormat::ArcCentric
+            || options.format_version != FormatVersion::V2
+        {
+            bail!("Can only write a patch in the arc-centric format of the current version");
+        }
+        if options.id_encoding != IdEncoding::Decimal || options.one_based {
+            bail!("Can only write a patch with decimal zero-based node indices");
+        }
+    }
+    if options.self_complemental_report.is_some()
+        && options.output_format != OutputFormat::ArcCentric
+    {
+        bail!("Can only report self-complemental arcs in the arc-centric format");
+    }
+    if options.flow_terminals.is_some() && options.output_format != OutputFormat::Dimacs {
+        bail!("Can only choose a source and a sink in the dimacs format");
+    }
+    if !options.report_at.is_empty() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only report at node counts in the arc-centric format");
+    }
+    if options.canonical_node_pairs {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only write canonical node pairs in the arc-centric format");
+        }
+        if options.preserve_original_ids || options.node_mapping.is_some() {
+            bail!("Cannot write canonical node pairs without numeric node names");
+        }
+        if options.is_post_processed() {
+            bail!("Cannot write canonical node pairs when post-processing the output, since it is read back as directed arcs");
+        }
+    }
+    if options.no_sequences && options.output_format != OutputFormat::AdjacencyList {
+        bail!("Can only leave out sequences in the adjacency-list format");
+    }
+    if options.fasta_line_width > 0 && options.output_format != OutputFormat::Bcalm2 {
+        bail!("Can only wrap sequence lines in the bcalm2 format");
+    }
+    if options.node_count.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only override the node count in the arc-centric format");
+    }
+    if options.id_encoding != IdEncoding::Decimal {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only encode node indices in the arc-centric format");
+        }
+        if options.preserve_original_ids {
+            bail!("Cannot encode node indices when preserving original ids");
+        }
+        if options.is_post_processed() || options.header_node_count != HeaderNodeCount::Graph {
+            bail!("Cannot encode node indices when post-processing the output, since it is read back in decimal");
+        }
+    }
+    if options.one_based {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only write one-based node indices in the arc-centric format");
+        }
+        if options.preserve_original_ids {
+            bail!("Cannot write one-based node indices when preserving original ids");
+        }
+        if options.is_post_processed() || options.header_node_count != HeaderNodeCount::Graph {
+            bail!("Cannot write one-based node indices when post-processing the output, since it is read back zero-based");
+        }
+    }
+    if options.header_node_count != HeaderNodeCount::Graph {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only choose the header node count in the arc-centric format");
+        }
+        if options.node_count.is_some() || options.resume_from_node.is_some() {
+            bail!("Cannot choose the header node count when overriding it or when resuming without header");
+        }
+        if options.preserve_original_ids {
+            bail!("Cannot count nodes with original ids, since they are not numeric");
+        }
+    }
+    if options.deduplicate_nodes_by_sequence && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only deduplicate nodes in the arc-centric format");
+    }
+    if options.collapse_linear_paths && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only collapse linear paths in the arc-centric format");
+    }
+    if options.max_sequence_length.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only split long sequences in the arc-centric format");
+    }
+    if options.largest_component_only && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only select the largest component in the arc-centric format");
+    }
+    if options.spanning_tree_only && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only select a spanning forest in the arc-centric format");
+    }
+    if (options.report_bubbles || options.pop_bubbles)
+        && options.output_format != OutputFormat::ArcCentric
+    {
+        bail!("Can only find bubbles in the arc-centric format");
+    }
+    if options.global_sort && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only sort arcs globally in the arc-centric format");
+    }
+    if options.buffered_sort.is_some() {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only sort arcs globally in the arc-centric format");
+        }
+        if options.global_sort
+            || options.is_post_processed()
+            || options.header_node_count != HeaderNodeCount::Graph
+        {
+            bail!("Cannot sort arcs with an external merge sort when sorting in memory or post-processing the output");
+        }
+    }
+    if let Some(probability) = options.sample_arcs {
+        if options.output_format != OutputFormat::ArcCentric {
+            bail!("Can only sample arcs in the arc-centric format");
+        }
+        if !(0.0..=1.0).contains(&probability) {
+            bail!("The sampling probability {probability} is not between zero and one");
+        }
+    }
+    if options.max_out_degree.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only cap the out-degree in the arc-centric format");
+    }
+    if options.limit_arcs.is_some() && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only limit the number of arcs in the arc-centric format");
+    }
+    if options.transpose && options.output_format != OutputFormat::ArcCentric {
+        bail!("Can only transpose the graph in the arc-centric format");
+    }
+    if options.merge_by_sequence_only && options.float_weights.is_some() {
+        bail!("Cannot merge arcs by sequence with float weights");
+    }
+    if options.check_eulerian && options.float_weights.is_some() {
+        bail!("Cannot check if the graph is Eulerian with float weights");
+    }
+    if options.materialize_mirrors && options.collapse_palindromic_unitigs {
+        bail!("Cannot materialize mirrors of collapsed palindromic unitigs");
+    }
+    if options.dot_clusters
+        && options.output_format != OutputFormat::Dot
+        && options.render_png.is_none()
+    {
+        bail!("Can only write clusters in the dot format or when rendering");
+    }
+    if let (Some(_), Some(render_max_nodes)) = (&options.render_png, options.render_max_nodes) {
+        if graph.node_count() > render_max_nodes {
+            bail!(
+                "Graph has {} nodes, which is more than the {render_max_nodes} allowed for rendering",
+                graph.node_count()
+            );
+        }
+    }
+    if alphabet == Alphabet::Rna
+        && matches!(
+            options.output_format,
+            OutputFormat::Bcalm2
+                | OutputFormat::Gfa1
+                | OutputFormat::Bifrost
+                | OutputFormat::Spaligner
+        )
+    {
+        bail!("RNA sequences are only supported in the arc-centric and matrix formats");
+    }
+    check_unitig_lengths(&graph, &sequence_store, k, options.skip_short)?;
+    if options.min_unitig_kmers > 1 {
+        let skipped_edge_count = graph
+            .edge_indices()
+            .filter(|&edge_id| {
+                let length = graph.edge_data(edge_id).length;
+                length >= k && is_skipped(&graph, edge_id, k, options)
+            })
+            .count();
+        info!(
+            "Skipping {skipped_edge_count} edges with less than {} k-mers",
+            options.min_unitig_kmers
+        );
+    }
+    let kmer_spectrum = if options.kmer_spectrum {
+        count_kmer_spectrum(&graph, k)
+    } else {
+        KmerSpectrum::default()
+    };
+
+    let released_link_record_bytes = if options.streaming {
+        let released_link_record_bytes = release_link_records(&mut graph);
+        info!(
+            "Released {:.1}KiB of link records",
+            released_link_record_bytes as f64 / 1024.0
+        );
+        released_link_record_bytes
+    } else {
+        0
+    };
+
+    if let Some(meter) = meter {
+        meter.report()?;
+    }
+
+    info!("Writing graph...");
+    let write_start_time = Instant::now();
+    let mut output = ByteCounter::new(output);
+    let output = &mut output;
+    let mut statistics = GraphStatistics::default();
+    let mut written_arc_count = graph.edge_count();
+    match options.output_format {
+        OutputFormat::ArcCentric => {
+            let node_names = if options.preserve_original_ids {
+                NodeNames::from_original_ids(&graph)
+            } else if let Some(mapping) = &options.node_mapping {
+                NodeNames::from_mapping(&graph, mapping, options.allow_unmapped)?
+            } else {
+                numeric_node_names(&graph, &sequence_store, k, options)
+            };
+            let colors = options.colors.then_some(&colors);
+            let is_post_processed = options.is_post_processed();
+            if is_post_processed
+                || options.global_sort
+                || options.header_node_count != HeaderNodeCount::Graph
+            {
+                let mut arc_centric_output = Vec::new();
+                statistics = output_arc_centric_dbg(
+                    &graph,
+                    &sequence_store,
+                    &node_names,
+                    colors,
+                    k,
+                    options,
+                    &mut arc_centric_output,
+                )?;
+                if is_post_processed {
+                    let mut arc_centric_graph =
+                        read_arc_centric_graph(arc_centric_output.as_slice())?;
+                    if options.deduplicate_nodes_by_sequence {
+                        let node_count = arc_centric_graph.node_count;
+                        arc_centric_graph = deduplicate_nodes_by_sequence(&arc_centric_graph, k);
+                        info!(
+                            "Merged {} nodes with duplicate sequences",
+                            node_count - arc_centric_graph.node_count
+                        );
+                    }
+                    if options.collapse_linear_paths {
+                        let arc_count = arc_centric_graph.arcs.len();
+                        arc_centric_graph = collapse_linear_paths(&arc_centric_graph, k)?;
+                        info!(
+                            "Collapsed {arc_count} arcs into {}",
+                            arc_centric_graph.arcs.len()
+                        );
+                    }
+                    if options.report_bubbles || options.pop_bubbles {
+                        let bubbles = find_bubbles(&arc_centric_graph)?;
+                        if options.report_bubbles {
+                            for bubble in &bubbles {
+                                info!("Bubble {bubble}");
+                            }
+                            info!("Found {} bubbles", bubbles.len());
+                        }
+                        if options.pop_bubbles {
+                            let removed_count;
+                            (arc_centric_graph, removed_count) =
+                                pop_bubbles(&arc_centric_graph, &bubbles)?;
+                            info!(
+                                "Popped {} bubbles by removing {removed_count} arcs",
+                                bubbles.len()
+                            );
+                        }
+                    }
+                    if options.expand_kmers {
+                        // Merged self-complemental arcs share their new nodes with their mirror, so this is an upper bound.
+                        let max_node_count = arc_centric_graph.node_count
+                            + arc_centric_graph
+                                .arcs
+                                .iter()
+                                .map(|arc| arc.sequence.len().saturating_sub(k))
+                                .sum::<usize>();
+                        if max_node_count > options.expand_kmers_max_nodes {
+                            bail!(
+                                "The expanded graph may have up to {max_node_count} nodes, which is more than the {} allowed for expanding k-mers",
+                                options.expand_kmers_max_nodes
+                            );
+                        }
+                    }
+                    if let Some(max_sequence_length) = options.max_sequence_length {
+                        arc_centric_graph =
+                            split_long_arcs(&arc_centric_graph, k, max_sequence_length)?;
+                    }
+                    if let Some(probability) = options.sample_arcs {
+                        let mut rng = make_rng(options.seed);
+                        arc_centric_graph = sample_arcs(&arc_centric_graph, probability, &mut rng)?;
+                        info!("Sampled {} arcs", arc_centric_graph.arcs.len());
+                    }
+                    if options.largest_component_only {
+                        let dropped_component_count;
+                        (arc_centric_graph, dropped_component_count) =
+                            largest_component(&arc_centric_graph);
+                        info!(
+                            "Dropped {dropped_component_count} smaller components, keeping {} arcs",
+                            arc_centric_graph.arcs.len()
+                        );
+                    }
+                    if options.spanning_tree_only {
+                        let dropped_arc_count;
+                        (arc_centric_graph, dropped_arc_count) =
+                            spanning_forest(&arc_centric_graph)?;
+                        info!(
+                            "Dropped {dropped_arc_count} arcs that do not join components, keeping {} arcs",
+                            arc_centric_graph.arcs.len()
+                        );
+                    }
+                    if options.transpose {
+                        arc_centric_graph = transpose(&arc_centric_graph);
+                    }
+                    arc_centric_output.clear();
+                    write_arc_centric_graph(&arc_centric_graph, &mut arc_centric_output)?;
+                    written_arc_count = arc_centric_graph.arcs.len();
+                }
+                let mut arc_centric_output = String::from_utf8(arc_centric_output)?;
+                if options.global_sort {
+                    arc_centric_output = sort_arc_lines(&arc_centric_output, options.id_encoding);
+                }
+                let arc_centric_output =
+                    rewrite_header_node_count(&arc_centric_output, options.header_node_count)?;
+                output.write_all(arc_centric_output.as_bytes())?;
+            } else if let Some(directory) = &options.buffered_sort {
+                let mut sorter =
+                    ExternalSorter::new(directory, options.sort_memory_budget, options.id_encoding);
+                statistics = output_arc_centric_dbg(
+                    &graph,
+                    &sequence_store,
+                    &node_names,
+                    colors,
+                    k,
+                    options,
+                    &mut sorter,
+                )?;
+                info!("Merging {} sorted runs", sorter.run_count());
+                sorter.finish(output)?;
+            } else {
+                statistics = output_arc_centric_dbg(
+                    &graph,
+                    &sequence_store,
+                    &node_names,
+                    colors,
+                    k,
+                    options,
+                    output,
+                )?;
+            }
+            if !is_post_processed {
+                written_arc_count = statistics.arc_count();
+            }
+            if let Some(cut_at) = options.cut_at {
+                info!(
+                    "The cut at node {} has size {}",
+                    cut_at + usize::from(options.one_based),
+                    statistics.arc_count()
+                );
+            }
+            if let Some(outliers) = &statistics.abundance_outliers {
+                for line in outliers.to_string().lines() {
+                    info!("{line}");
+                }
+            }
+            if let (Some(path), Some(arcs)) = (
+                &options.self_complemental_report,
+                &statistics.self_complemental_report,
+            ) {
+                info!("Writing {} self-complemental arcs to {path:?}", arcs.len());
+                let file =
+                    File::create(path).with_context(|| format!("Could not create {path:?}"))?;
+                let mut output = BufWriter::new(file);
+                write_self_complemental_report(arcs, &mut output)?;
+                output.flush()?;
+            }
+        }
+        OutputFormat::Bcalm2 => {
+            let result = if options.fasta_line_width > 0 {
+                let mut output = FastaLineWrapper::new(&mut *output, options.fasta_line_width);
+                write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output)
+            } else {
+                write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut *output)
+            };
+            result.map_err(|error| anyhow!("Could not write bcalm2 graph: {error}"))?;
+        }
+        OutputFormat::Matrix => {
+            if let Some(matrix_max_nodes) = options.matrix_max_nodes {
+                if graph.node_count() > matrix_max_nodes {
+                    bail!(
+                        "Graph has {} nodes, which is more than the {matrix_max_nodes} allowed for the matrix format",
+                        graph.node_count()
+                    );
+                }
+            }
+
+            let arc_centric_graph = convert_to_arc_centric_graph(
+                &graph,
+                &sequence_store,
+                options.colors.then_some(&colors),
+                k,
+                options,
+            )?;
+            write_matrix(&arc_centric_graph, output)?;
+        }
+        OutputFormat::Gfa1 => {
+            if options.trim_overlap {
+                bail!("The GFA format does not support trimmed overlaps");
+            }
+
+            let arc_centric_graph = convert_to_arc_centric_graph(
+                &graph,
+                &sequence_store,
+                options.colors.then_some(&colors),
+                k,
+                options,
+            )?;
+            write_gfa1(&arc_centric_graph, k, options.gfa_emit_paths, output)?;
+        }
+        OutputFormat::Dot => {
+            let arc_centric_graph = convert_to_arc_centric_graph(
+                &graph,
+                &sequence_store,
+                options.colors.then_some(&colors),
+                k,
+                options,
+            )?;
+            write_dot(&arc_centric_graph, options.dot_clusters, output)?;
+        }
+        OutputFormat::Protobuf => {
+            let arc_centric_graph = convert_to_arc_centric_graph(
+                &graph,
+                &sequence_store,
+                options.colors.then_some(&colors),
+                k,
+                options,
+            )?;
+            write_protobuf(&arc_centric_graph, output)?;
+        }
+        OutputFormat::Metis => {
+            let arc_centric_graph = convert_to_arc_centric_graph(
+                &graph,
+                &sequence_store,
+                options.colors.then_some(&colors),
+                k,
+                options,
+            )?;
+            write_metis(&arc_centric_graph, output)?;
+        }
+        OutputFormat::Bifrost => {
+            if options.trim_overlap {
+                bail!("The Bifrost format does not support trimmed overlaps");
+            }
+            if options.colors {
+                bail!("Bifrost stores colors in a separate binary file, which is not supported");
+            }
+
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_bifrost_gfa(&arc_centric_graph, k, output)?;
+        }
+        OutputFormat::Spaligner => {
+            if options.trim_overlap {
+                bail!("The SPAligner format does not support trimmed overlaps");
+            }
+
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_spaligner_gfa(&arc_centric_graph, k, output)?;
+        }
+        OutputFormat::Newick => {
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_newick(&arc_centric_graph, output)?;
+        }
+        OutputFormat::Dimacs => {
+            let Some((source, sink)) = options.flow_terminals else {
+                bail!("The dimacs format requires --source and --sink");
+            };
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_dimacs(&arc_centric_graph, source, sink, output)?;
+        }
+        OutputFormat::Parquet => {
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_parquet(&arc_centric_graph, output)?;
+        }
+        OutputFormat::Json => {
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_json(&arc_centric_graph, output)?;
+        }
+        OutputFormat::AdjacencyList => {
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_adjacency_list(&arc_centric_graph, !options.no_sequences, output)?;
+        }
+        OutputFormat::LineGraph => {
+            if options.trim_overlap {
+                bail!("The line graph does not support trimmed overlaps");
+            }
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            let line_graph = line_graph(&arc_centric_graph, k, options.line_graph_max_arcs)?;
+            info!(
+                "The line graph has {} nodes and {} arcs",
+                line_graph.node_count,
+                line_graph.arcs.len()
+            );
+            write_arc_centric_graph(&line_graph, output)?;
+        }
+        OutputFormat::Csr => {
+            let arc_centric_graph =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+            write_csr(&arc_centric_graph, output)?;
+        }
+    }
+    if let Some(path) = &options.render_png {
+        info!("Rendering graph to {path:?}");
+        let arc_centric_graph = convert_to_arc_centric_graph(
+            &graph,
+            &sequence_store,
+            options.colors.then_some(&colors),
+            k,
+            options,
+        )?;
+        render_png(&arc_centric_graph, options.dot_clusters, path)?;
+    }
+    if options.check_eulerian {
+        info!("Checking if the graph is Eulerian");
+        let arc_centric_graph =
+            convert_to_arc_centric_graph(&graph, &sequence_store, None, k, options)?;
+        for line in check_eulerian(&arc_centric_graph).to_string().lines() {
+            info!("{line}");
+        }
+    }
+    if options.ascii_preview {
+        let node_count = numeric_node_names(&graph, &sequence_store, k, options).node_count(&graph);
+        if node_count <= ASCII_PREVIEW_MAX_NODES {
+            // The preview always shows zero-based node indices and integer weights.
+            let preview_options = ConversionOptions {
+                one_based: false,
+                id_encoding: IdEncoding::Decimal,
+                float_weights: None,
+                ..options.clone()
+            };
+            let preview =
+                convert_to_arc_centric_graph(&graph, &sequence_store, None, k, &preview_options)
+                    .and_then(|arc_centric_graph| {
+                        Ok(write_ascii_preview(
+                            &arc_centric_graph,
+                            &mut std::io::stderr().lock(),
+                        )?)
+                    });
+            if let Err(error) = preview {
+                warn!("Could not preview the graph: {error:#}");
+            }
+        } else {
+            warn!(
+                "Not previewing the graph, since it has {node_count} nodes, which is more than {ASCII_PREVIEW_MAX_NODES}"
+            );
+        }
+    }
+    let write_duration = write_start_time.elapsed();
+    let output_bytes = output.bytes();
+    if options.require_nonempty && written_arc_count == 0 {
+        bail!("No arcs were written, but --require-nonempty is set");
+    }
+    info!("Finished graph writing in {:.2?}", write_duration);
+
+    let report = ConversionReport {
+        read_duration,
+        write_duration,
+        total_duration: start_time.elapsed(),
+        released_link_record_bytes,
+        deduplicated_sequences,
+        alphabet,
+        input_bytes: input_counter.bytes(),
+        output_bytes,
+        arc_count: graph.edge_count(),
+        statistics,
+        kmer_spectrum,
+    };
+    info!("Total conversion time: {:.2?}", report.total_duration);
+    Ok(report)
+}
+
+/// The maximum weight of an arc written by [`output_arc_centric_dbg`], as float.
+/// Returns one if no arc with a positive weight is written, such that normalising by it has no effect.
+fn max_arc_weight(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    options: &ConversionOptions,
+    dropped_by_out_degree: &HashSet<<Graph as GraphBase>::EdgeIndex>,
+) -> f64 {
+    let mut max_weight: f64 = 0.0;
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        if is_skipped(graph, edge_id, k, options)
+            || !is_selected(graph, sequence_store, edge_id, options)
+            || dropped_by_out_degree.contains(&edge_id)
+        {
+            continue;
+        }
+
+        let weight_multiplier = weight_multiplier(graph, sequence_store, edge_id, options);
+        if options.only_merged && weight_multiplier == 1 {
+            continue;
+        }
+
+        let divisor = options.weight_metric.divisor(edge_data.length, k);
+        let weight = edge_data.total_abundance as f64 / divisor as f64 * weight_multiplier as f64;
+        max_weight = max_weight.max(weight);
+    }
+
+    if max_weight > 0.0 {
+        max_weight
+    } else {
+        1.0
+    }
+}
+
+/// The factor by which [`output_arc_centric_dbg`] multiplies the weight of the arc of the edge:
+/// collapsed palindromes and merged mirrors have doubled weight.
+fn weight_multiplier(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    options: &ConversionOptions,
+) -> usize {
+    let mirror_edge = graph.mirror_edge_edge_centric(edge_id).unwrap();
+    let is_collapsed_palindrome = options.collapse_palindromic_unitigs
+        && is_palindrome(sequence_store.get(&graph.edge_data(edge_id).sequence_handle));
+    let is_merged = !options.materialize_mirrors
+        && graph.edge_endpoints(edge_id) == graph.edge_endpoints(mirror_edge)
+        && should_merge_revcomp_pair(
+            graph,
+            sequence_store,
+            edge_id,
+            mirror_edge,
+            options.merge_threshold,
+        );
+    if is_collapsed_palindrome || is_merged {
+        2
+    } else {
+        1
+    }
+}
+
+/// The weight scaled as `round(log2(weight + 1) * scale)`.
+fn log_scaled_weight(weight: usize, scale: f64) -> usize {
+    ((weight as f64 + 1.0).log2() * scale).round() as usize
+}
+
+/// The fraction of `G` and `C` characters in the sequence, or zero if the sequence is empty.
+fn gc_content(sequence: &[u8]) -> f64 {
+    if sequence.is_empty() {
+        return 0.0;
+    }
+    let gc_count = sequence
+        .iter()
+        .filter(|character| matches!(character, b'G' | b'C' | b'g' | b'c'))
+        .count();
+    gc_count as f64 / sequence.len() as f64
+}
+
+/// The 64-bit FNV-1a hash of the lexicographically smaller of the sequence and its reverse complement.
+fn canonical_sequence_hash(sequence: &str) -> u64 {
+    let reverse_complement = reverse_complement(sequence);
+    let canonical = sequence.min(reverse_complement.as_str());
+    canonical.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Returns true if the edge is selected by `--only-nodes`, `--cut-at` and `--filter-sequence-regex`,
+/// or if they are not given.
+/// An edge is selected by `--only-nodes` if it or its mirror starts or ends in a selected node,
+/// by `--cut-at` if it or its mirror crosses the cut,
+/// and by `--filter-sequence-regex` if its sequence or the reverse complement sequence of its mirror matches.
+fn is_selected(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    options: &ConversionOptions,
+) -> bool {
+    if options.only_nodes.is_none() && options.cut_at.is_none() && options.sequence_regex.is_none()
+    {
+        return true;
+    }
+    let mirror_edge = graph.mirror_edge_edge_centric(edge_id).unwrap();
+    let edges = [edge_id, mirror_edge].map(|edge_id| {
+        let edge = graph.edge_endpoints(edge_id);
+        (edge.from_node.as_usize(), edge.to_node.as_usize())
+    });
+
+    let is_selected_by_nodes = options.only_nodes.as_ref().map_or(true, |only_nodes| {
+        edges
+            .iter()
+            .any(|(from, to)| only_nodes.contains(from) || only_nodes.contains(to))
+    });
+    let is_selected_by_cut = options.cut_at.map_or(true, |cut_at| {
+        edges
+            .iter()
+            .any(|&(from, to)| (from < cut_at) != (to < cut_at))
+    });
+    let is_selected_by_regex = options.sequence_regex.as_ref().map_or(true, |regex| {
+        let sequence = sequence_store.get(&graph.edge_data(edge_id).sequence_handle);
+        let sequence = sequence.as_string();
+        regex.is_match(&sequence) || regex.is_match(&reverse_complement(&sequence))
+    });
+    is_selected_by_nodes && is_selected_by_cut && is_selected_by_regex
+}
+
+/// The edges dropped by `--max-out-degree`, i.e. the out-edges of each node except for the `max_out_degree`
+/// with the highest written weights, together with their mirror edges.
+/// An edge whose mirror edge starts in the same node does not count towards the out-degree,
+/// since it may be merged with its mirror edge.
+fn edges_above_max_out_degree(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    options: &ConversionOptions,
+    max_out_degree: usize,
+) -> HashSet<<Graph as GraphBase>::EdgeIndex> {
+    let mut dropped = HashSet::new();
+    for node in graph.node_indices() {
+        let mut edges: Vec<_> = graph
+            .out_neighbors(node)
+            .map(|neighbor| neighbor.edge_id)
+            .filter(|&edge_id| {
+                !is_skipped(graph, edge_id, k, options)
+                    && is_selected(graph, sequence_store, edge_id, options)
+            })
+            .collect();
+        edges.sort_by_key(|&edge_id| {
+            let edge_data = graph.edge_data(edge_id);
+            let weight = edge_data.total_abundance
+                / options.weight_metric.divisor(edge_data.length, k)
+                * weight_multiplier(graph, sequence_store, edge_id, options);
+            (Reverse(weight), edge_id)
+        });
+
+        let mut kept = Vec::new();
+        for edge_id in edges {
+            if dropped.contains(&edge_id) {
+                continue;
+            }
+            let mirror_edge = graph.mirror_edge_edge_centric(edge_id).unwrap();
+            if kept.contains(&mirror_edge) || kept.len() < max_out_degree {
+                kept.push(edge_id);
+            } else {
+                dropped.insert(edge_id);
+                dropped.insert(mirror_edge);
+            }
+        }
+    }
+    dropped
+}
+
+/// The edges dropped by `--max-out-degree`, or none if it is not given.
+fn dropped_by_out_degree(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    options: &ConversionOptions,
+) -> HashSet<<Graph as GraphBase>::EdgeIndex> {
+    options
+        .max_out_degree
+        .map(|max_out_degree| {
+            edges_above_max_out_degree(graph, sequence_store, k, options, max_out_degree)
+        })
+        .unwrap_or_default()
+}
+
+/// Read node indices separated by whitespace or commas, as given to `--only-nodes-file`.
+fn read_node_list(path: &Path) -> anyhow::Result<HashSet<usize>> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Could not read {path:?}"))?;
+    content
+        .split(|character: char| character.is_whitespace() || character == ',')
+        .filter(|node| !node.is_empty())
+        .map(|node| {
+            node.parse()
+                .with_context(|| format!("Malformed node index {node:?} in {path:?}"))
+        })
+        .collect()
+}
+
+/// The configuration as used for the conversion, with the resolved k-mer size and log level.
+fn resolved_config(cli: &Cli, k: usize) -> Cli {
+    Cli {
+        k: Some(k),
+        log_level: Some(log::max_level()),
+        ..cli.clone()
+    }
+}
+
+/// Read whitespace-separated sequences, converted to upper case.
+fn read_sequence_list(path: &Path) -> anyhow::Result<HashSet<String>> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("Could not read {path:?}"))?;
+    Ok(content
+        .split_whitespace()
+        .map(str::to_ascii_uppercase)
+        .collect())
+}
+
+fn assert_balanced(graph: &Graph) -> anyhow::Result<()> {
+    info!("Checking that the graph is balanced");
+    let imbalances = balance::find_imbalances(graph);
+    for imbalance in &imbalances {
+        warn!("Imbalance: {imbalance}");
+    }
+    if !imbalances.is_empty() {
+        bail!("Found {} imbalances in the graph", imbalances.len());
+    }
+    Ok(())
+}
+
+fn verify_abundance_conservation(graph: &Graph) -> anyhow::Result<()> {
+    info!("Checking that the edges of each unitig have the same abundance");
+    let violations = balance::find_abundance_violations(graph);
+    for violation in &violations {
+        warn!("Abundance not conserved: {violation}");
+    }
+    if !violations.is_empty() {
+        bail!(
+            "Found {} unitigs whose edges have different abundances",
+            violations.len()
+        );
+    }
+    Ok(())
+}
+
+fn read_graph(
+    input: impl BufRead,
+    sequence_store: &mut impl SequenceStore<DnaAlphabet, Handle = SequenceHandle>,
+    k: usize,
+) -> anyhow::Result<Graph> {
+    read_bigraph_from_bcalm2_as_edge_centric(input, sequence_store, k)
+        .map_err(|error| anyhow!("Could not read bcalm2 graph: {error}"))
+}
+
+/// Release the raw link records that the bcalm2 reader stores with each edge.
+/// They are not needed for writing, since the links are represented by the topology of the graph.
+/// Returns the number of released bytes.
+fn release_link_records(graph: &mut Graph) -> usize {
+    let mut released_bytes = 0;
+    for edge_id in graph.edge_indices_copied() {
+        let links = std::mem::take(&mut graph.edge_data_mut(edge_id).edges);
+        released_bytes += links.capacity() * std::mem::size_of::<PlainBCalm2Edge>();
+    }
+    released_bytes
+}
+
+/// Count the k-mer spectrum of the unitigs, counting only the forwards edge of each unitig.
+/// Short unitigs without k-mers are left out.
+fn count_kmer_spectrum(graph: &Graph, k: usize) -> KmerSpectrum {
+    let mut spectrum = KmerSpectrum::default();
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        if edge_data.forwards && edge_data.length >= k {
+            spectrum.record_unitig(edge_data.total_abundance, edge_data.length - (k - 1));
+        }
+    }
+    spectrum
+}
+
+/// Check that the links of the records imply overlaps of k-1 characters, and warn about inconsistent links.
+/// If `strict` is set, then inconsistent links are an error.
+fn validate_k_consistency(
+    record_ends: &[RecordEnds],
+    k: usize,
+    strict: bool,
+) -> anyhow::Result<()> {
+    info!(
+        "Validating the link overlaps of {} records",
+        record_ends.len()
+    );
+    let mismatches = find_overlap_mismatches(record_ends, k);
+    for mismatch in &mismatches {
+        warn!("Inconsistent overlap: {mismatch}");
+    }
+    if !mismatches.is_empty() {
+        let message = format!(
+            "Found {} links that do not overlap by k-1 = {}",
+            mismatches.len(),
+            k - 1
+        );
+        if strict {
+            bail!("{message}");
+        }
+        warn!("{message}");
+    }
+    Ok(())
+}
+
+/// Write the coverage along the unitigs of the reference order as bedGraph track.
+fn write_coverage_track(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    reference_order: &ReferenceOrder,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let edges: HashMap<_, _> = graph
+        .edge_indices()
+        .filter(|&edge_id| graph.edge_data(edge_id).forwards)
+        .map(|edge_id| (graph.edge_data(edge_id).id, edge_id))
+        .collect();
+    let unitigs = reference_order
+        .unitigs
+        .iter()
+        .map(|&(id, forwards)| {
+            let Some(&edge_id) = edges.get(&id) else {
+                bail!("The reference order contains the unitig {id}, which is not in the input");
+            };
+            let edge_data = graph.edge_data(edge_id);
+            let sequence = sequence_store.get(&edge_data.sequence_handle).as_string();
+            let sequence = if forwards {
+                sequence
+            } else {
+                reverse_complement(&sequence)
+            };
+            let kmer_count = edge_data.length.saturating_sub(k - 1).max(1);
+            Ok((
+                sequence,
+                edge_data.total_abundance as f64 / kmer_count as f64,
+            ))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let intervals = coverage_intervals(&unitigs, k)?;
+    write_bedgraph(&reference_order.name, &intervals, output)
+}
+
+/// Count the canonical k-mers of the unitigs of the input, counting only the forwards edge of each unitig.
+/// Short unitigs without k-mers are left out.
+fn count_kmers(input: impl BufRead, k: usize) -> anyhow::Result<KmerCounts> {
+    let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::new();
+    let graph = read_graph(input, &mut sequence_store, k)?;
+    let mut counts = KmerCounts::default();
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        if edge_data.forwards && edge_data.length >= k {
+            let sequence = sequence_store.get(&edge_data.sequence_handle).as_string();
+            counts.record_unitig(&sequence, edge_data.total_abundance, k);
+        }
+    }
+    Ok(counts)
+}
+
+/// Convert the input with the default options and with `--materialize-mirrors`, read both outputs back,
+/// and check that merging reverse complemental arcs is stable with [`check_merge_stability`].
+fn self_test(input: &[u8], k: usize) -> anyhow::Result<()> {
+    let convert = |materialize_mirrors| {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            k,
+            &ConversionOptions {
+                materialize_mirrors,
+                ..Default::default()
+            },
+            &mut &input[..],
+            &mut output,
+        )?;
+        read_arc_centric_graph(output.as_slice())
+    };
+    check_merge_stability(&convert(false)?, &convert(true)?)
+}
+
+/// Ensure that all unitigs contain at least one k-mer, since the abundance of an arc is computed per k-mer.
+/// If `skip_short` is set, short unitigs are only counted, and must be skipped when writing.
+fn check_unitig_lengths(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    skip_short: bool,
+) -> anyhow::Result<()> {
+    let mut short_edge_count = 0;
+    for edge_id in graph.edge_indices() {
+        let edge_data = graph.edge_data(edge_id);
+        if edge_data.length >= k {
+            continue;
+        }
+
+        if !skip_short {
+            let sequence = sequence_store.get(&edge_data.sequence_handle);
+            bail!(
+                "Unitig >{} has length {}, which is shorter than k = {k}: {}",
+                edge_data.id,
+                edge_data.length,
+                sequence.as_string()
+            );
+        }
+        short_edge_count += 1;
+    }
+
+    if short_edge_count > 0 {
+        warn!("Skipping {short_edge_count} edges that are shorter than k = {k}");
+    }
+    Ok(())
+}
+
+/// Returns true if the edge is skipped by `--skip-short` or `--min-unitig-kmers`.
+/// Both edges of a unitig have the same length, so an arc is skipped together with its mirror.
+fn is_skipped(
+    graph: &Graph,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    k: usize,
+    options: &ConversionOptions,
+) -> bool {
+    let length = graph.edge_data(edge_id).length;
+    (options.skip_short && length < k) || length + 1 < k + options.min_unitig_kmers
+}
+
+/// Convert the graph in memory, for output formats that are derived from the arc-centric format.
+/// Then the weights are computed exactly like for the arc-centric format.
+fn convert_to_arc_centric_graph(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    colors: Option<&ColorTable>,
+    k: usize,
+    options: &ConversionOptions,
+) -> anyhow::Result<ArcCentricGraph> {
+    if options.float_weights.is_some() {
+        bail!("Float weights are only supported in the arc-centric format");
+    }
+
+    let mut arc_centric_output = Vec::new();
+    output_arc_centric_dbg(
+        graph,
+        sequence_store,
+        &numeric_node_names(graph, sequence_store, k, options),
+        colors,
+        k,
+        options,
+        &mut arc_centric_output,
+    )?;
+    read_arc_centric_graph(arc_centric_output.as_slice())
+}
+
+/// Name the nodes by their indices, leaving out nodes without written arcs if requested.
+fn numeric_node_names(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    k: usize,
+    options: &ConversionOptions,
+) -> NodeNames {
+    if options.drop_isolated_nodes || options.only_nodes.is_some() {
+        let dropped_by_out_degree = dropped_by_out_degree(graph, sequence_store, k, options);
+        NodeNames::without_isolated_nodes(graph, |edge_id| {
+            !is_skipped(graph, edge_id, k, options)
+                && is_selected(graph, sequence_store, edge_id, options)
+                && !dropped_by_out_degree.contains(&edge_id)
+                && (!options.only_merged
+                    || weight_multiplier(graph, sequence_store, edge_id, options) > 1)
+        })
+    } else {
+        NodeNames::Indices
+    }
+}
+
+fn output_arc_centric_dbg(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    node_names: &NodeNames,
+    colors: Option<&ColorTable>,
+    k: usize,
+    options: &ConversionOptions,
+    output: &mut impl Write,
+) -> anyhow::Result<GraphStatistics> {
+    let mut statistics = GraphStatistics {
+        abundance_outliers: options.abundance_outliers.map(AbundanceOutliers::new),
+        self_complemental_report: options
+            .self_complemental_report
+            .as_ref()
+            .map(|_| Vec::new()),
+        ..Default::default()
+    };
+    let dropped_by_out_degree = dropped_by_out_degree(graph, sequence_store, k, options);
+    let max_weight = if options.normalize_weights {
+        let max_weight = max_arc_weight(graph, sequence_store, k, options, &dropped_by_out_degree);
+        info!("Normalising weights by the maximum weight {max_weight}");
+        max_weight
+    } else {
+        1.0
+    };
+    if options.resume_from_node.is_none() {
+        let node_count = options
+            .node_count
+            .unwrap_or_else(|| node_names.node_count(graph));
+        writeln!(output, "{node_count}")?;
+    }
+    let mut progress = ProgressReporter::new(options.progress_interval, graph.node_count());
+    let mut arc_limit = options.limit_arcs.map(ArcLimit::new);
+    let nodes: Box<dyn Iterator<Item = _>> = if options.reverse_order {
+        Box::new(graph.node_indices().rev())
+    } else if options.shuffle_nodes {
+        let mut nodes: Vec<_> = graph.node_indices().collect();
+        nodes.shuffle(&mut make_rng(options.seed));
+        Box::new(nodes.into_iter())
+    } else {
+        Box::new(graph.node_indices())
+    };
+    for (node_number, n1) in nodes.enumerate() {
+        if n1.as_usize() < options.resume_from_node.unwrap_or(0) {
+            progress.advance();
+            continue;
+        }
+
+        // Parallel arcs are ordered by their sequence, such that an arc is directly followed by its
+        // reverse complemental mirror arc with the same sequence, which is required for merging them below.
+        // This also makes the order of parallel arcs independent of the order of the adjacency lists.
+        let mut neighbors: Vec<_> = graph.out_neighbors(n1).collect();
+        neighbors.sort_by(|a, b| {
+            a.node_id
+                .cmp(&b.node_id)
+                .then_with(|| compare_arc_sequences(graph, sequence_store, a.edge_id, b.edge_id))
+                .then_with(|| a.edge_id.cmp(&b.edge_id))
+        });
+
+        // The arcs are buffered, such that the node annotation with the number of arcs can be written before them.
+        let mut node_output = Vec::new();
+        let mut out_degree = 0;
+        let mut n2_iterator = neighbors.iter().peekable();
+        while let Some(Neighbor {
+            node_id: n2,
+            edge_id,
+        }) = n2_iterator.next().cloned()
+        {
+            let edge_data = graph.edge_data(edge_id);
+            if is_skipped(graph, edge_id, k, options) {
+                continue;
+            }
+            if !is_selected(graph, sequence_store, edge_id, options)
+                || dropped_by_out_degree.contains(&edge_id)
+            {
+                continue;
+            }
+
+            // A palindromic unitig is represented by two parallel arcs with the same sequence.
+            // When collapsing, we emit only the forwards one, no matter where the other one is sorted to.
+            let is_collapsed_palindrome = options.collapse_palindromic_unitigs
+                && is_palindrome(sequence_store.get(&edge_data.sequence_handle));
+            if is_collapsed_palindrome && !edge_data.forwards {
+                continue;
+            }
+
+            // if there is a pair of reverse complemental edges with a self-complemental label,
+            // then we merge them, as they represent the same sequence.
+            let merged_edge = if is_collapsed_palindrome || options.materialize_mirrors {
+                None
+            } else {
+                n2_iterator
+                    .next_if(|next| {
+                        n2 == next.node_id
+                            && should_merge_revcomp_pair(
+                                graph,
+                                sequence_store,
+                                edge_id,
+                                next.edge_id,
+                                options.merge_threshold,
+                            )
+                    })
+                    .map(|next| next.edge_id)
+            };
+            let weight_multiplier = if is_collapsed_palindrome || merged_edge.is_some() {
+                2
+            } else {
+                1
+            };
+            // Since the neighbors are sorted by sequence, parallel arcs with equal sequences are consecutive.
+            let mut sequence_merged_weight = 0;
+            if options.merge_by_sequence_only && !is_collapsed_palindrome {
+                while let Some(next) = n2_iterator.next_if(|next| {
+                    n2 == next.node_id
+                        && compare_arc_sequences(graph, sequence_store, edge_id, next.edge_id)
+                            == Ordering::Equal
+                }) {
+                    let next_edge_data = graph.edge_data(next.edge_id);
+                    sequence_merged_weight += next_edge_data.total_abundance
+                        / options.weight_metric.divisor(next_edge_data.length, k);
+                }
+            }
+            if options.trace_edge == Some((n1.as_usize(), n2.as_usize())) {
+                trace_edge(graph, sequence_store, edge_id, merged_edge, k);
+            }
+            if options.only_merged && weight_multiplier == 1 {
+                continue;
+            }
+            if let Some(arc_limit) = &mut arc_limit {
+                let mirror_edge = (weight_multiplier == 1)
+                    .then(|| graph.mirror_edge_edge_centric(edge_id).unwrap());
+                if !arc_limit.admit(edge_id, mirror_edge) {
+                    continue;
+                }
+            }
+
+            let divisor = options.weight_metric.divisor(edge_data.length, k);
+            if options.float_weights.is_none() && edge_data.total_abundance % divisor != 0 {
+                let sequence = sequence_store.get(&edge_data.sequence_handle);
+                let is_suppressed = options.suppressed_warning_sequences.as_ref().is_some_and(
+                    |suppressed_sequences| {
+                        let sequence = sequence.as_string();
+                        suppressed_sequences.contains(&sequence)
+                            || suppressed_sequences.contains(&reverse_complement(&sequence))
+                    },
+                );
+                if !is_suppressed {
+                    let sequence = &sequence[..(k + 10).min(sequence.len())];
+                    warn!(
+                        "Found edge with non-integer average abundance: {}",
+                        sequence.as_string()
+                    );
+                }
+            }
+
+            let labels = options
+                .node_labels
+                .as_ref()
+                .map(|labels| (labels.get(&n1.as_usize()), labels.get(&n2.as_usize())));
+            let mirror_edge = graph.mirror_edge_edge_centric(edge_id).unwrap();
+            let Edge {
+                from_node: mirror_n1,
+                to_node: mirror_n2,
+            } = graph.edge_endpoints(mirror_edge);
+            let n1 = node_names.name(n1.as_usize());
+            let n2 = node_names.name(n2.as_usize());
+            let mirror_n1 = node_names.name(mirror_n1.as_usize());
+            let mirror_n2 = node_names.name(mirror_n2.as_usize());
+            if let Some(node_count) = options.node_count {
+                for name in [&n1, &n2, &mirror_n1, &mirror_n2] {
+                    if let NodeName::Index(index) = name {
+                        if *index >= node_count {
+                            bail!("Node index {index} is not below the node count {node_count}");
+                        }
+                    }
+                }
+            }
+            let is_swapped = options.canonical_node_pairs
+                && matches!((n1, n2), (NodeName::Index(n1), NodeName::Index(n2)) if n1 > n2);
+            let (n1, n2) = if is_swapped { (n2, n1) } else { (n1, n2) };
+            let [n1, n2, mirror_n1, mirror_n2] = [n1, n2, mirror_n1, mirror_n2].map(|name| {
+                options
+                    .id_encoding
+                    .encode_name(name.offset(usize::from(options.one_based)))
+            });
+            if let Some(precision) = options.float_weights {
+                let weight = edge_data.total_abundance as f64 / divisor as f64
+                    * weight_multiplier as f64
+                    / max_weight;
+                write!(
+                    node_output,
+                    "{n1} {n2} {weight:.precision$} {mirror_n1} {mirror_n2} "
+                )?;
+            } else {
+                let mut weight = edge_data.total_abundance / divisor * weight_multiplier
+                    + sequence_merged_weight;
+                if let Some(scale) = options.log_weight_scale {
+                    weight = log_scaled_weight(weight, scale);
+                }
+                write!(node_output, "{n1} {n2} {weight} {mirror_n1} {mirror_n2} ")?;
+            }
+
+            let sequence = sequence_store.get(&edge_data.sequence_handle);
+            let trimmed_length = if options.trim_overlap { k - 1 } else { 0 };
+            let sequence_start = node_output.len();
+            if edge_data.forwards {
+                for character in sequence.iter().skip(trimmed_length) {
+                    let character = translate_character(u8::from(*character), options.alphabet);
+                    node_output.push(options.sequence_case.apply(character));
+                }
+            } else {
+                if options.verify_revcomp {
+                    verify_reverse_complement(sequence, edge_data.id)?;
+                }
+                for character in sequence.reverse_complement_iter().skip(trimmed_length) {
+                    let character = translate_character(u8::from(character), options.alphabet);
+                    node_output.push(options.sequence_case.apply(character));
+                }
+            }
+            statistics.record_arc(node_output.len() - sequence_start);
+            statistics.record_arc_structure(
+                graph.edge_endpoints(edge_id) == graph.edge_endpoints(mirror_edge),
+                is_palindrome(sequence),
+                merged_edge.is_some(),
+            );
+            let unscaled_weight =
+                edge_data.total_abundance / divisor * weight_multiplier + sequence_merged_weight;
+            statistics.record_arc_weight(unscaled_weight);
+            if let Some(arcs) = &mut statistics.self_complemental_report {
+                if weight_multiplier == 2 && is_palindrome(sequence) {
+                    arcs.push(SelfComplementalArc {
+                        from: n1.to_string(),
+                        to: n2.to_string(),
+                        sequence: String::from_utf8_lossy(&node_output[sequence_start..])
+                            .into_owned(),
+                    });
+                }
+            }
+            if let Some(outliers) = &mut statistics.abundance_outliers {
+                outliers.record(Outlier::new(
+                    unscaled_weight,
+                    n1.to_string(),
+                    n2.to_string(),
+                    &String::from_utf8_lossy(&node_output[sequence_start..]),
+                ));
+            }
+            let gc_content = options
+                .emit_gc
+                .then(|| gc_content(&node_output[sequence_start..]));
+            if options.trim_overlap {
+                write!(node_output, " TR:i:{trimmed_length}")?;
+            }
+            if options.emit_overlap {
+                write!(node_output, " OV:i:{}", k - 1)?;
+            }
+            if options.emit_orientation {
+                let orientation = if edge_data.forwards { '+' } else { '-' };
+                write!(node_output, " OR:A:{orientation}")?;
+            }
+            if options.emit_source_offset {
+                write!(node_output, " {SOURCE_OFFSET_TAG}0")?;
+            }
+            if options.canonical_node_pairs {
+                let direction = if is_swapped { '-' } else { '+' };
+                write!(node_output, " DI:A:{direction}")?;
+            }
+            if let Some(gc_content) = gc_content {
+                write!(node_output, " GC:f:{gc_content:.4}")?;
+            }
+            if options.emit_arc_hash {
+                let hash = canonical_sequence_hash(&sequence.as_string());
+                write!(node_output, " AH:Z:{hash:016x}")?;
+            }
+            if let Some((from_label, to_label)) = labels {
+                if let Some(from_label) = from_label {
+                    write!(node_output, " {FROM_LABEL_TAG}{from_label}")?;
+                }
+                if let Some(to_label) = to_label {
+                    write!(node_output, " {TO_LABEL_TAG}{to_label}")?;
+                }
+            }
+            if is_collapsed_palindrome {
+                write!(node_output, " PA:i:1")?;
+            }
+            if let Some(colors) = colors {
+                let mut arc_colors = colors.get(&edge_data.id).cloned().unwrap_or_default();
+                if let Some(merged_edge) = merged_edge {
+                    if let Some(merged_colors) = colors.get(&graph.edge_data(merged_edge).id) {
+                        union_colors(&mut arc_colors, merged_colors);
+                    }
+                }
+                write!(node_output, " {COLOR_TAG}{}", format_colors(&arc_colors))?;
+            }
+            writeln!(node_output)?;
+            out_degree += 1;
+        }
+
+        if options.annotate_nodes && out_degree > 0 {
+            writeln!(
+                output,
+                "# node {} out={out_degree}",
+                options.id_encoding.encode_name(
+                    node_names
+                        .name(n1.as_usize())
+                        .offset(usize::from(options.one_based))
+                )
+            )?;
+        }
+        output.write_all(&node_output)?;
+        statistics.record_node(out_degree);
+        progress.advance();
+        if options
+            .snapshot_request
+            .as_ref()
+            .is_some_and(SnapshotRequest::take)
+        {
+            progress.report_snapshot();
+            // A new memory meter measures the whole process, just like the one of the conversion.
+            MemoryMeter::new().report()?;
+        }
+        if let Some(timeout) = &options.timeout {
+            timeout.check()?;
+        }
+        if options.report_at.contains(&(node_number + 1)) {
+            info!("Reached {} processed nodes", node_number + 1);
+            MemoryMeter::new().report()?;
+        }
+        if arc_limit.as_ref().is_some_and(ArcLimit::is_complete) {
+            break;
+        }
+    }
+    if let Some(arc_limit) = &arc_limit {
+        info!("Limited output to {} arcs", arc_limit.written_count());
+    }
+
+    Ok(statistics)
+}
+
+/// Log the details of the edge and whether it was merged with the given edge, for `--trace-edge`.
+fn trace_edge(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    merged_edge: Option<<Graph as GraphBase>::EdgeIndex>,
+    k: usize,
+) {
+    let edge_data = graph.edge_data(edge_id);
+    let edge = graph.edge_endpoints(edge_id);
+    let mirror_edge = graph.edge_endpoints(graph.mirror_edge_edge_centric(edge_id).unwrap());
+    debug!(
+        "Trace of edge {} -> {} (unitig {}): mirror {} -> {}, total abundance {}, {} k-mers, forwards {}, {}, stored sequence {}",
+        edge.from_node.as_usize(),
+        edge.to_node.as_usize(),
+        edge_data.id,
+        mirror_edge.from_node.as_usize(),
+        mirror_edge.to_node.as_usize(),
+        edge_data.total_abundance,
+        edge_data.length - (k - 1),
+        edge_data.forwards,
+        if merged_edge.is_some() {
+            "merged with its mirror"
+        } else {
+            "not merged"
+        },
+        sequence_store.get(&edge_data.sequence_handle).as_string(),
+    );
+}
+
+/// Check that the reverse complement iterator of the sequence produces the reverse complement of the sequence.
+fn verify_reverse_complement(
+    sequence: &<DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::SequenceRef,
+    unitig_id: usize,
+) -> anyhow::Result<()> {
+    let forwards: String = sequence.iter().map(|c| char::from(u8::from(*c))).collect();
+    let iterated: String = sequence
+        .reverse_complement_iter()
+        .map(|c| char::from(u8::from(c)))
+        .collect();
+    let expected = reverse_complement(&forwards);
+    if iterated != expected {
+        bail!("The reverse complement of unitig >{unitig_id} is {expected}, but the iterator produced {iterated}");
+    }
+    Ok(())
+}
+
+/// Compare the sequences of two edges, in the orientation in which they are written.
+fn compare_arc_sequences(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    other_edge_id: <Graph as GraphBase>::EdgeIndex,
+) -> Ordering {
+    let arc_sequence = |edge_id| -> Box<dyn Iterator<Item = DnaCharacter> + '_> {
+        let edge_data = graph.edge_data(edge_id);
+        let sequence = sequence_store.get(&edge_data.sequence_handle);
+        if edge_data.forwards {
+            Box::new(sequence.iter().copied())
+        } else {
+            Box::new(sequence.reverse_complement_iter())
+        }
+    };
+    arc_sequence(edge_id).cmp(arc_sequence(other_edge_id))
+}
+
+/// Returns true if the edge is followed by its reverse complemental mirror edge with the same label,
+/// such that both represent the same sequence and can be merged.
+///
+/// An edge is never its own mirror, since each unitig becomes two edges of opposite orientation.
+/// This includes a self-complemental self-loop on a self-complemental node, whose two edges are merged like any other pair.
+fn should_merge_revcomp_pair(
+    graph: &Graph,
+    sequence_store: &DefaultSequenceStore<DnaAlphabet>,
+    edge_id: <Graph as GraphBase>::EdgeIndex,
+    next_edge_id: <Graph as GraphBase>::EdgeIndex,
+    merge_threshold: usize,
+) -> bool {
+    if graph.mirror_edge_edge_centric(edge_id).unwrap() != next_edge_id {
+        return false;
+    }
+
+    let edge_data = graph.edge_data(edge_id);
+    let next_edge_data = graph.edge_data(next_edge_id);
+    let sequence = sequence_store.get(&edge_data.sequence_handle);
+    let next_sequence = sequence_store.get(&next_edge_data.sequence_handle);
+    let mismatches = if edge_data.forwards == next_edge_data.forwards {
+        sequence
+            .iter()
+            .zip(next_sequence.iter())
+            .filter(|(c1, c2)| c1 != c2)
+            .count()
+    } else {
+        sequence
+            .iter()
+            .copied()
+            .zip(next_sequence.reverse_complement_iter())
+            .filter(|(c1, c2)| c1 != c2)
+            .count()
+    };
+    mismatches <= merge_threshold
+}
+
+fn is_palindrome<Subsequence: GenomeSequence<DnaAlphabet, Subsequence> + ?Sized>(
+    sequence: &Subsequence,
+) -> bool {
+    sequence
+        .iter()
+        .copied()
+        .zip(sequence.reverse_complement_iter())
+        .all(|(c1, c2)| c1 == c2)
+}
+
+/// Open the input file for reading, or standard input if the path is `-`.
+/// If `two_pass` is set, the file is scanned for record counts before it is reopened for the actual read.
+fn open_input(path: &Path, two_pass: bool) -> anyhow::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        if two_pass {
+            bail!("Two-pass reading is not possible when reading from standard input");
+        }
+        return Ok(compression::decompress(BufReader::new(std::io::stdin()))?);
+    }
+
+    let open = || {
+        let file = File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("Could not open input file {path:?}"))?;
+        compression::decompress(file).with_context(|| format!("Could not read input file {path:?}"))
+    };
+    if two_pass {
+        info!("Counting records in {path:?}");
+        let counts = count_records(open()?)?;
+        info!(
+            "Found {} records with {} links, the graph will have {} edges",
+            counts.records,
+            counts.links,
+            2 * counts.records
+        );
+    }
+
+    Ok(Box::new(open()?))
+}
+
+/// The conversion options given by the command line, including those that are read from files.
+fn conversion_options(cli: &Cli) -> anyhow::Result<ConversionOptions> {
+    if cli.abundance_histogram_bins == 0 {
+        bail!("The abundance histogram needs at least one bin");
+    }
+    let mut options = ConversionOptions::from(cli);
+    if cli.report_on_signal {
+        options.snapshot_request = Some(SnapshotRequest::on_signal()?);
+    }
+    if let Some(seconds) = cli.timeout {
+        let duration = Duration::try_from_secs_f64(seconds)
+            .with_context(|| format!("Invalid timeout of {seconds} seconds"))?;
+        options.timeout = Some(Timeout::start(duration));
+    }
+    if let Some(path) = &cli.only_nodes_file {
+        options.only_nodes = Some(read_node_list(path)?);
+    }
+    if let Some(path) = &cli.base {
+        options.base = Some(read_arc_centric_file(path, false)?);
+    }
+    if let (Some(track_path), Some(order_path)) =
+        (&cli.emit_read_coverage_wig, &cli.reference_order)
+    {
+        let content = std::fs::read_to_string(order_path)
+            .with_context(|| format!("Could not read {order_path:?}"))?;
+        let name = order_path
+            .file_stem()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let reference_order = ReferenceOrder::parse(name, &content)
+            .with_context(|| format!("Could not parse the reference order {order_path:?}"))?;
+        options.coverage_track = Some((track_path.clone(), reference_order));
+    }
+    if let Some(path) = &cli.include_node_labels {
+        let file = File::open(path).with_context(|| format!("Could not open {path:?}"))?;
+        let labels = read_node_labels(BufReader::new(file))
+            .with_context(|| format!("Could not read node labels {path:?}"))?;
+        info!("Read {} node labels", labels.len());
+        options.node_labels = Some(labels);
+    }
+    if let Some(path) = &cli.relabel_from_mapping {
+        let file = File::open(path).with_context(|| format!("Could not open {path:?}"))?;
+        let mapping = read_node_labels(BufReader::new(file))
+            .with_context(|| format!("Could not read node mapping {path:?}"))?;
+        info!("Read {} node names", mapping.len());
+        options.node_mapping = Some(mapping);
+    }
+    if let Some(pattern) = &cli.filter_sequence_regex {
+        options.sequence_regex = Some(
+            Regex::new(pattern).with_context(|| format!("Malformed sequence regex {pattern:?}"))?,
+        );
+    }
+    if let Some(path) = &cli.suppress_warning_sequences {
+        let sequences = read_sequence_list(path)?;
+        info!("Suppressing warnings for {} sequences", sequences.len());
+        options.suppressed_warning_sequences = Some(sequences);
+    }
+    Ok(options)
+}
+
+/// Convert each input file matching the pattern of `--input-glob` into the output given by `--output-template`.
+/// Logs the status of each file, and fails if any file could not be converted.
+fn run_batch(cli: &Cli, pattern: &str, meter: &mut MemoryMeter) -> anyhow::Result<()> {
+    // Clap ensures that the template is given with a pattern.
+    let Some(output_template) = &cli.output_template else {
+        unreachable!()
+    };
+    let input_paths = glob_paths(pattern)?;
+    if input_paths.is_empty() {
+        bail!("No input files match {pattern:?}");
+    }
+    if input_paths.len() > 1 && !output_template.contains("{name}") {
+        bail!(
+            "The output template {output_template:?} does not contain {{name}}, \
+             but {} input files match {pattern:?}",
+            input_paths.len()
+        );
+    }
+    info!("Converting {} input files", input_paths.len());
+    let options = conversion_options(cli)?;
+
+    let mut failure_count = 0;
+    for input_path in &input_paths {
+        let name = input_path.file_stem().unwrap_or_default().to_string_lossy();
+        let output_path = PathBuf::from(output_template.replace("{name}", &name));
+        info!("Converting {input_path:?} to {output_path:?}");
+        let result = (|| {
+            let k = resolve_k(cli.k, input_path)?;
+            let mut input = open_input(input_path, cli.two_pass)?;
+            let file = File::create(&output_path)
+                .with_context(|| format!("Could not open output file {output_path:?}"))?;
+            let mut output = BufWriter::new(file);
+            node_to_arc_centric_dbg_with_memory_meter(
+                k,
+                &options,
+                &mut input,
+                &mut output,
+                Some(meter),
+            )?;
+            output.flush()?;
+            anyhow::Ok(())
+        })();
+        match result {
+            Ok(()) => info!("Converted {input_path:?}"),
+            Err(error) if cli.fail_fast => {
+                return Err(error.context(format!("Could not convert {input_path:?}")))
+            }
+            Err(error) => {
+                warn!("Could not convert {input_path:?}: {error:#}");
+                failure_count += 1;
+            }
+        }
+    }
+
+    if failure_count > 0 {
+        bail!(
+            "Could not convert {failure_count} of {} input files",
+            input_paths.len()
+        );
+    }
+    info!("Success!");
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let result = run();
+    if let Err(error) = &result {
+        if error.downcast_ref::<TimeoutError>().is_some() {
+            eprintln!("Error: {error:?}");
+            std::process::exit(TIMEOUT_EXIT_CODE);
+        }
+    }
+    result
+}
+
+fn run() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    initialise_logging(cli.log_level);
+    // Created after the logger, such that a failure to initialise the meter is logged.
+    let mut meter = MemoryMeter::new();
+
+    if let Some(command) = &cli.command {
+        return match command {
+            Command::Diff {
+                first,
+                second,
+                details,
+                one_based,
+            } => run_diff(first, second, *details, *one_based),
+            Command::Reformat {
+                input,
+                output,
+                output_format,
+                k,
+                one_based,
+            } => run_reformat(input, output, *output_format, *k, *one_based),
+        };
+    }
+    meter.set_memory_limit(cli.memory_limit);
+    meter.report()?;
+    if let Some(pattern) = &cli.input_glob {
+        return run_batch(&cli, pattern, &mut meter);
+    }
+    // Without a subcommand or batch, clap ensures that one of these is given.
+    let input_path = match (&cli.input, &cli.input_from_process) {
+        (Some(input_path), _) => input_path.as_path(),
+        // Clap ensures that k is given, so the command is only used in messages.
+        (None, Some(command)) => Path::new(command),
+        (None, None) => unreachable!(),
+    };
+
+    let k = resolve_k(cli.k, input_path)?;
+    let config = toml::to_string(&resolved_config(&cli, k))?;
+    info!("Configuration:");
+    for line in config.lines() {
+        info!("  {line}");
+    }
+    let mut produced_files = Vec::new();
+    if let Some(config_path) = &cli.config_out {
+        std::fs::write(config_path, &config)
+            .with_context(|| format!("Could not write {config_path:?}"))?;
+        produced_files.push(ManifestEntry {
+            path: config_path.clone(),
+            role: "config",
+        });
+    }
+    if cli.parse_only {
+        return parse_only(input_path, k, cli.two_pass, cli.input_encoding);
+    }
+    if let Some(counts_path) = &cli.count_kmers_only {
+        info!("Counting k-mers of {input_path:?} with k = {k} and writing to {counts_path:?}");
+        let mut input = open_input(input_path, cli.two_pass)?;
+        if cli.input_encoding.detect(&mut input)? == InputEncoding::Crlf {
+            input = Box::new(CrlfNormalizer::new(input));
+        }
+        let counts = count_kmers(input, k)?;
+        info!("Counted {} distinct k-mers", counts.multiplicities.len());
+        return std::fs::write(counts_path, counts.to_string())
+            .with_context(|| format!("Could not write {counts_path:?}"));
+    }
+    if cli.self_test {
+        info!("Checking that merging the reverse complemental arcs of {input_path:?} is stable");
+        let mut input = Vec::new();
+        open_input(input_path, cli.two_pass)?.read_to_end(&mut input)?;
+        self_test(&input, k)?;
+        info!("The self-test passed");
+        return Ok(());
+    }
+    // Clap ensures that the output is given unless only parsing.
+    let Some(output_path) = &cli.output else {
+        unreachable!()
+    };
+    info!(
+        "Loading graph from {:?} with k = {} and writing to {:?}",
+        input_path, k, output_path
+    );
+    let mut input = if let Some(command) = &cli.input_from_process {
+        compression::decompress(ProcessInput::spawn(command)?)?
+    } else {
+        open_input(input_path, cli.two_pass)?
+    };
+    let options = conversion_options(&cli)?;
+    let (output_paths, report) = if let Some(max_output_size) = cli.max_output_size {
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only split output in the arc-centric format");
+        }
+
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg_with_memory_meter(
+            k,
+            &options,
+            &mut input,
+            &mut output,
+            Some(&mut meter),
+        )?;
+        let output = String::from_utf8(output)?;
+        let part_count = write_split_output(&output, max_output_size, |index| {
+            let path = part_path(output_path, index);
+            let file = File::create(&path).with_context(|| format!("Could not create {path:?}"))?;
+            Ok(BufWriter::new(file))
+        })?;
+        info!("Split output into {part_count} parts");
+        let output_paths = (0..part_count)
+            .map(|index| part_path(output_path, index))
+            .collect();
+        (output_paths, report)
+    } else if let Some(shards) = cli.shards {
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only shard output in the arc-centric format");
+        }
+
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg_with_memory_meter(
+            k,
+            &options,
+            &mut input,
+            &mut output,
+            Some(&mut meter),
+        )?;
+        let output = String::from_utf8(output)?;
+        write_sharded_output(&output, shards, |index| {
+            let path = shard_path(output_path, index);
+            let file = File::create(&path).with_context(|| format!("Could not create {path:?}"))?;
+            Ok(BufWriter::new(file))
+        })?;
+        info!("Partitioned output into {shards} shards");
+        let output_paths = (0..shards)
+            .map(|index| shard_path(output_path, index))
+            .collect();
+        (output_paths, report)
+    } else if cli.split_by_strand {
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only split output by strand in the arc-centric format");
+        }
+        // Clap ensures that both strand files are given.
+        let (Some(forward_path), Some(reverse_path)) = (&cli.forward_out, &cli.reverse_out) else {
+            unreachable!()
+        };
+
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg_with_memory_meter(
+            k,
+            &ConversionOptions {
+                emit_orientation: true,
+                ..options
+            },
+            &mut input,
+            &mut output,
+            Some(&mut meter),
+        )?;
+        let output = String::from_utf8(output)?;
+        let output_paths = vec![
+            forward_path.clone(),
+            reverse_path.clone(),
+            output_path.clone(),
+        ];
+        let mut files = output_paths
+            .iter()
+            .map(|path| {
+                let file =
+                    File::create(path).with_context(|| format!("Could not create {path:?}"))?;
+                Ok(BufWriter::new(file))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let [forward, reverse, merged] = &mut files[..] else {
+            unreachable!()
+        };
+        let [forward_count, reverse_count, merged_count] =
+            write_strand_output(&output, cli.emit_orientation, [forward, reverse, merged])?;
+        info!("Split output into {forward_count} forward, {reverse_count} reverse and {merged_count} merged arcs");
+        (output_paths, report)
+    } else {
+        let write_path = if cli.atomic_output {
+            temporary_output_path(output_path)
+        } else {
+            output_path.clone()
+        };
+        let output = if let Some(resume_from_node) = cli.resume_from_node {
+            info!("Resuming from node {resume_from_node}, appending to {output_path:?}");
+            File::options().append(true).open(output_path)
+        } else if cli.mmap_output {
+            // The file must be readable to be mapped.
+            File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&write_path)
+        } else {
+            File::create(&write_path)
+        };
+        let output =
+            output.with_context(|| format!("Could not open output file {write_path:?}"))?;
+        if let Some(size) = cli.preallocate {
+            preallocate(&output, size).with_context(|| {
+                format!("Could not preallocate {size} bytes for {write_path:?}")
+            })?;
+        }
+        let report = (|| {
+            if cli.mmap_output {
+                let expected_size = cli.preallocate.unwrap_or(0).try_into()?;
+                let mut output = MmapWriter::new(output, expected_size)
+                    .with_context(|| format!("Could not map output file {write_path:?}"))?;
+                let report = node_to_arc_centric_dbg_with_memory_meter(
+                    k,
+                    &options,
+                    &mut input,
+                    &mut output,
+                    Some(&mut meter),
+                )?;
+                output.finish()?;
+                return anyhow::Ok(report);
+            }
+            if cli.parallel_io {
+                let mut output = DoubleBufferedWriter::new(output);
+                let report = node_to_arc_centric_dbg_with_memory_meter(
+                    k,
+                    &options,
+                    &mut input,
+                    &mut output,
+                    Some(&mut meter),
+                )?;
+                let mut output = output.finish()?;
+                if cli.preallocate.is_some() {
+                    truncate_to_position(&mut output)?;
+                }
+                return anyhow::Ok(report);
+            }
+
+            let mut output = BufWriter::new(output);
+            let report = node_to_arc_centric_dbg_with_memory_meter(
+                k,
+                &options,
+                &mut input,
+                &mut output,
+                Some(&mut meter),
+            )?;
+            output.flush()?;
+            if cli.preallocate.is_some() {
+                truncate_to_position(output.get_mut())?;
+            }
+            anyhow::Ok(report)
+        })();
+        if cli.atomic_output {
+            if report.is_ok() {
+                std::fs::rename(&write_path, output_path).with_context(|| {
+                    format!("Could not rename {write_path:?} to {output_path:?}")
+                })?;
+            } else if let Err(error) = std::fs::remove_file(&write_path) {
+                warn!("Could not remove temporary output file {write_path:?}: {error}");
+            }
+        }
+        (vec![output_path.clone()], report?)
+    };
+    let output_role = if cli.max_output_size.is_some() {
+        "part"
+    } else if cli.shards.is_some() {
+        "shard"
+    } else if cli.split_by_strand {
+        "strand"
+    } else {
+        "output"
+    };
+    produced_files.extend(output_paths.iter().map(|path| ManifestEntry {
+        path: path.clone(),
+        role: output_role,
+    }));
+    if let Some(png_path) = &cli.render_png {
+        produced_files.push(ManifestEntry {
+            path: png_path.clone(),
+            role: "png",
+        });
+    }
+
+    if cli.validate_output || cli.strict_bigraph_symmetry {
+        if cli.id_encoding != IdEncoding::Decimal {
+            bail!("Can only validate output with decimal node indices");
+        }
+        if cli.one_based {
+            bail!("Can only validate output with zero-based node indices");
+        }
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only validate output in the arc-centric format");
+        }
+        if cli.preserve_original_ids {
+            bail!("Cannot validate output with original ids, since they are not numeric");
+        }
+        if cli.float_weights {
+            bail!("Cannot validate output with float weights");
+        }
+        if cli.canonical_node_pairs {
+            bail!("Cannot validate output with canonical node pairs, since the arcs lose their direction");
+        }
+        if cli.trim_overlap {
+            bail!("Cannot validate output with trimmed overlaps, since the sequences of mirror arcs are trimmed at different ends");
+        }
+        if report.alphabet == Alphabet::Rna {
+            bail!("Cannot validate output with RNA sequences");
+        }
+        for output_path in &output_paths {
+            info!("Validating output {output_path:?}");
+            if cli.validate_output {
+                output_validation::validate_output(BufReader::new(File::open(output_path)?))?;
+            }
+            if cli.strict_bigraph_symmetry {
+                output_validation::validate_bigraph_symmetry(BufReader::new(File::open(
+                    output_path,
+                )?))?;
+            }
+        }
+    }
+
+    if let Some(mirror_index_path) = &cli.mirror_index {
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only write a mirror index for the arc-centric format");
+        }
+        if cli.id_encoding != IdEncoding::Decimal {
+            bail!("Can only write a mirror index for output with decimal node indices");
+        }
+        if cli.one_based {
+            bail!("Can only write a mirror index for output with zero-based node indices");
+        }
+        if cli.canonical_node_pairs {
+            bail!("Cannot write a mirror index with canonical node pairs, since the arcs lose their direction");
+        }
+        info!("Writing mirror index to {mirror_index_path:?}");
+        let graph = read_arc_centric_graph(BufReader::new(File::open(output_path)?))?;
+        let file = File::create(mirror_index_path)
+            .with_context(|| format!("Could not create {mirror_index_path:?}"))?;
+        let mut output = BufWriter::new(file);
+        write_mirror_index(&graph, &mut output)?;
+        output.flush()?;
+        produced_files.push(ManifestEntry {
+            path: mirror_index_path.clone(),
+            role: "mirror-index",
+        });
+    }
+
+    if let Some(lengths_path) = &cli.lengths_file {
+        if cli.output_format != OutputFormat::ArcCentric {
+            bail!("Can only write a lengths file for the arc-centric format");
+        }
+        info!("Writing sequence lengths to {lengths_path:?}");
+        let graph = read_arc_centric_graph_with_node_indexing(
+            BufReader::new(File::open(output_path)?),
+            cli.id_encoding,
+            cli.one_based,
+        )?;
+        let file = File::create(lengths_path)
+            .with_context(|| format!("Could not create {lengths_path:?}"))?;
+        let mut output = BufWriter::new(file);
+        write_sequence_lengths(&graph, &mut output)?;
+        output.flush()?;
+        produced_files.push(ManifestEntry {
+            path: lengths_path.clone(),
+            role: "lengths",
+        });
+    }
+
+    if let Some(spectrum_path) = &cli.emit_spectrum {
+        info!(
+            "Writing k-mer spectrum of {} k-mers to {spectrum_path:?}",
+            report.kmer_spectrum.kmer_count()
+        );
+        std::fs::write(spectrum_path, report.kmer_spectrum.to_string())
+            .with_context(|| format!("Could not write {spectrum_path:?}"))?;
+        produced_files.push(ManifestEntry {
+            path: spectrum_path.clone(),
+            role: "spectrum",
+        });
+    }
+
+    if let Some(manifest_path) = &cli.manifest {
+        info!(
+            "Writing manifest of {} files to {manifest_path:?}",
+            produced_files.len()
+        );
+        let file = File::create(manifest_path)
+            .with_context(|| format!("Could not create {manifest_path:?}"))?;
+        let mut output = BufWriter::new(file);
+        write_manifest(&produced_files, &mut output)?;
+        output.flush()?;
+    }
+
+    if cli.benchmark {
+        info!("Throughput: {}", report.throughput());
+    }
+    if cli.stats && cli.output_format != OutputFormat::ArcCentric {
+        warn!("Statistics are only collected for the arc-centric format");
+    } else if cli.stats {
+        for line in report.statistics.to_string().lines() {
+            info!("{line}");
+        }
+        let histogram = report
+            .statistics
+            .abundance_histogram(cli.abundance_histogram_bins, cli.abundance_histogram_scale);
+        for line in histogram.to_string().lines() {
+            info!("{line}");
+        }
+    }
+
+    meter.report()?;
+
+    info!("Success!");
+    Ok(())
+}
+
+fn parse_only(
+    input_path: &Path,
+    k: usize,
+    two_pass: bool,
+    input_encoding: InputEncoding,
+) -> anyhow::Result<()> {
+    info!("Parsing graph from {input_path:?} with k = {k}");
+    let mut input = open_input(input_path, two_pass)?;
+    if input_encoding.detect(&mut input)? == InputEncoding::Crlf {
+        input = Box::new(CrlfNormalizer::new(input));
+    }
+    let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::new();
+    let graph = read_graph(input, &mut sequence_store, k)
+        .with_context(|| format!("Could not parse {input_path:?}"))?;
+    info!(
+        "Parsed graph with {} nodes and {} edges",
+        graph.node_count(),
+        graph.edge_count()
+    );
+    Ok(())
+}
+
+/// The path that `--atomic-output` writes to before renaming, e.g. `out.txt.tmp` for `out.txt`.
+fn temporary_output_path(output: &Path) -> PathBuf {
+    let mut path = output.as_os_str().to_owned();
+    path.push(".tmp");
+    PathBuf::from(path)
+}
+
+/// Read an arc-centric graph, in the protobuf format if the file ends in `.pb`.
+/// If `one_based` is set, then the node indices of the text format start at one.
+fn read_arc_centric_file(path: &Path, one_based: bool) -> anyhow::Result<ArcCentricGraph> {
+    let file = File::open(path).with_context(|| format!("Could not open {path:?}"))?;
+    if path.extension().is_some_and(|extension| extension == "pb") {
+        if one_based {
+            bail!("The protobuf format always uses zero-based node indices");
+        }
+        read_protobuf(BufReader::new(file))
+    } else {
+        read_arc_centric_graph_with_node_indexing(
+            BufReader::new(file),
+            IdEncoding::Decimal,
+            one_based,
+        )
+    }
+    .with_context(|| format!("Could not read arc-centric graph {path:?}"))
+}
+
+fn run_diff(first: &Path, second: &Path, details: bool, one_based: bool) -> anyhow::Result<()> {
+    let diff = diff_graphs(
+        &read_arc_centric_file(first, one_based)?,
+        &read_arc_centric_file(second, one_based)?,
+    );
+
+    if diff.is_empty() {
+        println!("The graphs are equal");
+        return Ok(());
+    }
+    if details {
+        for line in diff.details() {
+            println!("{line}");
+        }
+    }
+    println!("{diff}");
+    Ok(())
+}
+
+fn run_reformat(
+    input: &Path,
+    output: &Path,
+    output_format: OutputFormat,
+    k: Option<usize>,
+    one_based: bool,
+) -> anyhow::Result<()> {
+    info!("Reformatting {input:?} to {output_format:?} format in {output:?}");
+    let graph = read_arc_centric_file(input, one_based)?;
+    let file = File::create(output).with_context(|| format!("Could not create {output:?}"))?;
+    let mut output = BufWriter::new(file);
+    write_arc_centric_graph_as(&graph, output_format, k, &mut output)?;
+    output.flush()?;
+    info!("Success!");
+    Ok(())
+}
+
+/// Write an arc-centric graph in the given output format.
+/// The k-mer size is required for the formats that write the overlaps between arcs.
+fn write_arc_centric_graph_as(
+    graph: &ArcCentricGraph,
+    output_format: OutputFormat,
+    k: Option<usize>,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    let require_k = || k.with_context(|| format!("The {output_format:?} format requires -k"));
+    match output_format {
+        OutputFormat::ArcCentric => write_arc_centric_graph(graph, output),
+        OutputFormat::Bcalm2 => bail!("Cannot reformat an arc-centric graph to the bcalm2 format"),
+        OutputFormat::Matrix => write_matrix(graph, output),
+        OutputFormat::Gfa1 => write_gfa1(graph, require_k()?, false, output),
+        OutputFormat::Dot => write_dot(graph, false, output),
+        OutputFormat::Protobuf => write_protobuf(graph, output),
+        OutputFormat::Metis => write_metis(graph, output),
+        OutputFormat::Bifrost => write_bifrost_gfa(graph, require_k()?, output),
+        OutputFormat::Spaligner => write_spaligner_gfa(graph, require_k()?, output),
+        OutputFormat::Newick => write_newick(graph, output),
+        OutputFormat::Dimacs => bail!("Cannot reformat an arc-centric graph to the dimacs format"),
+        OutputFormat::Parquet => write_parquet(graph, output),
+        OutputFormat::Json => write_json(graph, output),
+        OutputFormat::AdjacencyList => write_adjacency_list(graph, true, output),
+        OutputFormat::LineGraph => write_arc_centric_graph(
+            &line_graph(graph, require_k()?, line_graph::DEFAULT_MAX_ARCS)?,
+            output,
+        ),
+        OutputFormat::Csr => write_csr(graph, output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::abundance_histogram::HistogramScale;
+    use crate::alphabet::{Alphabet, SequenceCase};
+    use crate::arc_centric_reader::{
+        read_arc_centric_graph, read_arc_centric_graph_with_id_encoding,
+        read_arc_centric_graph_with_node_indexing, reverse_complement,
+    };
+    use crate::arc_centric_reader::{write_arc_centric_graph, ArcCentricGraph};
+    use crate::ascii_preview::write_ascii_preview;
+    use crate::balance::{find_abundance_violations, find_imbalances};
+    use crate::bcalm2_header::{Bcalm2Header, HeaderInspector};
+    use crate::compression::{detect_compression, Compression};
+    use crate::coverage_track::ReferenceOrder;
+    use crate::diff::{apply_patch, GraphPatch};
+    use crate::diff::{diff_graphs, ArcPair};
+    use crate::format_version::FormatVersion;
+    use crate::global_sort::sort_arc_lines;
+    use crate::id_encoding::IdEncoding;
+    use crate::line_endings::InputEncoding;
+    use crate::link_verification::{verify_links, LinkMismatch};
+    use crate::memory_meter::MemoryMeter;
+    use crate::meta_sidecar::{resolve_k, sidecar_path};
+    use crate::mirror_index::find_mirror_arcs;
+    use crate::mirror_index::write_mirror_index;
+    use crate::node_labels::read_node_labels;
+    use crate::output_validation::{
+        find_arcs_without_mirror, validate_bigraph_symmetry, validate_output,
+    };
+    use crate::parquet_output::NODE_COUNT_KEY;
+    use crate::protobuf_output::read_protobuf;
+    use crate::sequence_lengths::write_sequence_lengths;
+    use crate::snapshot_request::SnapshotRequest;
+    use crate::split_output::{
+        part_path, shard_of, shard_path, write_sharded_output, write_split_output,
+        write_strand_output,
+    };
+    use crate::test_fixtures::{
+        COMPLEX_CIRCULARISED_FILE, COMPLEX_FILE, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE,
+    };
+    use crate::test_logger::capture_logs;
+    use crate::transpose::transpose;
+    use crate::weight_metric::WeightMetric;
+    use crate::{
+        assert_balanced, count_kmers, log_level_from_environment, log_scaled_weight,
+        node_to_arc_centric_dbg, node_to_arc_centric_dbg_with_options, open_input, read_graph,
+        resolved_config, verify_abundance_conservation, write_arc_centric_graph_as, Cli,
+        ConversionOptions, OutputFormat, LOG_LEVEL_VARIABLE,
+    };
+    use arrow_array::{RecordBatch, StringArray, UInt64Array};
+    use bytes::Bytes;
+    use clap::Parser;
+    use genome_graph::bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
+    use genome_graph::bigraph::traitgraph::index::GraphIndex;
+    use genome_graph::bigraph::traitgraph::interface::{
+        ImmutableGraphContainer, MutableGraphContainer,
+    };
+    use genome_graph::compact_genome::implementation::DefaultSequenceStore;
+    use log::{Level, LevelFilter};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use regex::Regex;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::fs::File;
+    use std::io::{BufReader, Write};
+    use std::path::Path;
+    use std::time::Duration;
+
+    #[test]
+    fn test_complex_file() {
+        let mut file = BufReader::new(COMPLEX_FILE.as_bytes());
+
+        let expected = "6
+0 1 42 0 1 ATCGATCGATCGAT
+1 2 43 3 0 TCGATCGATCGATC
+2 3 40 2 3 CGATCGATCGATCG
+2 4 1 5 3 CGATCGATCGATCAGT
+3 0 43 1 2 GATCGATCGATCGA
+5 3 1 2 4 ACTGATCGATCGATCG
+";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut file, &mut output).unwrap();
+        println!("{}", String::from_utf8(output.clone()).unwrap());
+
+        assert_eq!(expected.as_bytes(), output);
+    }
+
+    #[test]
+    fn test_complex_circularised_file() {
+        let mut file = BufReader::new(COMPLEX_CIRCULARISED_FILE.as_bytes());
+
+        let expected = "6
+0 1 40 0 1 CGATCGATCGATCG
+0 2 1 3 1 CGATCGATCGATCTCGATCGATCGAT
+0 4 2 5 1 CGATCGATCGATCA
+1 3 43 2 0 GATCGATCGATCGA
+2 0 43 1 3 TCGATCGATCGATC
+3 1 1 0 2 ATCGATCGATCGAGATCGATCGATCG
+3 2 42 3 2 ATCGATCGATCGAT
+4 5 1 4 5 GATCGATCGATCACTGATCGATCGATC
+4 5 1 4 5 GATCGATCGATCAGTGATCGATCGATC
+5 1 2 0 4 TGATCGATCGATCG
+";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut file, &mut output).unwrap();
+        println!("{}", String::from_utf8(output.clone()).unwrap());
+
+        assert_eq!(expected.as_bytes(), output);
+    }
+
+    #[test]
+    fn test_pseudo_reverse_complemental_arc() {
+        let mut file = BufReader::new(PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE.as_bytes());
+
+        let expected = "10
+0 1 1 0 1 ATATATATATATGGCACCATATATATATAT
+0 1 1 0 1 ATATATATATATGGTGCCATATATATATAT
+1 2 2 2 0 CCATATATATATATAT
+2 0 2 1 2 ATATATATATATATGG
+2 3 1 3 2 ATATATATATATATA
+3 2 1 2 3 TATATATATATATAT
+3 7 1 6 3 TATATATATATATAAAAACAACCGTGTGTGTCCCCCCC
+4 6 1 7 5 ACGGGGGGGGGGACACACA
+6 3 1 3 7 GGGGGGGACACACACGGTTGTTTTTATATATATATATA
+7 5 1 4 6 TGTGTGTCCCCCCCCCCGT
+7 9 1 8 6 TGTGTGTCCCCCCCAGCAT
+8 6 1 7 9 ATGCTGGGGGGGACACACA
+";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(15, &mut file, &mut output).unwrap();
+        println!("{}", String::from_utf8(output.clone()).unwrap());
+
+        assert_eq!(expected.as_bytes(), output);
+    }
+
+    #[test]
+    fn test_pseudo_reverse_complemental_arc_stats() {
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg(
+            15,
+            &mut PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        // The two arcs from node 0 to node 1 are mirrors of each other, but their sequences are not palindromic,
+        // so they are not merged.
+        let statistics = report.statistics;
+        assert_eq!(statistics.self_complemental_arcs, 2);
+        assert_eq!(statistics.palindromic_arcs, 0);
+        assert_eq!(statistics.merged_arcs, 0);
+        assert_eq!(statistics.merged_arc_ratio(), 0.0);
+
+        // Merging the palindromic unitig of a self-loop halves its arcs.
+        let input = ">0 LN:i:4 KC:i:4 km:f:2.0 L:+:0:+ L:-:0:- L:-:0:+ L:+:0:-\nATAT\n";
+        let report = node_to_arc_centric_dbg(3, &mut input.as_bytes(), &mut Vec::new()).unwrap();
+        let statistics = report.statistics;
+        assert_eq!(statistics.self_complemental_arcs, 1);
+        assert_eq!(statistics.palindromic_arcs, 1);
+        assert_eq!(statistics.merged_arcs, 1);
+        assert_eq!(statistics.merged_arc_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_bcalm2_multiplicities() {
+        let strings = [
+            "ACTGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATC",
+            "GATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGA",
+        ];
+        let strings: Vec<_> = strings
+            .iter()
+            .flat_map(|&string| {
+                [
+                    string.to_owned(),
+                    string
+                        .chars()
+                        .rev()
+                        .map(|c| match c {
+                            'A' => 'T',
+                            'C' => 'G',
+                            'G' => 'C',
+                            'T' => 'A',
+                            c => panic!("{c}"),
+                        })
+                        .collect(),
+                ]
+            })
+            .collect();
+
+        let requirements = [
+            ("ATCGATCGATCGAT", 42),
+            ("TCGATCGATCGATC", 43),
+            ("CGATCGATCGATCG", 40),
+            ("CGATCGATCGATCAGT", 1),
+        ];
+
+        for (kmer, count) in requirements {
+            for offset in 0..kmer.len() - 13 {
+                let kmer = &kmer[offset..offset + 14];
+                let mut actual_count = 0;
+                for string in &strings {
+                    let mut string = string.as_str();
+                    while let Some(index) = string.find(kmer) {
+                        actual_count += 1;
+                        string = &string[(index + 1).min(string.len())..];
+                    }
+                }
+                assert_eq!(
+                    count, actual_count,
+                    "kmer {kmer} should match {count} times but matches {actual_count} times"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_kmers() {
+        let strings = [
+            "ACTGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATC",
+            "GATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGA",
+        ];
+        let mut expected_counts = BTreeMap::<String, usize>::new();
+        for string in strings {
+            for offset in 0..string.len() - 13 {
+                let kmer = &string[offset..offset + 14];
+                let reverse_kmer = reverse_complement(kmer);
+                *expected_counts
+                    .entry(kmer.to_owned().min(reverse_kmer))
+                    .or_default() += 1;
+            }
+        }
+
+        let counts = count_kmers(COMPLEX_FILE.as_bytes(), 14).unwrap();
+        assert_eq!(counts.multiplicities, expected_counts);
+    }
+
+    #[test]
+    fn test_bcalm2_circularised_multiplicities() {
+        let strings = [
+            "ACTGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCACTGATCGATCGA",
+            "GATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGATCGAGATCGATCGATCG",
+        ];
+        let strings: Vec<_> = strings
+            .iter()
+            .flat_map(|&string| {
+                [
+                    string.to_owned(),
+                    string
+                        .chars()
+                        .rev()
+                        .map(|c| match c {
+                            'A' => 'T',
+                            'C' => 'G',
+                            'G' => 'C',
+                            'T' => 'A',
+                            c => panic!("{c}"),
+                        })
+                        .collect(),
+                ]
+            })
+            .collect();
+        for string in &strings {
+            println!("{string}");
+        }
+
+        let requirements = [
+            ("ATCGATCGATCGAT", 42),
+            ("CGATCGATCGATCG", 40),
+            ("TCGATCGATCGATC", 43),
+            ("CGATCGATCGATCA", 2),
+            ("GATCGATCGATCAGTGATCGATCGATC", 1),
+            ("CGATCGATCGATCTCGATCGATCGAT", 1),
+        ];
+
+        for (kmer, count) in requirements {
+            for offset in 0..kmer.len() - 13 {
+                let kmer = &kmer[offset..offset + 14];
+                let mut actual_count = 0;
+                for string in &strings {
+                    let mut string = string.as_str();
+                    while let Some(index) = string.find(kmer) {
+                        actual_count += 1;
+                        string = &string[(index + 1).min(string.len())..];
+                    }
+                }
+                assert_eq!(
+                    count, actual_count,
+                    "kmer {kmer} should match {count} times but matches {actual_count} times"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_phase_durations_are_reported() {
+        let mut file = BufReader::new(COMPLEX_FILE.as_bytes());
+
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg(14, &mut file, &mut output).unwrap();
+
+        assert!(report.read_duration > Duration::ZERO);
+        assert!(report.write_duration > Duration::ZERO);
+        assert!(report.total_duration >= report.read_duration + report.write_duration);
+    }
+
+    fn link_mismatches(k: usize, input: &str) -> Vec<LinkMismatch> {
+        let mut headers = Vec::new();
+        let mut sequence_store = DefaultSequenceStore::new();
+        let graph = read_graph(
+            HeaderInspector::new(input.as_bytes(), |header| {
+                headers.extend(Bcalm2Header::parse(header))
+            }),
+            &mut sequence_store,
+            k,
+        )
+        .unwrap();
+        verify_links(&graph, &headers)
+    }
+
+    #[test]
+    fn test_validate_k_consistency() {
+        let convert = |k, input: &str, strict| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    validate_k_consistency: true,
+                    strict,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .map(|_| ())
+        };
+        for (k, file) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            convert(k, file, true).unwrap();
+        }
+
+        // Record 3 starts with the wrong character, such that it does not overlap with record 2.
+        let inconsistent_file = COMPLEX_FILE.replacen("CGATCGATCGATCAGT", "GGATCGATCGATCAGT", 1);
+        let logs = capture_logs(|| convert(14, &inconsistent_file, false).unwrap());
+        let warnings: Vec<_> = logs
+            .iter()
+            .filter(|(level, _)| *level == Level::Warn)
+            .map(|(_, message)| message.as_str())
+            .collect();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("Inconsistent overlap: record >2: L:+:3:+ to record >3"));
+        assert!(warnings[1].starts_with("Inconsistent overlap: record >3: L:-:2:- to record >2"));
+        let error = convert(14, &inconsistent_file, true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Found 2 links that do not overlap by k-1 = 13"
+        );
+    }
+
+    #[test]
+    fn test_verify_links() {
+        assert_eq!(link_mismatches(14, COMPLEX_FILE), Vec::new());
+        assert_eq!(link_mismatches(14, COMPLEX_CIRCULARISED_FILE), Vec::new());
+        assert_eq!(
+            link_mismatches(15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+            Vec::new()
+        );
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                verify_links: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_links_detects_tampered_link() {
+        let tampered = COMPLEX_FILE.replace(
+            ">3 LN:i:16 KC:i:3 km:f:1.0   L:-:2:-",
+            ">3 LN:i:16 KC:i:3 km:f:1.0   L:-:0:-",
+        );
+
+        let mismatches = link_mismatches(14, &tampered);
+        // The reader follows the tampered link, so the mismatch shows up at the other end of the original link.
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].record_id, 2);
+        assert!(mismatches[0].description.starts_with("L:+:3:+"));
+
+        let mut output = Vec::new();
+        assert!(node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                verify_links: true,
+                ..Default::default()
+            },
+            &mut tampered.as_bytes(),
+            &mut output,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_collapse_palindromic_unitigs() {
+        let input = ">0 LN:i:6 KC:i:4 km:f:2.0\nACGCGT\n";
+        let options = ConversionOptions {
+            collapse_palindromic_unitigs: true,
+            ..Default::default()
+        };
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(5, &options, &mut input.as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "2\n0 1 4 0 1 ACGCGT PA:i:1\n"
+        );
+
+        // The palindromes in this fixture are already merged by default, they only gain the marker.
+        let expected = "6
+0 1 42 0 1 ATCGATCGATCGAT PA:i:1
+1 2 43 3 0 TCGATCGATCGATC
+2 3 40 2 3 CGATCGATCGATCG PA:i:1
+2 4 1 5 3 CGATCGATCGATCAGT
+3 0 43 1 2 GATCGATCGATCGA
+5 3 1 2 4 ACTGATCGATCGATCG
+";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &options,
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unitig_shorter_than_k() {
+        let input = ">0 LN:i:6 KC:i:2 km:f:1.0\nACGTTA\n>1 LN:i:5 KC:i:1 km:f:1.0\nCCGTA\n";
+
+        let mut output = Vec::new();
+        let error = node_to_arc_centric_dbg(6, &mut input.as_bytes(), &mut output).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unitig >1 has length 5, which is shorter than k = 6: CCGTA"
+        );
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            6,
+            &ConversionOptions {
+                skip_short: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "8\n0 2 2 3 1 ACGTTA\n3 1 2 0 2 TAACGT\n"
+        );
+    }
+
+    #[test]
+    fn test_two_pass() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("input.fa");
+        std::fs::write(&path, COMPLEX_CIRCULARISED_FILE).unwrap();
+
+        let mut single_pass_output = Vec::new();
+        node_to_arc_centric_dbg(
+            14,
+            &mut open_input(&path, false).unwrap(),
+            &mut single_pass_output,
+        )
+        .unwrap();
+        let mut two_pass_output = Vec::new();
+        node_to_arc_centric_dbg(
+            14,
+            &mut open_input(&path, true).unwrap(),
+            &mut two_pass_output,
+        )
+        .unwrap();
+        assert_eq!(single_pass_output, two_pass_output);
+
+        assert!(open_input(Path::new("-"), true).is_err());
+    }
+
+    #[test]
+    fn test_preserve_original_ids() {
+        let expected = "6
+0+s 0+e 42 0+s 0+e ATCGATCGATCGAT
+0+e 1+s 43 1+e 0+s TCGATCGATCGATC
+1+s 1+e 40 1+s 1+e CGATCGATCGATCG
+1+s 3+e 1 3-s 1+e CGATCGATCGATCAGT
+1+e 0+s 43 0+e 1+s GATCGATCGATCGA
+3-s 1+e 1 1+s 3+e ACTGATCGATCGATCG
+";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                preserve_original_ids: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        // Each arc starts and ends at the unitig ends that the corresponding bcalm2 links connect to.
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_compress_homopolymers() {
+        let input = ">0 LN:i:9 KC:i:4 km:f:1.0\nAAAACGTTG\n";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            3,
+            &ConversionOptions {
+                compress_homopolymers: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "4\n0 2 1 3 1 ACGTG\n3 1 1 0 2 CACGT\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_tags() {
+        let tagged_file = COMPLEX_FILE.replace(" KC:", " XX:i:5 KC:");
+        let convert = |input: &str| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    strip_tags: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            output
+        };
+
+        let mut expected_output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut expected_output).unwrap();
+        assert_ne!(tagged_file, COMPLEX_FILE);
+        assert_eq!(convert(&tagged_file), expected_output);
+        assert_eq!(convert(COMPLEX_FILE), expected_output);
+    }
+
+    #[test]
+    fn test_node_count() {
+        let convert = |node_count| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    node_count: Some(node_count),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+
+        let mut expected_output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut expected_output).unwrap();
+        let expected_output = String::from_utf8(expected_output).unwrap();
+        let (header, arcs) = expected_output.split_once('\n').unwrap();
+        let graph_node_count: usize = header.parse().unwrap();
+
+        let output = convert(graph_node_count + 5).unwrap();
+        assert_eq!(output, format!("{}\n{arcs}", graph_node_count + 5));
+        validate_output(output.as_bytes()).unwrap();
+        assert!(convert(graph_node_count - 1).is_err());
+    }
+
+    #[test]
+    fn test_mirror_index() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut output).unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            let mut index = Vec::new();
+            write_mirror_index(&graph, &mut index).unwrap();
+
+            let mirrors: HashMap<usize, usize> = String::from_utf8(index)
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    let (line, mirror_line) = line.split_once(' ').unwrap();
+                    (line.parse().unwrap(), mirror_line.parse().unwrap())
+                })
+                .collect();
+            assert_eq!(mirrors.len(), graph.arcs.len());
+            let lines: Vec<_> = String::from_utf8(output)
+                .unwrap()
+                .lines()
+                .map(ToOwned::to_owned)
+                .collect();
+            for (&line, &mirror_line) in &mirrors {
+                assert_eq!(mirrors[&mirror_line], line);
+                let arc: Vec<_> = lines[line - 1].split(' ').collect();
+                let mirror: Vec<_> = lines[mirror_line - 1].split(' ').collect();
+                assert_eq!(arc[3..5], mirror[0..2]);
+                assert_eq!(mirror[3..5], arc[0..2]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_arcs() {
+        let convert = |seed| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                15,
+                &ConversionOptions {
+                    sample_arcs: Some(0.5),
+                    seed,
+                    ..Default::default()
+                },
+                &mut PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            validate_output(output.as_slice()).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let mut full_output = Vec::new();
+        node_to_arc_centric_dbg(
+            15,
+            &mut PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE.as_bytes(),
+            &mut full_output,
+        )
+        .unwrap();
+        let full_output = String::from_utf8(full_output).unwrap();
+        for seed in [0, 1, 2] {
+            let sampled_output = convert(Some(seed));
+            assert_eq!(convert(Some(seed)), sampled_output);
+            assert!(sampled_output
+                .lines()
+                .all(|line| full_output.lines().any(|full_line| full_line == line)));
+        }
+        let outputs: HashSet<_> = (0..10).map(|seed| convert(Some(seed))).collect();
+        assert!(outputs.len() > 1);
+    }
+
+    #[test]
+    fn test_limit_arcs() {
+        let mut full_output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut full_output).unwrap();
+        let full_graph = read_arc_centric_graph(full_output.as_slice()).unwrap();
+
+        for limit in [0, 1, 2, 5, full_graph.arcs.len()] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    limit_arcs: Some(limit),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            validate_output(output.as_slice()).unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            assert_eq!(graph.node_count, full_graph.node_count);
+            assert!(graph.arcs.len() >= limit && graph.arcs.len() <= limit + 1);
+            assert!(graph.arcs.iter().all(|arc| full_graph.arcs.contains(arc)));
+        }
+
+        // The first node only has a merged self-complemental arc, so the conversion stops after it.
+        let logs = capture_logs(|| {
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    limit_arcs: Some(1),
+                    report_at: [1, 2].into_iter().collect(),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        });
+        let milestones: Vec<_> = logs
+            .iter()
+            .filter(|(_, message)| message.starts_with("Reached "))
+            .map(|(_, message)| message.as_str())
+            .collect();
+        assert_eq!(milestones, vec!["Reached 1 processed nodes"]);
+    }
+
+    #[test]
+    fn test_transpose() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut full_output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut full_output).unwrap();
+            let full_graph = read_arc_centric_graph(full_output.as_slice()).unwrap();
+
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    transpose: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            validate_output(output.as_slice()).unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            assert_ne!(graph, full_graph);
+            assert_eq!(transpose(&graph), full_graph);
+        }
+    }
+
+    #[test]
+    fn test_abundance_tag() {
+        let convert = |input: &str| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    abundance_tag: Some("ab".to_owned()),
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+
+        assert_eq!(
+            convert(">0 LN:i:6 ab:f:3.0\nACGTAC\n").unwrap(),
+            "4\n0 2 3 3 1 ACGTAC\n3 1 3 0 2 GTACGT\n"
+        );
+        assert!(convert(">0 LN:i:6 KC:i:12 km:f:3.0\nACGTAC\n").is_err());
+    }
+
+    #[test]
+    fn test_suppress_warning_sequences() {
+        // Both unitigs have a non-integer average abundance.
+        let input = ">0 LN:i:6 KC:i:13 km:f:3.2\nACGTAC\n>1 LN:i:5 KC:i:10 km:f:3.3\nAAGCT\n";
+        let logs = capture_logs(|| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    suppressed_warning_sequences: Some(HashSet::from(["ACGTAC".to_owned()])),
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+        });
+
+        let warnings: Vec<_> = logs
+            .iter()
+            .filter(|(_, message)| message.starts_with("Found edge with non-integer"))
+            .map(|(_, message)| message.as_str())
+            .collect();
+        // The unlisted unitig is warned about once for each direction.
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .all(|message| !message.contains("ACGTAC") && !message.contains("GTACGT")));
+    }
+
+    #[test]
+    fn test_lengths_file() {
+        // The palindromic unitig is written once, so it has one length.
+        let input = ">0 LN:i:6 KC:i:12 km:f:3.0\nACGTAC\n>1 LN:i:4 KC:i:4 km:f:2.0\nACGT\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            3,
+            &ConversionOptions {
+                collapse_palindromic_unitigs: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        let mut lengths = Vec::new();
+        write_sequence_lengths(&graph, &mut lengths).unwrap();
+
+        let lengths: Vec<usize> = String::from_utf8(lengths)
+            .unwrap()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let arc_lengths: Vec<_> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(' ').nth(5).unwrap().len())
+            .collect();
+        assert_eq!(lengths, arc_lengths);
+        assert_eq!(lengths.iter().sum::<usize>(), 16);
+    }
+
+    #[test]
+    fn test_kmer_spectrum() {
+        // With k = 3, the unitigs have 4, 2, 3 and 1 k-mers, with mean abundances 3, 5, 3.5 and 1.
+        let input = ">0 LN:i:6 KC:i:12 km:f:3.0\nACGTAC\n\
+                     >1 LN:i:4 KC:i:10 km:f:5.0\nAAGC\n\
+                     >2 LN:i:5 KC:i:10 km:f:3.5\nCCATG\n\
+                     >3 LN:i:3 KC:i:1 km:f:1.0\nGGA\n";
+        let mut output = Vec::new();
+        let report = node_to_arc_centric_dbg_with_options(
+            3,
+            &ConversionOptions {
+                kmer_spectrum: true,
+                float_weights: Some(1),
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        let spectrum = report.kmer_spectrum;
+        assert_eq!(spectrum.kmer_count(), 10);
+        assert_eq!(
+            spectrum.kmer_counts.into_iter().collect::<Vec<_>>(),
+            vec![(1, 1), (3, 7), (5, 2)]
+        );
+    }
+
+    #[test]
+    fn test_global_sort() {
+        let convert = |global_sort| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    preserve_original_ids: true,
+                    global_sort,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let per_node_output = convert(false);
+        let sorted_output = convert(true);
+        assert_ne!(per_node_output, sorted_output);
+
+        let directory = tempfile::tempdir().unwrap();
+        let mut buffered_output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                preserve_original_ids: true,
+                buffered_sort: Some(directory.path().to_owned()),
+                sort_memory_budget: 40,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut buffered_output,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(buffered_output).unwrap(), sorted_output);
+        let mut per_node_lines: Vec<_> = per_node_output.lines().collect();
+        let mut sorted_lines: Vec<_> = sorted_output.lines().collect();
+        assert_eq!(per_node_lines[0], sorted_lines[0]);
+        let keys: Vec<_> = sorted_lines[1..]
+            .iter()
+            .map(|line| {
+                let columns: Vec<_> = line.split(' ').collect();
+                (columns[0], columns[1], columns[5])
+            })
+            .collect();
+        assert!(keys.windows(2).all(|window| window[0] <= window[1]));
+        per_node_lines.sort_unstable();
+        sorted_lines.sort_unstable();
+        assert_eq!(per_node_lines, sorted_lines);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_report_on_signal() {
+        let snapshot_request = SnapshotRequest::on_signal().unwrap();
+        signal_hook::low_level::raise(signal_hook::consts::SIGUSR1).unwrap();
+        let logs = capture_logs(|| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    snapshot_request: Some(snapshot_request.clone()),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+        });
+        let snapshots: Vec<_> = logs
+            .iter()
+            .filter(|(_, message)| message.starts_with("Snapshot: processed 1 / "))
+            .collect();
+        assert_eq!(snapshots.len(), 1);
+        assert!(!snapshot_request.take());
+    }
+
+    #[test]
+    fn test_no_arcs() {
+        // The only unitig is too short, so the graph has two nodes but no arcs.
+        let convert = |output_format| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                5,
+                &ConversionOptions {
+                    output_format,
+                    skip_short: true,
+                    gfa_emit_paths: true,
+                    ..Default::default()
+                },
+                &mut ">0 LN:i:4 KC:i:2 km:f:1.0\nACGT\n".as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(convert(OutputFormat::ArcCentric), "2\n");
+        assert_eq!(
+            convert(OutputFormat::Gfa1),
+            format!(
+                "H\tVN:Z:1.0\tPG:Z:node-to-arc-centric-dbg {}\n",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(convert(OutputFormat::Bifrost), "H\tVN:Z:1.0\n");
+        assert_eq!(convert(OutputFormat::Spaligner), "H\tVN:Z:1.0\n");
+        assert_eq!(
+            convert(OutputFormat::Dot),
+            "digraph dbg {\n    0;\n    1;\n}\n"
+        );
+        assert_eq!(convert(OutputFormat::Matrix), "0 0\n0 0\n");
+        assert_eq!(convert(OutputFormat::Metis), "2 0 001\n\n\n");
+        assert_eq!(
+            convert(OutputFormat::Json),
+            "{\"node_count\": 2, \"arcs\": []}\n"
+        );
+    }
+
+    #[test]
+    fn test_newick_output() {
+        let convert = |k, input: &str| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    output_format: OutputFormat::Newick,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+
+        let branching_file = ">0 LN:i:5 KC:i:2 km:f:1.0 L:+:1:+ L:+:2:+\nACGTC\n\
+            >1 LN:i:5 KC:i:2 km:f:1.0 L:-:0:-\nGTCAG\n\
+            >2 LN:i:5 KC:i:2 km:f:1.0 L:-:0:-\nGTCCA\n";
+        // The unitig 0 branches into 1 and 2, and its reverse complement joins the reverse complements of 1 and 2.
+        assert_eq!(
+            convert(4, branching_file).unwrap(),
+            "((4,6)2)0;\n((5,7)3)1;\n"
+        );
+        assert!(convert(14, COMPLEX_FILE).is_err());
+    }
+
+    #[test]
+    fn test_dot_clusters() {
+        let convert = |dot_clusters| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    output_format: OutputFormat::Dot,
+                    dot_clusters,
+                    ..Default::default()
+                },
+                &mut ">0 LN:i:5 KC:i:6 km:f:2.0\nAAACC\n".as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+
+        // The unitig and its reverse complement do not share a node, so they form two components.
+        let output = convert(true).unwrap();
+        assert_eq!(output.matches("subgraph cluster_").count(), 2);
+        assert!(!convert(false).unwrap().contains("subgraph"));
+    }
+
+    #[test]
+    fn test_verify_revcomp() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut expected_output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut expected_output).unwrap();
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    verify_revcomp: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(output, expected_output);
+        }
+    }
+
+    #[test]
+    fn test_id_encoding() {
+        // Enough nodes for the encodings to use letters.
+        let input: String = (0..15)
+            .map(|id| {
+                let sequence: String = (0..5)
+                    .map(|position| ['A', 'C', 'G', 'T'][(id >> position) % 4])
+                    .collect();
+                format!(">{id} LN:i:5 KC:i:6 km:f:2.0\n{sequence}\n")
+            })
+            .collect();
+        let convert = |id_encoding| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    id_encoding,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            output
+        };
+
+        let decimal_graph =
+            read_arc_centric_graph(convert(IdEncoding::Decimal).as_slice()).unwrap();
+        assert!(decimal_graph.node_count > 36);
+        for id_encoding in [IdEncoding::Base36, IdEncoding::Hex] {
+            let output = convert(id_encoding);
+            assert!(read_arc_centric_graph(output.as_slice()).is_err());
+            let graph =
+                read_arc_centric_graph_with_id_encoding(output.as_slice(), id_encoding).unwrap();
+            assert_eq!(graph, decimal_graph);
+        }
+    }
+
+    #[test]
+    fn test_one_based() {
+        let convert = |input: &str, k, one_based| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    one_based,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            output
+        };
+
+        let output = convert(">0 LN:i:9 KC:i:14 km:f:2.0\nACGTAACGT\n", 3, true);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "2\n1 2 2 1 2 ACGTAACGT\n1 2 2 1 2 ACGTTACGT\n"
+        );
+
+        let zero_based_graph =
+            read_arc_centric_graph(convert(COMPLEX_FILE, 14, false).as_slice()).unwrap();
+        let output = convert(COMPLEX_FILE, 14, true);
+        // The largest one-based node index is out of range for zero-based indices.
+        assert!(read_arc_centric_graph(output.as_slice()).is_err());
+        let graph =
+            read_arc_centric_graph_with_node_indexing(output.as_slice(), IdEncoding::Decimal, true)
+                .unwrap();
+        assert_eq!(graph, zero_based_graph);
+    }
+
+    #[test]
+    fn test_streaming() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut batch_output = Vec::new();
+            let batch_report =
+                node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut batch_output).unwrap();
+            let mut streaming_output = Vec::new();
+            let streaming_report = node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    streaming: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut streaming_output,
+            )
+            .unwrap();
+
+            assert_eq!(batch_output, streaming_output);
+            assert_eq!(batch_report.released_link_record_bytes, 0);
+            assert!(streaming_report.released_link_record_bytes > 0);
+        }
+    }
+
+    #[test]
+    fn test_validate_output() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut output).unwrap();
+            validate_output(output.as_slice()).unwrap();
+        }
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let tampered = String::from_utf8(output)
+            .unwrap()
+            .replace("1 2 43 3 0", "1 2 43 3 1");
+        assert!(validate_output(tampered.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_report_at() {
+        let logs = capture_logs(|| {
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    report_at: [1, 3, 1000].into_iter().collect(),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        });
+        // The graph has less than 1000 nodes, so the last count is never reached.
+        let milestones: Vec<_> = logs
+            .iter()
+            .filter(|(_, message)| message.starts_with("Reached "))
+            .map(|(_, message)| message.as_str())
+            .collect();
+        assert_eq!(
+            milestones,
+            vec!["Reached 1 processed nodes", "Reached 3 processed nodes"]
+        );
+        if MemoryMeter::new().memory_usage().is_some() {
+            let report_count = logs
+                .iter()
+                .filter(|(_, message)| message.starts_with("Current memory usage"))
+                .count();
+            assert_eq!(report_count, 2);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_nodes() {
+        let convert = |shuffle_nodes, seed| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    shuffle_nodes,
+                    seed: Some(seed),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let default_output = convert(false, 0);
+        let shuffled_output = convert(true, 0);
+        assert_eq!(shuffled_output, convert(true, 0));
+        assert_ne!(shuffled_output, default_output);
+
+        let sorted_lines = |output: &str| {
+            let mut lines: Vec<_> = output.lines().map(str::to_owned).collect();
+            lines.sort();
+            lines
+        };
+        assert_eq!(
+            sorted_lines(&shuffled_output),
+            sorted_lines(&default_output)
+        );
+    }
+
+    #[test]
+    fn test_reverse_order() {
+        let convert = |reverse_order| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    reverse_order,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let default_output = convert(false);
+        let reverse_output = convert(true);
+
+        let mut default_lines: Vec<_> = default_output.lines().collect();
+        let mut reverse_lines: Vec<_> = reverse_output.lines().collect();
+        assert_eq!(default_lines[0], reverse_lines[0]);
+        // Group the arcs by their first node, keeping the order of the groups and within the groups.
+        let groups = |lines: &[&str]| {
+            let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+            for line in &lines[1..] {
+                let node = line.split(' ').next().unwrap();
+                match groups.last_mut() {
+                    Some((last_node, group)) if last_node == node => group.push(line.to_string()),
+                    _ => groups.push((node.to_owned(), vec![line.to_string()])),
+                }
+            }
+            groups
+        };
+        let mut default_groups = groups(&default_lines);
+        default_groups.reverse();
+        assert_eq!(groups(&reverse_lines), default_groups);
+
+        default_lines.sort_unstable();
+        reverse_lines.sort_unstable();
+        assert_eq!(default_lines, reverse_lines);
+    }
+
+    #[test]
+    fn test_self_complemental_self_loop() {
+        // The node `AT` is its own reverse complement, and the palindromic unitig is a self-loop on it.
+        let input = ">0 LN:i:4 KC:i:4 km:f:2.0 L:+:0:+ L:-:0:- L:-:0:+ L:+:0:-\nATAT\n";
+        let mut sequence_store = DefaultSequenceStore::new();
+        let graph = read_graph(input.as_bytes(), &mut sequence_store, 3).unwrap();
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.edge_count(), 2);
+        for edge_id in graph.edge_indices() {
+            assert_ne!(graph.mirror_edge_edge_centric(edge_id), Some(edge_id));
+        }
+
+        let convert = |materialize_mirrors| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    materialize_mirrors,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        // Like all merged pairs, the merged self-loop has the weight of both edges.
+        assert_eq!(convert(false), "1\n0 0 4 0 0 ATAT\n");
+        assert_eq!(convert(true), "1\n0 0 2 0 0 ATAT\n0 0 2 0 0 ATAT\n");
+    }
+
+    #[test]
+    fn test_merge_threshold() {
+        // The unitig is its own reverse complement except for the middle character,
+        // so it starts and ends in reverse complemental nodes.
+        let input = ">0 LN:i:9 KC:i:14 km:f:2.0\nACGTAACGT\n";
+        let convert = |merge_threshold| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    merge_threshold,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(convert(0), "2\n0 1 2 0 1 ACGTAACGT\n0 1 2 0 1 ACGTTACGT\n");
+        assert_eq!(convert(1), "2\n0 1 4 0 1 ACGTAACGT\n");
+    }
+
+    #[test]
+    fn test_min_unitig_kmers() {
+        let input = ">0 LN:i:3 KC:i:2 km:f:2.0\nAAC\n>1 LN:i:5 KC:i:6 km:f:2.0\nACCGA\n";
+        let convert = |min_unitig_kmers| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    min_unitig_kmers,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            graph
+                .arcs
+                .into_iter()
+                .map(|arc| arc.sequence)
+                .collect::<Vec<_>>()
+        };
+
+        // The first unitig has a single k-mer.
+        assert_eq!(convert(1), vec!["AAC", "GTT", "ACCGA", "TCGGT"]);
+        assert_eq!(convert(2), vec!["ACCGA", "TCGGT"]);
+        assert!(convert(4).is_empty());
+    }
+
+    #[test]
+    fn test_merge_by_sequence_only() {
+        // Unitig 2 is the reverse complement of unitig 1, and both connect unitig 0 with unitig 3,
+        // so the forwards arc of unitig 1 and the reverse complement arc of unitig 2 are parallel with the same sequence.
+        let input = ">0 LN:i:4 KC:i:2 km:f:1.0 L:+:1:+ L:+:2:-\nAACT\n\
+            >1 LN:i:5 KC:i:6 km:f:2.0 L:-:0:- L:+:3:+\nCTTGA\n\
+            >2 LN:i:5 KC:i:9 km:f:3.0 L:+:0:- L:-:3:+\nTCAAG\n\
+            >3 LN:i:4 KC:i:2 km:f:1.0 L:-:1:- L:-:2:+\nGACC\n";
+        let convert = |merge_by_sequence_only| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    merge_by_sequence_only,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let separate_output = convert(false);
+        assert!(separate_output.contains("2 4 2 5 3 CTTGA\n2 4 3 5 3 CTTGA\n"));
+        assert!(separate_output.contains("5 3 2 2 4 TCAAG\n5 3 3 2 4 TCAAG\n"));
+        let merged_output = convert(true);
+        assert!(merged_output.contains("2 4 5 5 3 CTTGA\n3 1"));
+        assert!(merged_output.contains("5 3 5 2 4 TCAAG\n7 5"));
+        let merged_graph = read_arc_centric_graph(merged_output.as_bytes()).unwrap();
+        assert_eq!(merged_graph.arcs.len(), 6);
+        assert!(find_arcs_without_mirror(&merged_graph).is_empty());
+    }
+
+    #[test]
+    fn test_config_out() {
+        let cli = Cli::try_parse_from([
+            "node-to-arc-centric-dbg",
+            "--input",
+            "input.fa",
+            "--output",
+            "output.txt",
+            "--output-format",
+            "gfa1",
+            "--seed",
+            "3",
+            "--only-nodes",
+            "1,2",
+        ])
+        .unwrap();
+        let config = toml::to_string(&resolved_config(&cli, 15)).unwrap();
+        assert!(config.contains("output-format = \"gfa1\"\n"));
+        assert!(config.contains("k = 15\n"));
+        assert!(config.contains("matrix-max-nodes = 1000\n"));
+
+        let parsed: Cli = toml::from_str(&config).unwrap();
+        assert_eq!(parsed.output_format, OutputFormat::Gfa1);
+        assert_eq!(parsed.seed, Some(3));
+        assert_eq!(parsed.only_nodes, Some(vec![1, 2]));
+        assert_eq!(parsed.input.as_deref(), Some(Path::new("input.fa")));
+        assert_eq!(toml::to_string(&parsed).unwrap(), config);
+    }
+
+    #[test]
+    fn test_strict_bigraph_symmetry() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut output).unwrap();
+            validate_bigraph_symmetry(output.as_slice()).unwrap();
+        }
+
+        // A changed weight keeps the mirror columns consistent, but breaks the symmetry.
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let tampered = String::from_utf8(output)
+            .unwrap()
+            .replace("1 2 43 3 0", "1 2 44 3 0");
+        validate_output(tampered.as_bytes()).unwrap();
+        assert!(validate_bigraph_symmetry(tampered.as_bytes()).is_err());
+
+        // A duplicated arc gives its mirror two reciprocal arcs.
+        let duplicated = "2\n0 1 4 0 1 ACGT\n0 1 2 1 0 AAAC\n1 0 2 0 1 GTTT\n1 0 2 0 1 GTTT\n";
+        assert!(validate_bigraph_symmetry(duplicated.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_fasta_line_width() {
+        let sequence: String = (0..150)
+            .map(|index| ['A', 'C', 'G', 'T'][index * index % 7 % 4])
+            .collect();
+        let input = format!(">0 LN:i:150 KC:i:136 km:f:1.0\n{sequence}\n");
+        let convert = |fasta_line_width| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                15,
+                &ConversionOptions {
+                    output_format: OutputFormat::Bcalm2,
+                    fasta_line_width,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let single_line = convert(0);
+        let wrapped = convert(60);
+        let mut lines = wrapped.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with('>'));
+        let sequence_lines: Vec<_> = lines.collect();
+        assert_eq!(
+            sequence_lines
+                .iter()
+                .map(|line| line.len())
+                .collect::<Vec<_>>(),
+            vec![60, 60, 30]
+        );
+        assert_eq!(
+            format!("{header}\n{}\n", sequence_lines.concat()),
+            single_line
+        );
+    }
+
+    #[test]
+    fn test_bcalm2_output() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut bcalm2_output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    output_format: OutputFormat::Bcalm2,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut bcalm2_output,
+            )
+            .unwrap();
+
+            let mut sequence_store = DefaultSequenceStore::new();
+            let graph = read_graph(bcalm2_output.as_slice(), &mut sequence_store, k).unwrap();
+            let mut sequence_store = DefaultSequenceStore::new();
+            let original_graph = read_graph(input.as_bytes(), &mut sequence_store, k).unwrap();
+            assert_eq!(graph.node_count(), original_graph.node_count());
+            assert_eq!(graph.edge_count(), original_graph.edge_count());
+
+            let mut expected = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut expected).unwrap();
+            let mut actual = Vec::new();
+            node_to_arc_centric_dbg(k, &mut bcalm2_output.as_slice(), &mut actual).unwrap();
+            assert_eq!(
+                String::from_utf8(expected).unwrap(),
+                String::from_utf8(actual).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_colors() {
+        let input = ">0 LN:i:6 KC:i:4 km:f:2.0 CL:Z:10\nACGCGT
+>1 LN:i:6 KC:i:2 km:f:1.0 CL:Z:01 L:+:2:+
+ACGTTA
+>2 LN:i:6 KC:i:2 km:f:1.0 L:-:1:-
+GTTACC
+";
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                colors: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let colors_of = |sequence: &str| {
+            graph
+                .arcs
+                .iter()
+                .filter(|arc| arc.sequence == sequence)
+                .map(|arc| arc.colors.clone())
+                .collect::<Vec<_>>()
+        };
+        // The palindrome is merged into a single arc.
+        assert_eq!(colors_of("ACGCGT"), vec![vec![true, false]]);
+        assert_eq!(colors_of("ACGTTA"), vec![vec![false, true]]);
+        assert_eq!(colors_of("TAACGT"), vec![vec![false, true]]);
+        assert_eq!(colors_of("GTTACC"), vec![Vec::<bool>::new()]);
+    }
+
+    #[test]
+    fn test_progress_interval() {
+        for (interval, expected_lines) in [(0, 0), (1, 10), (3, 3), (10, 1), (11, 0)] {
+            let logs = capture_logs(|| {
+                let mut output = Vec::new();
+                node_to_arc_centric_dbg_with_options(
+                    15,
+                    &ConversionOptions {
+                        progress_interval: interval,
+                        ..Default::default()
+                    },
+                    &mut PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE.as_bytes(),
+                    &mut output,
+                )
+                .unwrap();
+            });
+
+            let progress_lines: Vec<_> = logs
+                .iter()
+                .filter(|(_, message)| message.starts_with("Processed "))
+                .collect();
+            assert_eq!(progress_lines.len(), expected_lines, "interval {interval}");
+            if interval == 3 {
+                assert!(progress_lines[0]
+                    .1
+                    .starts_with("Processed 3 / 10 nodes, ETA "));
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_output_size() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let max_output_size = 100;
+        let directory = tempfile::tempdir().unwrap();
+        let output_path = directory.path().join("out.txt");
+        let part_count = write_split_output(&output, max_output_size, |index| {
+            Ok(File::create(part_path(&output_path, index))?)
+        })
+        .unwrap();
+        assert!(part_count > 1);
+        let parts: Vec<_> = (0..part_count)
+            .map(|index| std::fs::read(part_path(&output_path, index)).unwrap())
+            .collect();
+        assert!(!part_path(&output_path, part_count).exists());
+
+        let mut arc_lines = Vec::new();
+        for part in &parts {
+            assert!(part.len() <= max_output_size);
+            let part = std::str::from_utf8(part).unwrap();
+            assert!(part.starts_with("6\n"));
+            // Each part contains the reverse complement of each of its arcs.
+            validate_output(part.as_bytes()).unwrap();
+            arc_lines.extend(part.lines().skip(1));
+        }
+
+        let mut expected_arc_lines: Vec<_> = output.lines().skip(1).collect();
+        expected_arc_lines.sort_unstable();
+        arc_lines.sort_unstable();
+        assert_eq!(arc_lines, expected_arc_lines);
+
+        assert!(write_split_output(&output, 20, |_| Ok(Vec::new())).is_err());
+    }
+
+    #[test]
+    fn test_matrix_output() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                output_format: OutputFormat::Matrix,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let matrix: Vec<Vec<usize>> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|row| row.split(' ').map(|entry| entry.parse().unwrap()).collect())
+            .collect();
+
+        assert_eq!(matrix.len(), 6);
+        assert!(matrix.iter().all(|row| row.len() == 6));
+        assert_eq!(matrix[0][1], 42);
+        assert_eq!(matrix[1][2], 43);
+        assert_eq!(matrix[2][3], 40);
+        assert_eq!(matrix[2][4], 1);
+        assert_eq!(matrix[3][0], 43);
+        assert_eq!(matrix[5][3], 1);
+        assert_eq!(matrix[1][0], 0);
+        assert_eq!(
+            matrix.iter().flatten().sum::<usize>(),
+            42 + 43 + 40 + 1 + 43 + 1
+        );
+
+        assert!(node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                output_format: OutputFormat::Matrix,
+                matrix_max_nodes: Some(5),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut Vec::new(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_dedup_sequences() {
+        let duplicate_file =
+            ">0 LN:i:6 KC:i:4 km:f:2.0\nACGTTA\n>1 LN:i:6 KC:i:6 km:f:3.0\nACGTTA\n";
+        for (k, input, expected_deduplicated_sequences) in [
+            (14, COMPLEX_FILE, 0),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE, 0),
+            (5, duplicate_file, 1),
+        ] {
+            let mut expected = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut expected).unwrap();
+
+            let mut output = Vec::new();
+            let report = node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    dedup_sequences: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                String::from_utf8(expected).unwrap()
+            );
+            assert_eq!(
+                report.deduplicated_sequences,
+                expected_deduplicated_sequences
+            );
+        }
+    }
+
+    #[test]
+    fn test_materialize_mirrors() {
+        for (k, input, merged_arc_count) in [
+            (14, COMPLEX_FILE, 2),
+            (14, COMPLEX_CIRCULARISED_FILE, 2),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE, 0),
+        ] {
+            let mut merged_output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut merged_output).unwrap();
+            let merged_graph = read_arc_centric_graph(merged_output.as_slice()).unwrap();
+
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    materialize_mirrors: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+            // Each merged arc is written as two arcs with half the weight.
+            assert_eq!(graph.arcs.len(), merged_graph.arcs.len() + merged_arc_count);
+            assert_eq!(
+                graph.arcs.iter().map(|arc| arc.weight).sum::<usize>(),
+                merged_graph
+                    .arcs
+                    .iter()
+                    .map(|arc| arc.weight)
+                    .sum::<usize>()
+            );
+
+            // Each arc has a reciprocal record other than itself.
+            for (index, arc) in graph.arcs.iter().enumerate() {
+                let reverse_complement = reverse_complement(&arc.sequence);
+                assert!(
+                    graph.arcs.iter().enumerate().any(|(mirror_index, mirror)| {
+                        mirror_index != index
+                            && (mirror.from, mirror.to) == (arc.mirror_from, arc.mirror_to)
+                            && (mirror.mirror_from, mirror.mirror_to) == (arc.from, arc.to)
+                            && mirror.sequence == reverse_complement
+                    }),
+                    "arc {index} has no reciprocal record"
+                );
+            }
+        }
+
+        let cli = Cli::try_parse_from([
+            "node-to-arc-centric-dbg",
+            "--input",
+            "input.fa",
+            "--output",
+            "output.txt",
+            "--split-palindromes",
+        ])
+        .unwrap();
+        assert!(ConversionOptions::from(&cli).materialize_mirrors);
+    }
+
+    #[test]
+    fn test_k_from_meta_sidecar() {
+        let directory = tempfile::tempdir().unwrap();
+        let input_path = directory.path().join("complex.unitigs.fa");
+        std::fs::write(&input_path, COMPLEX_FILE).unwrap();
+        assert!(resolve_k(None, &input_path).is_err());
+        assert_eq!(resolve_k(Some(15), &input_path).unwrap(), 15);
+
+        std::fs::write(sidecar_path(&input_path), "kmer-size 14\nabundance-min 2\n").unwrap();
+        let k = resolve_k(None, &input_path).unwrap();
+        assert_eq!(k, 14);
+        assert_eq!(resolve_k(Some(15), &input_path).unwrap(), 15);
+
+        let mut input = open_input(&input_path, false).unwrap();
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(k, &mut input, &mut output).unwrap();
+        let mut expected = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut expected).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reformat() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let reformat = |graph, output_format| {
+            let mut output = Vec::new();
+            write_arc_centric_graph_as(graph, output_format, None, &mut output).unwrap();
+            output
+        };
+        let protobuf_output = reformat(&graph, OutputFormat::Protobuf);
+        let protobuf_graph = read_protobuf(protobuf_output.as_slice()).unwrap();
+        assert_eq!(protobuf_graph, graph);
+        assert_eq!(reformat(&protobuf_graph, OutputFormat::ArcCentric), output);
+
+        let mut gfa_output = Vec::new();
+        assert!(
+            write_arc_centric_graph_as(&graph, OutputFormat::Gfa1, None, &mut gfa_output).is_err()
+        );
+        assert!(write_arc_centric_graph_as(
+            &graph,
+            OutputFormat::Bcalm2,
+            Some(14),
+            &mut gfa_output
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let graph = read_arc_centric_graph(output.as_bytes()).unwrap();
+        assert!(diff_graphs(&graph, &graph).is_empty());
+
+        let modified = output
+            .replace("0 1 42 0 1", "0 1 40 0 1")
+            .replace("2 4 1 5 3 CGATCGATCGATCAGT\n", "")
+            .replace("5 3 1 2 4 ACTGATCGATCGATCG\n", "")
+            + "2 1 3 0 5 GTTTT\n0 5 3 2 1 AAAAC\n";
+        let modified = read_arc_centric_graph(modified.as_bytes()).unwrap();
+
+        let diff = diff_graphs(&graph, &modified);
+        assert_eq!(
+            diff.only_in_first,
+            vec![ArcPair {
+                from: 2,
+                to: 4,
+                sequence: "CGATCGATCGATCAGT".to_owned(),
+            }]
+        );
+        assert_eq!(
+            diff.only_in_second,
+            vec![ArcPair {
+                from: 0,
+                to: 5,
+                sequence: "AAAAC".to_owned(),
+            }]
+        );
+        assert_eq!(
+            diff.differing_weights,
+            vec![(
+                ArcPair {
+                    from: 0,
+                    to: 1,
+                    sequence: "ATCGATCGATCGAT".to_owned(),
+                },
+                42,
+                40
+            )]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "1 arc pairs only in the first graph, 1 only in the second, 1 with differing weights"
+        );
+        assert_eq!(
+            diff.details().collect::<Vec<_>>(),
+            vec![
+                "< 2 -> 4 CGATCGATCGATCAGT",
+                "> 0 -> 5 AAAAC",
+                "~ 0 -> 1 ATCGATCGATCGAT weight 42 -> 40",
+            ]
+        );
+
+        // Reverse complemental arcs are compared as a unit, so their order and direction does not matter.
+        let reordered =
+            output.replace("2 4 1 5 3 CGATCGATCGATCAGT\n", "") + "2 4 1 5 3 CGATCGATCGATCAGT\n";
+        let reordered = read_arc_centric_graph(reordered.as_bytes()).unwrap();
+        assert!(diff_graphs(&graph, &reordered).is_empty());
+    }
+
+    #[test]
+    fn test_trim_overlap() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut output).unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+            let mut trimmed_output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    trim_overlap: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut trimmed_output,
+            )
+            .unwrap();
+            let trimmed_graph = read_arc_centric_graph(trimmed_output.as_slice()).unwrap();
+
+            assert_eq!(graph.arcs.len(), trimmed_graph.arcs.len());
+            for (arc, trimmed_arc) in graph.arcs.iter().zip(&trimmed_graph.arcs) {
+                assert_eq!(trimmed_arc.sequence, arc.sequence[k - 1..]);
+                assert_eq!(trimmed_arc.tags, vec![format!("TR:i:{}", k - 1)]);
+            }
+
+            // Appending a trimmed successor to an arc spells the walk of both arcs.
+            for arc in &graph.arcs {
+                for (successor, trimmed_successor) in graph
+                    .arcs
+                    .iter()
+                    .zip(&trimmed_graph.arcs)
+                    .filter(|(successor, _)| successor.from == arc.to)
+                {
+                    let walk = format!("{}{}", arc.sequence, trimmed_successor.sequence);
+                    assert!(walk.ends_with(&successor.sequence));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_balanced() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut sequence_store = DefaultSequenceStore::new();
+            let graph = read_graph(input.as_bytes(), &mut sequence_store, k).unwrap();
+            assert_eq!(find_imbalances(&graph), Vec::<String>::new());
+
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    assert_balanced: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        }
+
+        let mut sequence_store = DefaultSequenceStore::new();
+        let mut graph = read_graph(COMPLEX_FILE.as_bytes(), &mut sequence_store, 14).unwrap();
+        let edge_id = graph
+            .edge_indices()
+            .find(|&edge_id| graph.mirror_edge_edge_centric(edge_id) != Some(edge_id))
+            .unwrap();
+        graph.remove_edge(edge_id);
+        let imbalances = find_imbalances(&graph);
+        assert_eq!(imbalances.len(), 2, "{imbalances:?}");
+        assert!(imbalances[0].ends_with("has no mirror edge"));
+        assert!(imbalances[1].contains("cannot be paired"));
+        assert!(assert_balanced(&graph).is_err());
+    }
+
+    #[test]
+    fn test_verify_abundance_conservation() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut sequence_store = DefaultSequenceStore::new();
+            let graph = read_graph(input.as_bytes(), &mut sequence_store, k).unwrap();
+            assert_eq!(find_abundance_violations(&graph), Vec::<String>::new());
+
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    verify_abundance_conservation: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        }
+
+        // The reader always gives both edges of a unitig the same abundance, so the violation is crafted in the graph.
+        let mut sequence_store = DefaultSequenceStore::new();
+        let mut graph = read_graph(COMPLEX_FILE.as_bytes(), &mut sequence_store, 14).unwrap();
+        let edge_id = graph
+            .edge_indices()
+            .find(|&edge_id| !graph.edge_data(edge_id).forwards)
+            .unwrap();
+        graph.edge_data_mut(edge_id).total_abundance += 1;
+        let violations = find_abundance_violations(&graph);
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert!(violations[0].contains(&format!("but its mirror edge {} has", edge_id.as_usize())));
+        assert!(verify_abundance_conservation(&graph).is_err());
+    }
+
+    #[test]
+    fn test_log_level_from_environment() {
+        std::env::set_var(LOG_LEVEL_VARIABLE, "debug");
+        assert_eq!(
+            log_level_from_environment(None).unwrap(),
+            LevelFilter::Debug
+        );
+        assert_eq!(
+            log_level_from_environment(Some(LevelFilter::Warn)).unwrap(),
+            LevelFilter::Warn
+        );
+
+        std::env::set_var(LOG_LEVEL_VARIABLE, "force:Trace");
+        assert_eq!(
+            log_level_from_environment(Some(LevelFilter::Warn)).unwrap(),
+            LevelFilter::Trace
+        );
+
+        std::env::set_var(LOG_LEVEL_VARIABLE, "loud");
+        assert!(log_level_from_environment(None).is_err());
+
+        std::env::remove_var(LOG_LEVEL_VARIABLE);
+        assert_eq!(log_level_from_environment(None).unwrap(), LevelFilter::Info);
+        assert_eq!(
+            log_level_from_environment(Some(LevelFilter::Error)).unwrap(),
+            LevelFilter::Error
+        );
+    }
+
+    #[test]
+    fn test_drop_isolated_nodes() {
+        let input = ">0 LN:i:5 KC:i:1 km:f:1.0\nCCGTA\n>1 LN:i:6 KC:i:2 km:f:1.0\nACGTTA\n";
+        let convert = |drop_isolated_nodes| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                6,
+                &ConversionOptions {
+                    skip_short: true,
+                    drop_isolated_nodes,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_eq!(convert(false), "8\n4 6 2 7 5 ACGTTA\n7 5 2 4 6 TAACGT\n");
+        assert_eq!(convert(true), "4\n0 2 2 3 1 ACGTTA\n3 1 2 0 2 TAACGT\n");
+        validate_output(convert(true).as_bytes()).unwrap();
+
+        // Without isolated nodes, the output stays the same.
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                drop_isolated_nodes: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let mut expected = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut expected).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_float_weights() {
+        // Both unitigs have 2 k-mers, with a mean abundance of 3.5 and 2.
+        let input = ">0 LN:i:6 KC:i:7 km:f:3.5\nACGTTA\n>1 LN:i:6 KC:i:4 km:f:2.0\nACGCGT\n";
+        let convert = |options: &ConversionOptions| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(5, options, &mut input.as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let integer_output = convert(&ConversionOptions::default());
+        let float_output = convert(&ConversionOptions {
+            float_weights: Some(2),
+            ..Default::default()
+        });
+        let weights = |output: &str| -> Vec<String> {
+            output
+                .lines()
+                .skip(1)
+                .map(|line| line.split(' ').nth(2).unwrap().to_owned())
+                .collect()
+        };
+        assert_eq!(weights(&integer_output), vec!["3", "3", "4"]);
+        // The palindromic unitig is merged, so its weight is still doubled.
+        assert_eq!(weights(&float_output), vec!["3.50", "3.50", "4.00"]);
+
+        let float_output = convert(&ConversionOptions {
+            float_weights: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(weights(&float_output), vec!["4", "4", "4"]);
+    }
+
+    #[test]
+    fn test_total_occurrences_weight() {
+        // Both unitigs have 2 k-mers, with a total abundance of 7 and 4.
+        let input = ">0 LN:i:6 KC:i:7 km:f:3.5\nACGTTA\n>1 LN:i:6 KC:i:4 km:f:2.0\nACGCGT\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                weight_metric: WeightMetric::TotalOccurrences,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let weights: Vec<_> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(' ').nth(2).unwrap().to_owned())
+            .collect();
+        // The palindromic unitig is merged, so its weight is doubled.
+        assert_eq!(weights, vec!["7", "7", "8"]);
+    }
+
+    #[test]
+    fn test_mean_abundance_per_base_weight() {
+        // Both unitigs have length 6, with a total abundance of 7 and 4.
+        let input = ">0 LN:i:6 KC:i:7 km:f:3.5\nACGTTA\n>1 LN:i:6 KC:i:4 km:f:2.0\nACGCGT\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                weight_metric: WeightMetric::MeanAbundancePerBase,
+                float_weights: Some(2),
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let weights: Vec<_> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(' ').nth(2).unwrap().to_owned())
+            .collect();
+        assert_eq!(weights, vec!["1.17", "1.17", "1.33"]);
+    }
+
+    #[test]
+    fn test_skip_bad_records() {
+        let convert = |input: &str, options: &ConversionOptions| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(14, options, &mut input.as_bytes(), &mut output)
+                .map(|_| String::from_utf8(output).unwrap())
+        };
+        let (records, last_record) = COMPLEX_FILE.rsplit_once('>').unwrap();
+        let corrupt_file = format!("{records}>{}", last_record.replace("LN:i:16", "LN:i:x16"));
+        assert!(convert(&corrupt_file, &ConversionOptions::default()).is_err());
+
+        let options = ConversionOptions {
+            skip_bad_records: true,
+            ..Default::default()
+        };
+        let mut output = String::new();
+        let logs = capture_logs(|| output = convert(&corrupt_file, &options).unwrap());
+        assert_eq!(output, convert(records, &options).unwrap());
+        assert!(logs
+            .iter()
+            .any(|(_, message)| message == "Skipped 1 malformed records"));
+        // The arcs of the fourth unitig are left out.
+        assert_eq!(output.lines().count() - 1, 4);
+    }
+
+    #[test]
+    fn test_resume_from_node() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut expected = Vec::new();
+            node_to_arc_centric_dbg(k, &mut input.as_bytes(), &mut expected).unwrap();
+            let expected = String::from_utf8(expected).unwrap();
+            let graph = read_arc_centric_graph(expected.as_bytes()).unwrap();
+
+            for resume_from_node in 0..=graph.node_count {
+                // Simulate a run that was interrupted after writing the arcs of the first nodes.
+                let mut output: String = expected
+                    .lines()
+                    .take(
+                        1 + graph
+                            .arcs
+                            .iter()
+                            .filter(|arc| arc.from < resume_from_node)
+                            .count(),
+                    )
+                    .map(|line| format!("{line}\n"))
+                    .collect();
+
+                let mut resumed_output = Vec::new();
+                node_to_arc_centric_dbg_with_options(
+                    k,
+                    &ConversionOptions {
+                        resume_from_node: Some(resume_from_node),
+                        ..Default::default()
+                    },
+                    &mut input.as_bytes(),
+                    &mut resumed_output,
+                )
+                .unwrap();
+                output.push_str(std::str::from_utf8(&resumed_output).unwrap());
+                assert_eq!(output, expected, "resume from node {resume_from_node}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_gfa1_paths() {
+        let convert = |k, input: &str| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    output_format: OutputFormat::Gfa1,
+                    gfa_emit_paths: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let paths = |gfa: &str| -> Vec<String> {
+            gfa.lines()
+                .filter(|line| line.starts_with("P\t"))
+                .map(ToOwned::to_owned)
+                .collect()
+        };
+
+        let linear_file = ">0 LN:i:5 KC:i:2 km:f:1.0 L:+:1:+\nACGTC\n\
+            >1 LN:i:5 KC:i:2 km:f:1.0 L:-:0:- L:+:2:+\nGTCAG\n\
+            >2 LN:i:5 KC:i:2 km:f:1.0 L:-:1:-\nCAGGA\n";
+        let gfa = convert(4, linear_file);
+        let (header, gfa) = gfa.split_once('\n').unwrap();
+        let header_tags: Vec<_> = header.split('\t').collect();
+        assert_eq!(header_tags[..2], ["H", "VN:Z:1.0"]);
+        assert_eq!(
+            header_tags[2],
+            format!("PG:Z:node-to-arc-centric-dbg {}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(
+            gfa,
+            "S\t0\tACGTC\tLN:i:5
+S\t1\tGTCAG\tLN:i:5
+S\t2\tCAGGA\tLN:i:5
+L\t0\t+\t1\t+\t3M
+L\t1\t+\t2\t+\t3M
+P\tpath0\t0+,1+,2+\t3M,3M
+"
+        );
+
+        let gfa = convert(14, COMPLEX_FILE);
+        let complex_paths = paths(&gfa);
+        assert!(complex_paths.len() > 1, "{gfa}");
+        assert!(complex_paths.iter().all(|path| path
+            .split('\t')
+            .nth(2)
+            .unwrap()
+            .split(',')
+            .count()
+            < 4));
+        assert_eq!(
+            gfa.lines().filter(|line| line.starts_with("S\t")).count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_parallel_arc_order() {
+        // Unitigs 0, 1 and 2 are parallel arcs from the end of unitig 3 to the start of unitig 4.
+        let parallel_records = [
+            ">0 LN:i:5 KC:i:3 km:f:1.0 L:-:3:- L:+:4:+\nAACCG\n",
+            ">1 LN:i:5 KC:i:3 km:f:1.0 L:-:3:- L:+:4:+\nAATCG\n",
+            ">2 LN:i:5 KC:i:3 km:f:1.0 L:-:3:- L:+:4:+\nAAGCG\n",
+        ];
+        let prefix = ">3 LN:i:4 KC:i:2 km:f:1.0 L:+:0:+ L:+:1:+ L:+:2:+\nGGAA\n\
+            >4 LN:i:4 KC:i:2 km:f:1.0 L:-:0:- L:-:1:- L:-:2:-\nCGTT\n";
+
+        let mut outputs = Vec::new();
+        for order in [[0, 1, 2], [2, 0, 1], [1, 2, 0], [2, 1, 0]] {
+            let input: String = std::iter::once(prefix)
+                .chain(order.iter().map(|&index| parallel_records[index]))
+                .collect();
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg(3, &mut input.as_bytes(), &mut output).unwrap();
+            outputs.push(String::from_utf8(output).unwrap());
+        }
+
+        assert!(outputs.iter().all(|output| output == &outputs[0]));
+        let graph = read_arc_centric_graph(outputs[0].as_bytes()).unwrap();
+        let parallel_sequences: Vec<_> = graph
+            .arcs
+            .iter()
+            .filter(|arc| arc.sequence.len() == 5 && arc.sequence.starts_with("AA"))
+            .map(|arc| arc.sequence.as_str())
+            .collect();
+        assert_eq!(parallel_sequences, vec!["AACCG", "AAGCG", "AATCG"]);
+    }
+
+    #[test]
+    fn test_only_merged() {
+        let convert = |k, input: &str, drop_isolated_nodes| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    only_merged: true,
+                    drop_isolated_nodes,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        // Only the arc of the palindromic unitig is merged with its mirror, the arcs of the other unitig are left out.
+        let input = ">0 LN:i:6 KC:i:4 km:f:2.0\nACGCGT\n>1 LN:i:6 KC:i:2 km:f:1.0\nAAAACC\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(5, &mut input.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "6\n0 1 4 0 1 ACGCGT\n2 4 1 5 3 AAAACC\n5 3 1 2 4 GGTTTT\n"
+        );
+        assert_eq!(convert(5, input, false), "6\n0 1 4 0 1 ACGCGT\n");
+        assert_eq!(
+            convert(14, COMPLEX_FILE, false),
+            "6\n0 1 42 0 1 ATCGATCGATCGAT\n2 3 40 2 3 CGATCGATCGATCG\n"
+        );
+
+        // Nodes that only have unmerged arcs are isolated.
+        assert_eq!(convert(5, input, true), "2\n0 1 4 0 1 ACGCGT\n");
+        assert_eq!(
+            convert(14, COMPLEX_FILE, true),
+            "4\n0 1 42 0 1 ATCGATCGATCGAT\n2 3 40 2 3 CGATCGATCGATCG\n"
+        );
+    }
+
+    /// Compress the data like bgzip, with one gzip member with a `BC` extra subfield per block.
+    fn bgzip(data: &[u8], block_size: usize) -> Vec<u8> {
+        let compress_block = |block: &[u8], block_size_field: u16| {
+            let mut extra = b"BC\x02\x00".to_vec();
+            extra.extend_from_slice(&block_size_field.to_le_bytes());
+            let mut encoder = flate2::GzBuilder::new()
+                .extra(extra)
+                .write(Vec::new(), flate2::Compression::default());
+            encoder.write_all(block).unwrap();
+            encoder.finish().unwrap()
+        };
+
+        let mut output = Vec::new();
+        for block in data.chunks(block_size).chain([&[][..]]) {
+            // The extra subfield contains the size of the compressed block minus one.
+            let compressed_size = compress_block(block, 0).len();
+            output.extend(compress_block(block, (compressed_size - 1) as u16));
+        }
+        output
+    }
+
+    #[test]
+    fn test_compressed_input() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut expected = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut expected).unwrap();
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(COMPLEX_FILE.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let bgzip = bgzip(COMPLEX_FILE.as_bytes(), 50);
+
+        for (name, data, compression) in [
+            (
+                "complex.fa",
+                COMPLEX_FILE.as_bytes().to_vec(),
+                Compression::None,
+            ),
+            ("complex.fa.gz", gzip, Compression::Gzip),
+            ("complex.fa.bgz", bgzip, Compression::Bgzip),
+        ] {
+            assert_eq!(
+                detect_compression(&mut data.as_slice()).unwrap(),
+                compression
+            );
+            let path = directory.path().join(name);
+            std::fs::write(&path, data).unwrap();
+
+            for two_pass in [false, true] {
+                let mut input = open_input(&path, two_pass).unwrap();
+                let mut output = Vec::new();
+                node_to_arc_centric_dbg(14, &mut input, &mut output).unwrap();
+                assert_eq!(output, expected, "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_annotate_nodes() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                annotate_nodes: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            "6
+# node 0 out=1
+0 1 42 0 1 ATCGATCGATCGAT
+# node 1 out=1
+1 2 43 3 0 TCGATCGATCGATC
+# node 2 out=2
+2 3 40 2 3 CGATCGATCGATCG
+2 4 1 5 3 CGATCGATCGATCAGT
+# node 3 out=1
+3 0 43 1 2 GATCGATCGATCGA
+# node 5 out=1
+5 3 1 2 4 ACTGATCGATCGATCG
+"
+        );
+        validate_output(output.as_slice()).unwrap();
+    }
+
+    #[test]
+    fn test_alphabet_autodetect() {
+        let rna_file: String = COMPLEX_FILE
+            .lines()
+            .map(|line| {
+                if line.starts_with('>') {
+                    format!("{line}\n")
+                } else {
+                    format!("{}\n", line.replace('T', "U"))
+                }
+            })
+            .collect();
+        let convert = |input: &str, alphabet| {
+            let mut output = Vec::new();
+            let report = node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    alphabet,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .map(|report| report.alphabet);
+            (String::from_utf8(output).unwrap(), report)
+        };
+
+        let (dna_output, alphabet) = convert(COMPLEX_FILE, Alphabet::Auto);
+        assert_eq!(alphabet.unwrap(), Alphabet::Dna);
+        let (rna_output, alphabet) = convert(&rna_file, Alphabet::Auto);
+        assert_eq!(alphabet.unwrap(), Alphabet::Rna);
+        let expected: String = dna_output
+            .lines()
+            .map(|line| match line.rsplit_once(' ') {
+                Some((columns, sequence)) => format!("{columns} {}\n", sequence.replace('T', "U")),
+                None => format!("{line}\n"),
+            })
+            .collect();
+        assert_eq!(rna_output, expected);
+        assert_eq!(convert(&rna_file, Alphabet::Rna).0, expected);
+
+        let mixed_file = format!("{COMPLEX_FILE}\n>4 LN:i:14 KC:i:20 km:f:20.0\nACGUACGUACGUAC\n");
+        assert!(convert(&mixed_file, Alphabet::Auto).1.is_err());
+    }
+
+    #[test]
+    fn test_render_png() {
+        if std::process::Command::new("dot")
+            .arg("-V")
+            .output()
+            .is_err()
+        {
+            eprintln!("Skipping test, since `dot` is not installed");
+            return;
+        }
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("graph.png");
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                render_png: Some(path.clone()),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+    }
+
+    #[test]
+    fn test_render_max_nodes() {
+        let result = node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                render_png: Some("graph.png".into()),
+                render_max_nodes: Some(5),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+        assert!(!Path::new("graph.png").exists());
+    }
+
+    #[test]
+    fn test_shards() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let shard_count = 3;
+        let directory = tempfile::tempdir().unwrap();
+        let output_path = directory.path().join("out.txt");
+        write_sharded_output(&output, shard_count, |index| {
+            Ok(File::create(shard_path(&output_path, index))?)
+        })
+        .unwrap();
+
+        let mut arc_lines = Vec::new();
+        for index in 0..shard_count {
+            let shard = std::fs::read_to_string(shard_path(&output_path, index)).unwrap();
+            assert!(shard.starts_with("6\n"));
+            // Each shard contains the reverse complement of each of its arcs.
+            validate_output(shard.as_bytes()).unwrap();
+            for line in shard.lines().skip(1) {
+                assert_eq!(shard_of(line, shard_count).unwrap(), index);
+                arc_lines.push(line.to_owned());
+            }
+        }
+
+        let mut expected_arc_lines: Vec<_> = output.lines().skip(1).collect();
+        expected_arc_lines.sort_unstable();
+        arc_lines.sort_unstable();
+        assert_eq!(arc_lines, expected_arc_lines);
+    }
+
+    #[test]
+    fn test_split_by_strand() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                emit_orientation: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        for keep_orientation_tags in [false, true] {
+            let mut strands = [Vec::new(), Vec::new(), Vec::new()];
+            let [forward, reverse, merged] = &mut strands;
+            let arc_counts =
+                write_strand_output(&output, keep_orientation_tags, [forward, reverse, merged])
+                    .unwrap();
+            // The two palindromic unitigs are merged.
+            assert_eq!(arc_counts, [2, 2, 2]);
+
+            let strands = strands.map(|strand| String::from_utf8(strand).unwrap());
+            for strand in &strands {
+                assert!(strand.starts_with("6\n"));
+                assert_eq!(strand.contains("OR:A:"), keep_orientation_tags);
+            }
+            if keep_orientation_tags {
+                for (strand, tag) in strands.iter().zip(["OR:A:+", "OR:A:-"]) {
+                    assert!(strand.lines().skip(1).all(|line| line.ends_with(tag)));
+                }
+            }
+            let merged = read_arc_centric_graph(strands[2].as_bytes()).unwrap();
+            assert!(merged
+                .arcs
+                .iter()
+                .all(|arc| (arc.from, arc.to) == (arc.mirror_from, arc.mirror_to)));
+        }
+    }
+
+    #[test]
+    fn test_benchmark() {
+        let mut output = Vec::new();
+        let report =
+            node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        assert_eq!(report.input_bytes, COMPLEX_FILE.len());
+        assert_eq!(report.output_bytes, output.len());
+        assert_eq!(report.arc_count, 8);
+
+        let throughput = report.throughput();
+        assert!(throughput.arcs_per_second > 0.0);
+        assert!(throughput.input_megabytes_per_second > 0.0);
+        assert!(throughput.output_megabytes_per_second > 0.0);
+    }
+
+    #[test]
+    fn test_dimacs_output() {
+        let convert = |flow_terminals| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    output_format: OutputFormat::Dimacs,
+                    flow_terminals,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let dimacs = convert(Some((0, 3))).unwrap();
+        let lines: Vec<_> = dimacs.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("p max {} {}", graph.node_count, graph.arcs.len())
+        );
+        assert_eq!(lines[1..3], ["n 1 s", "n 4 t"]);
+        assert_eq!(lines.len(), 3 + graph.arcs.len());
+        for (line, arc) in lines[3..].iter().zip(&graph.arcs).take(2) {
+            assert_eq!(
+                *line,
+                format!("a {} {} {}", arc.from + 1, arc.to + 1, arc.weight)
+            );
+        }
+
+        assert!(convert(None).is_err());
+        assert!(convert(Some((0, graph.node_count))).is_err());
+    }
+
+    #[test]
+    fn test_parquet_output() {
+        let convert = |output_format| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    output_format,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            output
+        };
+        let text_graph =
+            read_arc_centric_graph(convert(OutputFormat::ArcCentric).as_slice()).unwrap();
+
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(Bytes::from(convert(OutputFormat::Parquet)))
+                .unwrap();
+        let node_count = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.key == NODE_COUNT_KEY)
+            .and_then(|entry| entry.value.clone());
+        assert_eq!(node_count, Some(text_graph.node_count.to_string()));
+        let batches: Vec<_> = reader.build().unwrap().map(Result::unwrap).collect();
+        assert_eq!(
+            batches.iter().map(RecordBatch::num_rows).sum::<usize>(),
+            text_graph.arcs.len()
+        );
+
+        let batch = &batches[0];
+        let number_column = |name| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .unwrap()
+        };
+        let sequences = batch
+            .column_by_name("sequence")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        for (row, arc) in text_graph.arcs.iter().enumerate().take(5) {
+            assert_eq!(number_column("from").value(row), arc.from as u64);
+            assert_eq!(number_column("to").value(row), arc.to as u64);
+            assert_eq!(number_column("weight").value(row), arc.weight as u64);
+            assert_eq!(
+                number_column("mirror_from").value(row),
+                arc.mirror_from as u64
+            );
+            assert_eq!(number_column("mirror_to").value(row), arc.mirror_to as u64);
+            assert_eq!(sequences.value(row), arc.sequence);
+        }
+    }
+
+    #[test]
+    fn test_protobuf_output() {
+        let input = ">0 LN:i:6 KC:i:4 km:f:2.0 CL:Z:10\nACGCGT
+>1 LN:i:6 KC:i:2 km:f:1.0 CL:Z:01 L:+:2:+
+ACGTTA
+>2 LN:i:6 KC:i:2 km:f:1.0 L:-:1:-
+GTTACC
+";
+        let convert = |output_format| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                5,
+                &ConversionOptions {
+                    output_format,
+                    colors: true,
+                    collapse_palindromic_unitigs: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            output
+        };
+
+        let text_graph =
+            read_arc_centric_graph(convert(OutputFormat::ArcCentric).as_slice()).unwrap();
+        let protobuf_graph = read_protobuf(convert(OutputFormat::Protobuf).as_slice()).unwrap();
+        assert_eq!(protobuf_graph, text_graph);
+        assert!(protobuf_graph.arcs.iter().any(|arc| !arc.colors.is_empty()));
+        assert!(protobuf_graph.arcs.iter().any(|arc| !arc.tags.is_empty()));
+    }
+
+    #[test]
+    fn test_abundance_outliers() {
+        let mut output = Vec::new();
+        let logs = capture_logs(|| {
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    abundance_outliers: Some(1),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+        });
+        let output = String::from_utf8(output).unwrap();
+        let arcs: Vec<Vec<_>> = output
+            .lines()
+            .skip(1)
+            .map(|line| line.split(' ').collect())
+            .collect();
+        let weight = |arc: &Vec<&str>| arc[2].parse::<usize>().unwrap();
+        let highest = arcs.iter().max_by_key(|arc| weight(arc)).unwrap();
+        let lowest = arcs.iter().min_by_key(|arc| weight(arc)).unwrap();
+
+        let messages: Vec<_> = logs.into_iter().map(|(_, message)| message).collect();
+        let outlier_message = |arc: &Vec<&str>| {
+            format!(
+                "  {} -> {} weight {} {}",
+                arc[0],
+                arc[1],
+                arc[2],
+                &arc[5][..arc[5].len().min(20)]
+            )
+        };
+        let position = messages
+            .iter()
+            .position(|message| message == "Arcs with the highest weights:")
+            .unwrap();
+        assert_eq!(messages[position + 1], outlier_message(highest));
+        assert_eq!(messages[position + 2], "Arcs with the lowest weights:");
+        assert_eq!(messages[position + 3], outlier_message(lowest));
+        assert_eq!(weight(highest), 43);
+        assert_eq!(weight(lowest), 1);
+    }
+
+    #[test]
+    fn test_cut_at() {
+        let mut full_output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut full_output).unwrap();
+        let full_graph = read_arc_centric_graph(full_output.as_slice()).unwrap();
+
+        for cut_at in 0..=full_graph.node_count {
+            let mut output = Vec::new();
+            let logs = capture_logs(|| {
+                node_to_arc_centric_dbg_with_options(
+                    14,
+                    &ConversionOptions {
+                        cut_at: Some(cut_at),
+                        ..Default::default()
+                    },
+                    &mut COMPLEX_FILE.as_bytes(),
+                    &mut output,
+                )
+                .unwrap();
+            });
+            validate_output(output.as_slice()).unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+            let crosses = |from: usize, to: usize| (from < cut_at) != (to < cut_at);
+            let expected_arcs: Vec<_> = full_graph
+                .arcs
+                .iter()
+                .filter(|arc| crosses(arc.from, arc.to) || crosses(arc.mirror_from, arc.mirror_to))
+                .cloned()
+                .collect();
+            assert_eq!(graph.node_count, full_graph.node_count);
+            assert_eq!(graph.arcs, expected_arcs);
+            let message = format!("The cut at node {cut_at} has size {}", expected_arcs.len());
+            assert!(logs.iter().any(|(_, logged)| *logged == message));
+        }
+
+        // A one-based cut index is converted to a zero-based one.
+        let cli = Cli::try_parse_from([
+            "node-to-arc-centric-dbg",
+            "--input",
+            "input.fa",
+            "--output",
+            "output.txt",
+            "--cut-at",
+            "4",
+            "--one-based",
+        ])
+        .unwrap();
+        assert_eq!(ConversionOptions::from(&cli).cut_at, Some(3));
+    }
+
+    #[test]
+    fn test_only_nodes() {
+        let convert = |only_nodes: &[usize]| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    only_nodes: Some(only_nodes.iter().copied().collect()),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .map(|_| String::from_utf8(output).unwrap())
+        };
+
+        // Arc 1 2 is the mirror of arc 3 0, and arc 5 3 is the mirror of arc 2 4.
+        assert_eq!(
+            convert(&[0, 4]).unwrap(),
+            "6
+0 1 42 0 1 ATCGATCGATCGAT
+1 2 43 3 0 TCGATCGATCGATC
+2 4 1 5 3 CGATCGATCGATCAGT
+3 0 43 1 2 GATCGATCGATCGA
+5 3 1 2 4 ACTGATCGATCGATCG
+"
+        );
+        // Only nodes 2 to 5 remain, and are renumbered.
+        assert_eq!(
+            convert(&[4]).unwrap(),
+            "4
+0 2 1 3 1 CGATCGATCGATCAGT
+3 1 1 0 2 ACTGATCGATCGATCG
+"
+        );
+        assert!(convert(&[6]).is_err());
+    }
+
+    #[test]
+    fn test_filter_sequence_regex() {
+        let convert = |pattern| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    sequence_regex: Some(Regex::new(pattern).unwrap()),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        // The motif occurs in arc 2 4, and its reverse complement in the mirror arc 5 3.
+        let expected = "6\n2 4 1 5 3 CGATCGATCGATCAGT\n5 3 1 2 4 ACTGATCGATCGATCG\n";
+        assert_eq!(convert("CAGT"), expected);
+        // Matching only the sequence of the mirror arc keeps both arcs.
+        assert_eq!(convert("^ACTG"), expected);
+        assert_eq!(convert("GGGG"), "6\n");
+    }
+
+    #[test]
+    fn test_emit_gc() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                emit_gc: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        // ATCGATCGATCGAT has 6 of 14 characters G or C.
+        let arc = graph
+            .arcs
+            .iter()
+            .find(|arc| arc.sequence == "ATCGATCGATCGAT")
+            .unwrap();
+        assert_eq!(arc.tags, vec!["GC:f:0.4286".to_owned()]);
+        // The GC content is invariant under reverse complement.
+        for arc in &graph.arcs {
+            let mirror = graph
+                .arcs
+                .iter()
+                .find(|mirror| {
+                    (mirror.from, mirror.to) == (arc.mirror_from, arc.mirror_to)
+                        && mirror.sequence == reverse_complement(&arc.sequence)
+                })
+                .unwrap();
+            assert_eq!(mirror.tags, arc.tags);
+        }
+    }
+
+    #[test]
+    fn test_emit_arc_hash() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                emit_arc_hash: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let mut hashes = HashSet::new();
+        for arc in &graph.arcs {
+            let mirror = graph
+                .arcs
+                .iter()
+                .find(|mirror| {
+                    (mirror.from, mirror.to) == (arc.mirror_from, arc.mirror_to)
+                        && mirror.sequence == reverse_complement(&arc.sequence)
+                })
+                .unwrap();
+            assert_eq!(mirror.tags, arc.tags);
+            assert!(arc.tags[0].starts_with("AH:Z:"));
+            hashes.insert(arc.tags[0].clone());
+        }
+        // Each unitig has its own hash.
+        assert_eq!(hashes.len(), COMPLEX_FILE.matches('>').count());
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let crlf_file = format!("{}\r\n", COMPLEX_FILE.replace('\n', "\r\n"));
+        let convert = |input: &str, input_encoding| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    input_encoding,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let expected = convert(COMPLEX_FILE, InputEncoding::Lf);
+        assert_eq!(convert(&crlf_file, InputEncoding::Crlf), expected);
+        assert_eq!(convert(&crlf_file, InputEncoding::Auto), expected);
+        assert_eq!(convert(COMPLEX_FILE, InputEncoding::Crlf), expected);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut output = Vec::new();
+        let report =
+            node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let statistics = report.statistics;
+        assert_eq!(statistics.node_count(), graph.node_count);
+        assert_eq!(statistics.arc_count(), graph.arcs.len());
+        for scale in [HistogramScale::Linear, HistogramScale::Log] {
+            let histogram = statistics.abundance_histogram(3, scale);
+            assert_eq!(histogram.arc_count(), graph.arcs.len());
+            assert_eq!(histogram.bins.last().unwrap().end, 44);
+        }
+        assert_eq!(
+            statistics.out_degrees.into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 4), (2, 1)]
+        );
+        assert_eq!(
+            statistics.sequence_lengths.into_iter().collect::<Vec<_>>(),
+            vec![(8, 4), (16, 2)]
+        );
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", feature = "memory-metering"))]
+    fn test_memory_limit() {
+        let mut meter = MemoryMeter::new();
+        meter.set_memory_limit(Some(0));
+        let mut output = Vec::new();
+        let result = crate::node_to_arc_centric_dbg_with_memory_meter(
+            14,
+            &ConversionOptions::default(),
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+            Some(&mut meter),
+        );
+        // The memory meter may not be available in restricted environments.
+        if meter.report().is_ok() {
+            return;
+        }
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("exceeds the limit of 0MiB"), "{error}");
+        // The conversion aborts before writing the graph.
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_weights() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                float_weights: Some(4),
+                normalize_weights: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let weights: Vec<_> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.split(' ').nth(2).unwrap().to_owned())
+            .collect();
+        // The integer weights are 42, 43, 40, 1, 43 and 1.
+        assert_eq!(
+            weights,
+            vec!["0.9767", "1.0000", "0.9302", "0.0233", "1.0000", "0.0233"]
+        );
+    }
+
+    #[test]
+    fn test_include_node_labels() {
+        let labels = read_node_labels("2\tEscherichia\n4\tBacillus\n".as_bytes()).unwrap();
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                node_labels: Some(labels),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "6
+0 1 42 0 1 ATCGATCGATCGAT
+1 2 43 3 0 TCGATCGATCGATC TL:Z:Escherichia
+2 3 40 2 3 CGATCGATCGATCG FL:Z:Escherichia
+2 4 1 5 3 CGATCGATCGATCAGT FL:Z:Escherichia TL:Z:Bacillus
+3 0 43 1 2 GATCGATCGATCGA
+5 3 1 2 4 ACTGATCGATCGATCG
+"
+        );
+    }
+
+    #[test]
+    fn test_relabel_from_mapping() {
+        let mapping: HashMap<_, _> = (0..6).map(|node| (node, format!("n{node}"))).collect();
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                node_mapping: Some(mapping.clone()),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "6
+n0 n1 42 n0 n1 ATCGATCGATCGAT
+n1 n2 43 n3 n0 TCGATCGATCGATC
+n2 n3 40 n2 n3 CGATCGATCGATCG
+n2 n4 1 n5 n3 CGATCGATCGATCAGT
+n3 n0 43 n1 n2 GATCGATCGATCGA
+n5 n3 1 n2 n4 ACTGATCGATCGATCG
+"
+        );
+
+        let mut partial_mapping = mapping;
+        partial_mapping.remove(&4);
+        let result = node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                node_mapping: Some(partial_mapping.clone()),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                node_mapping: Some(partial_mapping),
+                allow_unmapped: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("n2 4 1 n5 n3 CGATCGATCGATCAGT\n"));
+    }
+
+    #[test]
+    fn test_adjacency_list_output() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                output_format: OutputFormat::AdjacencyList,
+                no_sequences: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 6);
+        // Node 2 has one arc to node 3 and one to node 4.
+        assert_eq!(output.lines().nth(2).unwrap(), "2: 3 40 4 1");
+    }
+
+    #[test]
+    fn test_coverage_track() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("coverage.bedgraph");
+        let track = |unitigs| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    coverage_track: Some((
+                        path.clone(),
+                        ReferenceOrder {
+                            name: "chr1".to_owned(),
+                            unitigs,
+                        },
+                    )),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            std::fs::read_to_string(&path).unwrap()
+        };
+
+        // Unitig 2 has one k-mer with abundance 43, and unitig 3 has three k-mers with abundance 1.
+        assert_eq!(
+            track(vec![(2, true), (3, true)]),
+            "track type=bedGraph name=\"chr1 coverage\"\n\
+             chr1\t0\t1\t43\n\
+             chr1\t1\t2\t22\n\
+             chr1\t2\t3\t15\n\
+             chr1\t3\t14\t11.5\n\
+             chr1\t14\t17\t1\n"
+        );
+        assert_eq!(
+            track(vec![(3, false), (2, false)]),
+            "track type=bedGraph name=\"chr1 coverage\"\n\
+             chr1\t0\t3\t1\n\
+             chr1\t3\t14\t11.5\n\
+             chr1\t14\t15\t15\n\
+             chr1\t15\t16\t22\n\
+             chr1\t16\t17\t43\n"
+        );
+    }
+
+    #[test]
+    fn test_dump_intermediate_graph() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("intermediate.txt");
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                dump_intermediate_graph: Some(path.clone()),
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        let graph = read_graph(
+            COMPLEX_FILE.as_bytes(),
+            &mut DefaultSequenceStore::<super::DnaAlphabet>::new(),
+            14,
+        )
+        .unwrap();
+        let dump = std::fs::read_to_string(&path).unwrap();
+        let mut lines = dump.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            format!("{} {}", graph.node_count(), graph.edge_count())
+        );
+        assert_eq!(lines.count(), graph.edge_count());
+        // The merged palindrome is still two separate edges.
+        assert!(dump.contains(" + ") && dump.contains(" - "));
+    }
+
+    #[test]
+    fn test_verify_input_sorted() {
+        let input = ">0 LN:i:5 KC:i:2 km:f:1.0\nAAACC\n\
+            >2 LN:i:5 KC:i:2 km:f:1.0\nCCGTA\n\
+            >1 LN:i:5 KC:i:2 km:f:1.0\nGGATC\n";
+        let convert = |strict| {
+            node_to_arc_centric_dbg_with_options(
+                4,
+                &ConversionOptions {
+                    verify_input_sorted: true,
+                    strict,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut Vec::new(),
+            )
+        };
+
+        let logs = capture_logs(|| {
+            convert(false).unwrap();
+        });
+        assert!(logs.iter().any(|(level, message)| *level == Level::Warn
+            && message.starts_with("Found 1 input records")
+            && message.ends_with("1 after 2")));
+        assert!(convert(true).is_err());
+    }
+
+    #[test]
+    fn test_line_graph_output() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                output_format: OutputFormat::LineGraph,
+                line_graph_max_arcs: 100,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        validate_output(output.as_slice()).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        // One node per arc of the converted graph, and one arc per pair of consecutive arcs.
+        assert_eq!(graph.node_count, 6);
+        assert_eq!(graph.arcs.len(), 6);
+        assert!(graph.arcs.iter().all(|arc| arc.sequence.len() == 15));
+    }
+
+    #[test]
+    fn test_metis_output() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                output_format: OutputFormat::Metis,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0], "6 6 001");
+        // Node 3 has arcs from node 2 and from node 5, and an arc to node 0.
+        assert_eq!(lines[4], "1 43 3 40 6 1");
+    }
+
+    #[test]
+    fn test_sequence_case() {
+        let convert = |sequence_case| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    sequence_case,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let upper_output = convert(SequenceCase::Upper);
+        assert_eq!(convert(SequenceCase::Preserve), upper_output);
+        assert_eq!(
+            convert(SequenceCase::Lower),
+            "6
+0 1 42 0 1 atcgatcgatcgat
+1 2 43 3 0 tcgatcgatcgatc
+2 3 40 2 3 cgatcgatcgatcg
+2 4 1 5 3 cgatcgatcgatcagt
+3 0 43 1 2 gatcgatcgatcga
+5 3 1 2 4 actgatcgatcgatcg
+"
+        );
+        validate_output(convert(SequenceCase::Lower).as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_emit_overlap() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    emit_overlap: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            assert!(!graph.arcs.is_empty());
+            for arc in &graph.arcs {
+                assert_eq!(arc.tags, vec![format!("OV:i:{}", k - 1)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_log_weights() {
+        for (weight, scale, expected) in [
+            (0, 1.0, 0),
+            (1, 1.0, 1),
+            (2, 1.0, 2),
+            (3, 1.0, 2),
+            (7, 1.0, 3),
+            (1023, 10.0, 100),
+            (100, 0.5, 3),
+        ] {
+            assert_eq!(
+                log_scaled_weight(weight, scale),
+                expected,
+                "{weight} {scale}"
+            );
+        }
+
+        // The merged arc has weight 2 * 3 = 6, and log2(7) * 3 = 8.42.
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            3,
+            &ConversionOptions {
+                log_weight_scale: Some(3.0),
+                ..Default::default()
+            },
+            &mut ">0 LN:i:4 KC:i:6 km:f:3.0\nACGT\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "2\n0 1 8 0 1 ACGT\n");
+    }
+
+    #[test]
+    fn test_trace_edge() {
+        let logs = capture_logs(|| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    trace_edge: Some((0, 1)),
+                    ..Default::default()
+                },
+                &mut ">0 LN:i:4 KC:i:4 km:f:2.0\nACGT\n>1 LN:i:4 KC:i:6 km:f:3.0\nAAGC\n"
+                    .as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+        });
+
+        let traces: Vec<_> = logs
+            .iter()
+            .filter(|(_, message)| message.starts_with("Trace of edge"))
+            .collect();
+        assert_eq!(
+            traces,
+            vec![&(
+                Level::Debug,
+                "Trace of edge 0 -> 1 (unitig 0): mirror 0 -> 1, total abundance 4, 2 k-mers, forwards true, merged with its mirror, stored sequence ACGT".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_canonical_node_pairs() {
+        let convert = |canonical_node_pairs| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    canonical_node_pairs,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let output = convert(false);
+        let canonical_output = convert(true);
+        assert_ne!(output, canonical_output);
+        let mut reconstructed_lines = vec![canonical_output.lines().next().unwrap().to_owned()];
+        for line in canonical_output.lines().skip(1) {
+            let mut columns: Vec<_> = line.split(' ').collect();
+            assert!(columns[0].parse::<usize>().unwrap() <= columns[1].parse::<usize>().unwrap());
+            match columns.pop().unwrap() {
+                "DI:A:+" => {}
+                "DI:A:-" => columns.swap(0, 1),
+                tag => panic!("unexpected direction tag {tag}"),
+            }
+            reconstructed_lines.push(columns.join(" "));
+        }
+        let mut lines: Vec<_> = output.lines().collect();
+        reconstructed_lines.sort_unstable();
+        lines.sort_unstable();
+        assert_eq!(reconstructed_lines, lines);
+    }
+
+    #[test]
+    fn test_emit_orientation() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    emit_orientation: true,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            let unitig_sequences: HashSet<_> = input
+                .lines()
+                .filter(|line| !line.starts_with('>'))
+                .collect();
+
+            assert!(graph.arcs.iter().any(|arc| arc.tags == ["OR:A:+"]));
+            assert!(graph.arcs.iter().any(|arc| arc.tags == ["OR:A:-"]));
+            for arc in &graph.arcs {
+                let unitig_sequence = match arc.tags[0].as_str() {
+                    "OR:A:+" => arc.sequence.clone(),
+                    "OR:A:-" => reverse_complement(&arc.sequence),
+                    tag => panic!("unexpected tag {tag}"),
+                };
+                assert!(unitig_sequences.contains(unitig_sequence.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_version_1() {
+        for (k, file) in [
+            (14, COMPLEX_FILE),
+            (14, COMPLEX_CIRCULARISED_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let convert = |options: &ConversionOptions| {
+                let mut output = Vec::new();
+                node_to_arc_centric_dbg_with_options(k, options, &mut file.as_bytes(), &mut output)
+                    .unwrap();
+                output
+            };
+            let version_1_output = convert(&ConversionOptions {
+                format_version: FormatVersion::V1,
+                annotate_nodes: true,
+                emit_gc: true,
+                emit_arc_hash: true,
+                emit_overlap: true,
+                emit_orientation: true,
+                emit_source_offset: true,
+                ..Default::default()
+            });
+            assert_eq!(version_1_output, convert(&Default::default()));
+        }
+    }
+
+    #[test]
+    fn test_emit_source_offset() {
+        let sequence = "ACGGTACCTTAGGCATCGATTGCAAGTC";
+        let reverse_sequence = crate::arc_centric_reader::reverse_complement(sequence);
+        let input = format!(">0 LN:i:{} KC:i:48 km:f:2.0\n{sequence}\n", sequence.len());
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                max_sequence_length: Some(10),
+                emit_source_offset: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        let mut forward_offsets = Vec::new();
+        let mut reverse_offsets = Vec::new();
+        for arc in &graph.arcs {
+            let offset: usize = arc.tags[0].strip_prefix("SO:i:").unwrap().parse().unwrap();
+            if sequence[offset..].starts_with(&arc.sequence) {
+                forward_offsets.push(offset);
+            } else {
+                assert!(reverse_sequence[offset..].starts_with(&arc.sequence));
+                reverse_offsets.push(offset);
+            }
+        }
+        forward_offsets.sort_unstable();
+        reverse_offsets.sort_unstable();
+        assert_eq!(forward_offsets, vec![0, 6, 12, 18]);
+        assert_eq!(forward_offsets, reverse_offsets);
+    }
+
+    #[test]
+    fn test_max_sequence_length() {
+        // A single unitig of length 28, with 24 5-mers.
+        let sequence = "ACGGTACCTTAGGCATCGATTGCAAGTC";
+        let input = format!(">0 LN:i:{} KC:i:48 km:f:2.0\n{sequence}\n", sequence.len());
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                max_sequence_length: Some(10),
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        validate_output(output.as_slice()).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+
+        // Follow the chain from the start of the forward unitig.
+        let mut chain = Vec::new();
+        let mut node = graph
+            .arcs
+            .iter()
+            .find(|arc| sequence.starts_with(&arc.sequence))
+            .unwrap()
+            .from;
+        while let Some(arc) = graph.arcs.iter().find(|arc| {
+            arc.from == node && sequence.contains(&arc.sequence) && !chain.contains(*arc)
+        }) {
+            chain.push(arc.clone());
+            node = arc.to;
+        }
+
+        assert!(chain.len() > 1);
+        assert_eq!(graph.arcs.len(), 2 * chain.len());
+        let mut reconstructed = chain[0].sequence.clone();
+        for (previous, arc) in chain.iter().zip(&chain[1..]) {
+            assert!(arc.sequence.len() <= 10);
+            assert_eq!(arc.weight, 2);
+            assert_eq!(
+                previous.sequence[previous.sequence.len() - 4..],
+                arc.sequence[..4]
+            );
+            reconstructed.push_str(&arc.sequence[4..]);
+        }
+        assert_eq!(reconstructed, sequence);
+    }
+
+    #[test]
+    fn test_deduplicate_nodes_by_sequence() {
+        // The second and the third unitig have the same sequence, but only the second is linked to the first.
+        let input = ">0 LN:i:6 KC:i:9 km:f:3.0 L:+:1:+ L:-:1:-\nAAACCC\n\
+            >1 LN:i:6 KC:i:6 km:f:2.0 L:-:0:-\nCCCTTG\n\
+            >2 LN:i:6 KC:i:3 km:f:1.0\nCCCTTG\n";
+        let convert = |deduplicate_nodes_by_sequence| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                4,
+                &ConversionOptions {
+                    deduplicate_nodes_by_sequence,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            read_arc_centric_graph(output.as_slice()).unwrap()
+        };
+        let graph = convert(false);
+        let deduplicated = convert(true);
+        assert!(find_arcs_without_mirror(&deduplicated).is_empty());
+        assert_eq!(deduplicated.arcs.len(), graph.arcs.len());
+        // The duplicate unitig shares both endpoints and its mirror endpoints with the linked one.
+        assert_eq!(deduplicated.node_count, graph.node_count - 4);
+        let endpoints = |weight| {
+            let mut endpoints: Vec<_> = deduplicated
+                .arcs
+                .iter()
+                .filter(|arc| arc.weight == weight)
+                .map(|arc| (arc.from, arc.to, arc.sequence.clone()))
+                .collect();
+            endpoints.sort();
+            endpoints
+        };
+        assert_eq!(endpoints(2), endpoints(1));
+    }
+
+    #[test]
+    fn test_collapse_linear_paths() {
+        // The unitig ACGGTACCTTAGGCATCGATTGCAAGTC fragmented into three linked records with k = 5.
+        let sequence = "ACGGTACCTTAGGCATCGATTGCAAGTC";
+        let input = ">0 LN:i:12 KC:i:16 km:f:2.0 L:+:1:+\nACGGTACCTTAG\n\
+            >1 LN:i:12 KC:i:32 km:f:4.0 L:-:0:- L:+:2:+\nTTAGGCATCGAT\n\
+            >2 LN:i:12 KC:i:16 km:f:2.0 L:-:1:-\nCGATTGCAAGTC\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            5,
+            &ConversionOptions {
+                collapse_linear_paths: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        validate_output(output.as_slice()).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        assert_eq!(graph.node_count, 4);
+        let mut sequences: Vec<_> = graph.arcs.iter().map(|arc| arc.sequence.clone()).collect();
+        sequences.sort_unstable();
+        let mut expected = vec![sequence.to_owned(), reverse_complement(sequence)];
+        expected.sort_unstable();
+        assert_eq!(sequences, expected);
+        // Each record has 8 k-mers, with weights 2, 4 and 2.
+        assert!(graph.arcs.iter().all(|arc| arc.weight == 3));
+    }
+
+    #[test]
+    fn test_expand_kmers() {
+        let k = 4;
+        let input = ">0 LN:i:8 KC:i:10 km:f:2.0\nACGGTACC\n>1 LN:i:6 KC:i:3 km:f:1.0\nATTGCA\n";
+        let kmer_count: usize = ["ACGGTACC", "ATTGCA"]
+            .iter()
+            .map(|sequence| sequence.len() - k + 1)
+            .sum();
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            k,
+            &ConversionOptions {
+                expand_kmers: true,
+                expand_kmers_max_nodes: 100,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        validate_output(output.as_slice()).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        // Each k-mer is written in both directions.
+        assert_eq!(graph.arcs.len(), 2 * kmer_count);
+        assert!(graph.arcs.iter().all(|arc| arc.sequence.len() == k));
+
+        let result = node_to_arc_centric_dbg_with_options(
+            k,
+            &ConversionOptions {
+                expand_kmers: true,
+                expand_kmers_max_nodes: 10,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bifrost_output() {
+        let input = ">0 LN:i:5 KC:i:2 km:f:1.0 L:+:1:+\nACGTC\n\
+            >1 LN:i:5 KC:i:2 km:f:1.0 L:-:0:- L:+:2:+\nGTCAG\n\
+            >2 LN:i:5 KC:i:2 km:f:1.0 L:-:1:-\nCAGGA\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            4,
+            &ConversionOptions {
+                output_format: OutputFormat::Bifrost,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "H\tVN:Z:1.0
+S\t1\tACGTC
+S\t2\tGTCAG
+S\t3\tCAGGA
+L\t1\t+\t2\t+\t3M
+L\t2\t+\t3\t+\t3M
+"
+        );
+    }
+
+    #[test]
+    fn test_spaligner_output() {
+        let input = ">0 LN:i:5 KC:i:4 km:f:2.0 L:+:1:+\nACGTC\n\
+            >1 LN:i:5 KC:i:2 km:f:1.0 L:-:0:- L:+:2:+\nGTCAG\n\
+            >2 LN:i:5 KC:i:2 km:f:1.0 L:-:1:-\nCAGGA\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            4,
+            &ConversionOptions {
+                output_format: OutputFormat::Spaligner,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "H\tVN:Z:1.0
+S\t0\tACGTC\tKC:i:4
+S\t1\tGTCAG\tKC:i:2
+S\t2\tCAGGA\tKC:i:2
+L\t0\t+\t1\t+\t3M
+L\t1\t+\t2\t+\t3M
+P\tpath0\t0+,1+,2+\t3M,3M
+"
+        );
+    }
+
+    #[test]
+    fn test_require_nonempty() {
+        // The only unitig is too short, so no arcs are written.
+        let convert = |require_nonempty| {
+            let mut output = Vec::new();
+            let result = node_to_arc_centric_dbg_with_options(
+                5,
+                &ConversionOptions {
+                    skip_short: true,
+                    require_nonempty,
+                    ..Default::default()
+                },
+                &mut ">0 LN:i:4 KC:i:2 km:f:1.0\nACGT\n".as_bytes(),
+                &mut output,
+            );
+            (result.is_ok(), String::from_utf8(output).unwrap())
+        };
+
+        assert_eq!(convert(false), (true, "2\n".to_owned()));
+        assert_eq!(convert(true), (false, "2\n".to_owned()));
+
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            14,
+            &ConversionOptions {
+                require_nonempty: true,
+                ..Default::default()
+            },
+            &mut COMPLEX_FILE.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_self_complemental_report() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("self_complemental.txt");
+        let report = |k, input: &str| {
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    self_complemental_report: Some(path.clone()),
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+            std::fs::read_to_string(&path).unwrap()
+        };
+
+        // The two arcs from node 0 to node 1 are mirrors of each other, but their sequences are not palindromic.
+        assert_eq!(report(15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE), "");
+        let input = ">0 LN:i:4 KC:i:4 km:f:2.0 L:+:0:+ L:-:0:- L:-:0:+ L:+:0:-\nATAT\n";
+        assert_eq!(report(3, input), "0 0 ATAT\n");
+    }
+
+    #[test]
+    fn test_patch_against_base() {
+        let convert = |input: &str, base| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    base,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let base = read_arc_centric_graph(convert(COMPLEX_FILE, None).as_bytes()).unwrap();
+        let new_output = convert(COMPLEX_CIRCULARISED_FILE, None);
+
+        let patch =
+            GraphPatch::parse(&convert(COMPLEX_CIRCULARISED_FILE, Some(base.clone()))).unwrap();
+        assert!(!patch.removed.is_empty());
+        assert!(!patch.added.is_empty());
+        let mut patched_output = Vec::new();
+        write_arc_centric_graph(&apply_patch(&base, &patch).unwrap(), &mut patched_output).unwrap();
+        assert_eq!(
+            sort_arc_lines(
+                &String::from_utf8(patched_output).unwrap(),
+                IdEncoding::Decimal
+            ),
+            sort_arc_lines(&new_output, IdEncoding::Decimal)
+        );
+
+        // A patch against the same graph is empty.
+        let patch = convert(COMPLEX_FILE, Some(base.clone()));
+        assert_eq!(patch, format!("{}\n", base.node_count));
+
+        let mut changed_base = base.clone();
+        changed_base.arcs[0].weight += 1;
+        let patch = GraphPatch::parse(&convert(COMPLEX_FILE, Some(changed_base.clone()))).unwrap();
+        assert_eq!(
+            patch.changed,
+            vec![(changed_base.arcs[0].clone(), base.arcs[0].weight)]
+        );
+        assert!(patch.removed.is_empty() && patch.added.is_empty());
+        assert_eq!(apply_patch(&changed_base, &patch).unwrap(), base);
+    }
+
+    #[test]
+    fn test_max_out_degree() {
+        // Unitigs 0, 1 and 2 are arcs with weights 3, 2 and 1 from the end of unitig 3 to the start of unitig 4.
+        let input = ">0 LN:i:5 KC:i:9 km:f:3.0 L:-:3:- L:+:4:+\nAACCG\n\
+            >1 LN:i:5 KC:i:6 km:f:2.0 L:-:3:- L:+:4:+\nAATCG\n\
+            >2 LN:i:5 KC:i:3 km:f:1.0 L:-:3:- L:+:4:+\nAAGCG\n\
+            >3 LN:i:4 KC:i:2 km:f:1.0 L:+:0:+ L:+:1:+ L:+:2:+\nGGAA\n\
+            >4 LN:i:4 KC:i:2 km:f:1.0 L:-:0:- L:-:1:- L:-:2:-\nCGTT\n";
+        let convert = |max_out_degree| {
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                3,
+                &ConversionOptions {
+                    max_out_degree,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            read_arc_centric_graph(output.as_slice()).unwrap()
+        };
+        let full_graph = convert(None);
+        let hub = full_graph
+            .arcs
+            .iter()
+            .find(|arc| arc.sequence == "AACCG")
+            .unwrap()
+            .from;
+        let out_degree =
+            |graph: &ArcCentricGraph| graph.arcs.iter().filter(|arc| arc.from == hub).count();
+        assert_eq!(out_degree(&full_graph), 3);
+
+        let graph = convert(Some(2));
+        assert_eq!(out_degree(&graph), 2);
+        // The arc with the lowest weight is dropped together with its mirror arc.
+        let mut sequences: Vec<_> = graph.arcs.iter().map(|arc| arc.sequence.as_str()).collect();
+        sequences.sort_unstable();
+        assert_eq!(
+            sequences,
+            ["AACCG", "AACG", "AATCG", "CGATT", "CGGTT", "CGTT", "GGAA", "TTCC"]
+        );
+        assert!(find_arcs_without_mirror(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_max_out_degree_merged_arcs() {
+        // The self-complemental unitig 0 is a merged arc with weight 4 that outranks the arc of unitig 1 with weight 3.
+        let input = ">0 LN:i:4 KC:i:4 km:f:2.0 L:-:2:- L:+:2:-\nACGT\n\
+            >1 LN:i:4 KC:i:6 km:f:3.0 L:-:2:-\nACAA\n\
+            >2 LN:i:4 KC:i:2 km:f:1.0 L:+:0:+ L:+:1:+ L:+:0:-\nGGAC\n";
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg_with_options(
+            3,
+            &ConversionOptions {
+                max_out_degree: Some(1),
+                drop_isolated_nodes: true,
+                ..Default::default()
+            },
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        let mut sequences: Vec<_> = graph.arcs.iter().map(|arc| arc.sequence.as_str()).collect();
+        sequences.sort_unstable();
+        assert_eq!(sequences, ["ACGT", "GGAC", "GTCC"]);
+        // The nodes of the dropped arcs are isolated.
+        assert_eq!(graph.node_count, 4);
+        assert!(find_arcs_without_mirror(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_ascii_preview() {
+        let mut output = Vec::new();
+        node_to_arc_centric_dbg(14, &mut COMPLEX_FILE.as_bytes(), &mut output).unwrap();
+        let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+        assert_eq!(graph.node_count, 6);
+
+        let mut preview = Vec::new();
+        write_ascii_preview(&graph, &mut preview).unwrap();
+        let preview = String::from_utf8(preview).unwrap();
+        assert!(preview.starts_with(&format!(
+            "Graph with 6 nodes and {} arcs:\n",
+            graph.arcs.len()
+        )));
+        for node in 0..graph.node_count {
+            let out_degree = graph.arcs.iter().filter(|arc| arc.from == node).count();
+            let plural = if out_degree == 1 { "" } else { "s" };
+            assert!(preview.contains(&format!("  ({node}) {out_degree} arc{plural}")));
+        }
+        for arc in &graph.arcs {
+            assert!(preview.contains(&format!("-{}-> ({})", arc.weight, arc.to)));
+        }
+    }
+
+    #[test]
+    fn test_check_eulerian() {
+        let convert = |float_weights| {
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    check_eulerian: true,
+                    float_weights,
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut Vec::new(),
+            )
+        };
+        convert(None).unwrap();
+        assert!(convert(Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_ascii_preview_of_formatted_output() {
+        let logs = capture_logs(|| {
+            node_to_arc_centric_dbg_with_options(
+                14,
+                &ConversionOptions {
+                    ascii_preview: true,
+                    one_based: true,
+                    float_weights: Some(2),
+                    ..Default::default()
+                },
+                &mut COMPLEX_FILE.as_bytes(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        });
+        assert!(!logs
+            .iter()
+            .any(|(level, message)| *level == Level::Warn && message.contains("preview")));
+    }
+
+    #[test]
+    fn test_rna_mirror_arcs() {
+        for (k, input) in [
+            (14, COMPLEX_FILE),
+            (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+        ] {
+            let input = input
+                .lines()
+                .map(|line| {
+                    if line.starts_with('>') {
+                        line.to_owned()
+                    } else {
+                        line.replace('T', "U")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut output = Vec::new();
+            node_to_arc_centric_dbg_with_options(
+                k,
+                &ConversionOptions {
+                    alphabet: Alphabet::Rna,
+                    ..Default::default()
+                },
+                &mut input.as_bytes(),
+                &mut output,
+            )
+            .unwrap();
+            let graph = read_arc_centric_graph(output.as_slice()).unwrap();
+            assert!(graph.arcs.iter().all(|arc| !arc.sequence.contains('T')));
+            assert!(find_arcs_without_mirror(&graph).is_empty());
+            let mirrors = find_mirror_arcs(&graph).unwrap();
+            assert!(mirrors
+                .iter()
+                .enumerate()
+                .all(|(index, &mirror)| mirrors[mirror] == index));
         }
     }
 }
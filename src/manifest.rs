@@ -0,0 +1,91 @@
+use anyhow::Context;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A file produced by a run, as listed in the manifest.
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    /// What the file contains, e.g. `output` or `shard`.
+    pub role: &'static str,
+}
+
+/// Write a JSON manifest listing each of the given files with its path, role, size in bytes and CRC32 checksum.
+/// The files are read to compute their checksums, so they must be complete.
+///
+/// The manifest is an object with the single key `files`, whose value is an array with one object per file,
+/// each written on its own line.
+pub fn write_manifest(entries: &[ManifestEntry], output: &mut impl Write) -> anyhow::Result<()> {
+    writeln!(output, "{{")?;
+    writeln!(output, "  \"files\": [")?;
+    for (index, entry) in entries.iter().enumerate() {
+        let content = std::fs::read(&entry.path)
+            .with_context(|| format!("Could not read {:?} for the manifest", entry.path))?;
+        let separator = if index + 1 < entries.len() { "," } else { "" };
+        writeln!(
+            output,
+            "    {{\"path\": {}, \"role\": {}, \"size\": {}, \"crc32\": \"{:08x}\"}}{separator}",
+            json_string(&entry.path.to_string_lossy()),
+            json_string(entry.role),
+            content.len(),
+            crc32fast::hash(&content),
+        )?;
+    }
+    writeln!(output, "  ]")?;
+    writeln!(output, "}}")?;
+    Ok(())
+}
+
+/// Quote and escape a string for JSON.
+pub fn json_string(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, write_manifest, ManifestEntry};
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("out.txt"), "\"out.txt\"");
+        assert_eq!(json_string("a\"b\\c\u{1}"), "\"a\\\"b\\\\c\\u0001\"");
+    }
+
+    #[test]
+    fn test_write_manifest() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("output.txt");
+        std::fs::write(&path, "123456789").unwrap();
+        let mut output = Vec::new();
+        write_manifest(
+            &[ManifestEntry {
+                path: path.clone(),
+                role: "output",
+            }],
+            &mut output,
+        )
+        .unwrap();
+        // The CRC32 of the standard check input.
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "{{\n  \"files\": [\n    {{\"path\": {}, \"role\": \"output\", \"size\": 9, \"crc32\": \"cbf43926\"}}\n  ]\n}}\n",
+                json_string(&path.to_string_lossy())
+            )
+        );
+    }
+}
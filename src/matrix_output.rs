@@ -0,0 +1,18 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use std::io::Write;
+
+/// Write the graph as a square matrix of arc weights, with one row per line and columns separated by spaces.
+/// The entry in row `i` and column `j` is the sum of the weights of all arcs from node `i` to node `j`,
+/// so it is zero if there is no such arc.
+pub fn write_matrix(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let mut matrix = vec![vec![0; graph.node_count]; graph.node_count];
+    for arc in &graph.arcs {
+        matrix[arc.from][arc.to] += arc.weight;
+    }
+
+    for row in matrix {
+        let row: Vec<_> = row.iter().map(ToString::to_string).collect();
+        writeln!(output, "{}", row.join(" "))?;
+    }
+    Ok(())
+}
@@ -1,39 +1,136 @@
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(all(target_os = "linux", feature = "memory-metering")))]
 pub use dummy_implementation::MemoryMeter;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "memory-metering"))]
 pub use implementation::MemoryMeter;
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "memory-metering"))]
 mod implementation {
-    use log::info;
+    use crate::dedup_sequence_store::DeduplicationStatistics;
+    use anyhow::bail;
+    use log::{info, warn};
     use self_meter::Meter;
+    use std::fmt::Display;
     use std::time::Duration;
 
+    /// Reports the memory usage of the process.
+    /// If the meter cannot be initialised, e.g. because `/proc` is not available, then nothing is reported.
     pub struct MemoryMeter {
-        meter: Meter,
+        meter: Option<Meter>,
+        /// The maximum resident set size in MiB.
+        limit: Option<usize>,
     }
 
     impl MemoryMeter {
         pub fn new() -> Self {
-            let mut meter = Meter::new(Duration::from_secs(1)).unwrap();
+            Self::from_meter(Self::initialise_meter())
+        }
+
+        fn initialise_meter() -> Result<Meter, self_meter::Error> {
+            let mut meter = Meter::new(Duration::from_secs(1))?;
             meter.track_current_thread("main");
-            meter.scan().unwrap();
-            Self { meter }
+            meter.scan()?;
+            Ok(meter)
+        }
+
+        fn from_meter(meter: Result<Meter, impl Display>) -> Self {
+            match meter {
+                Ok(meter) => Self {
+                    meter: Some(meter),
+                    limit: None,
+                },
+                Err(error) => {
+                    warn!("Could not initialise memory meter, not reporting memory usage: {error}");
+                    Self {
+                        meter: None,
+                        limit: None,
+                    }
+                }
+            }
+        }
+
+        /// Make [`report`](Self::report) fail if the resident set size exceeds the given number of MiB.
+        pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+            if limit.is_some() && self.meter.is_none() {
+                warn!("The memory limit is not enforced, since the memory meter is not available");
+            }
+            self.limit = limit;
+        }
+
+        /// The current resident set size in MiB, if it can be measured.
+        pub fn memory_usage(&mut self) -> Option<f64> {
+            let meter = self.meter.as_mut()?;
+            if let Err(error) = meter.scan() {
+                warn!("Could not measure memory usage: {error}");
+                return None;
+            }
+            let report = meter.report()?;
+            Some(report.memory_rss as f64 / (1024.0 * 1024.0))
+        }
+
+        /// Log the current memory usage.
+        /// Fails if it exceeds the memory limit.
+        pub fn report(&mut self) -> anyhow::Result<()> {
+            let Some(memory_usage) = self.memory_usage() else {
+                return Ok(());
+            };
+            info!("Current memory usage: {memory_usage:.0}MiB");
+            if let Some(limit) = self.limit {
+                if memory_usage > limit as f64 {
+                    bail!("Memory usage of {memory_usage:.0}MiB exceeds the limit of {limit}MiB");
+                }
+            }
+            Ok(())
+        }
+
+        pub fn report_deduplication(
+            &mut self,
+            statistics: &DeduplicationStatistics,
+        ) -> anyhow::Result<()> {
+            info!("Sequence deduplication {statistics}");
+            self.report()
         }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::MemoryMeter;
+        use crate::test_logger::capture_logs;
+        use log::Level;
 
-        pub fn report(&mut self) {
-            self.meter.scan().unwrap();
-            info!(
-                "Current memory usage: {:.0}MiB",
-                self.meter.report().unwrap().memory_rss as f64 / (1024.0 * 1024.0)
+        #[test]
+        fn test_initialisation_failure() {
+            let logs = capture_logs(|| {
+                let mut meter = MemoryMeter::from_meter(Err("no /proc"));
+                assert!(meter.meter.is_none());
+                meter.report().unwrap();
+            });
+            assert_eq!(
+                logs,
+                vec![(
+                    Level::Warn,
+                    "Could not initialise memory meter, not reporting memory usage: no /proc"
+                        .to_owned()
+                )]
             );
         }
+
+        #[test]
+        fn test_memory_limit() {
+            let mut meter = MemoryMeter::new();
+            if meter.meter.is_none() {
+                return;
+            }
+            meter.report().unwrap();
+            meter.set_memory_limit(Some(0));
+            assert!(meter.report().is_err());
+        }
     }
 }
 
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(all(target_os = "linux", feature = "memory-metering")))]
 mod dummy_implementation {
-    use log::info;
+    use crate::dedup_sequence_store::DeduplicationStatistics;
+    use log::{info, warn};
     pub struct MemoryMeter;
 
     impl MemoryMeter {
@@ -41,8 +138,29 @@ mod dummy_implementation {
             Self
         }
 
-        pub fn report(&mut self) {
-            info!("Memory reporting only supported on Linux");
+        pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+            if limit.is_some() {
+                warn!("The memory limit is not enforced, since memory reporting is only supported on Linux with the memory-metering feature");
+            }
+        }
+
+        /// Only used by tests that skip checks of the memory usage when it is unavailable.
+        #[cfg(test)]
+        pub fn memory_usage(&mut self) -> Option<f64> {
+            None
+        }
+
+        pub fn report(&mut self) -> anyhow::Result<()> {
+            info!("Memory reporting only supported on Linux with the memory-metering feature");
+            Ok(())
+        }
+
+        pub fn report_deduplication(
+            &mut self,
+            statistics: &DeduplicationStatistics,
+        ) -> anyhow::Result<()> {
+            info!("Sequence deduplication {statistics}");
+            self.report()
         }
     }
 }
@@ -0,0 +1,77 @@
+use anyhow::{bail, Context};
+use log::info;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// The path of the metadata sidecar of an input file, e.g. `graph.fa.meta` for `graph.fa`.
+pub fn sidecar_path(input: &Path) -> PathBuf {
+    let mut path = input.as_os_str().to_owned();
+    path.push(".meta");
+    PathBuf::from(path)
+}
+
+/// Parse k from a metadata file.
+/// The file consists of lines of the form `<key> <value>`, where the key and value may also be separated by `=` or `:`.
+/// The k-mer size is given by the key `k`, `kmer-size` or `kmer_size`, ignoring case. Other keys are ignored.
+/// Returns `None` if there is no such key.
+pub fn parse_k(input: impl BufRead) -> anyhow::Result<Option<usize>> {
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        let Some(separator) = line.find(|character: char| {
+            character == '=' || character == ':' || character.is_whitespace()
+        }) else {
+            continue;
+        };
+        let key = line[..separator].trim();
+        let value = line[separator + 1..].trim();
+
+        if ["k", "kmer-size", "kmer_size"]
+            .iter()
+            .any(|candidate| key.eq_ignore_ascii_case(candidate))
+        {
+            return value
+                .parse()
+                .map(Some)
+                .with_context(|| format!("Malformed k in metadata: {line:?}"));
+        }
+    }
+    Ok(None)
+}
+
+/// Return the given k, or if there is none, read it from the metadata sidecar of the input.
+pub fn resolve_k(k: Option<usize>, input: &Path) -> anyhow::Result<usize> {
+    if let Some(k) = k {
+        return Ok(k);
+    }
+
+    let sidecar = sidecar_path(input);
+    if input == Path::new("-") || !sidecar.exists() {
+        bail!("No k given, and there is no metadata file {sidecar:?} to read it from");
+    }
+    let file = File::open(&sidecar)
+        .with_context(|| format!("Could not open metadata file {sidecar:?}"))?;
+    let Some(k) = parse_k(BufReader::new(file))? else {
+        bail!("No k given, and the metadata file {sidecar:?} does not contain it");
+    };
+    info!("Read k = {k} from {sidecar:?}");
+    Ok(k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_k;
+
+    #[test]
+    fn test_parse_k() {
+        assert_eq!(parse_k("k=31\n".as_bytes()).unwrap(), Some(31));
+        assert_eq!(
+            parse_k("abundance_min 2\nkmer-size: 25\n".as_bytes()).unwrap(),
+            Some(25)
+        );
+        assert_eq!(parse_k("K 21".as_bytes()).unwrap(), Some(21));
+        assert_eq!(parse_k("abundance_min 2\n\n".as_bytes()).unwrap(), None);
+        assert!(parse_k("k = a\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Write the graph in the METIS graph format, as undirected graph with edge weights.
+///
+/// The header is `<nodes> <edges> 001`, followed by one line per node listing its neighbors (1-indexed)
+/// each followed by the weight of the edge to it.
+/// Since METIS does not allow self-loops or parallel edges, self-loops are left out,
+/// and all arcs between two nodes in either direction are combined into one edge with the sum of their weights.
+/// METIS requires positive weights, so edges with a sum of zero get weight one.
+pub fn write_metis(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let mut adjacencies = vec![BTreeMap::new(); graph.node_count];
+    for arc in graph.arcs.iter().filter(|arc| arc.from != arc.to) {
+        *adjacencies[arc.from].entry(arc.to).or_insert(0) += arc.weight;
+        *adjacencies[arc.to].entry(arc.from).or_insert(0) += arc.weight;
+    }
+
+    let edge_count: usize = adjacencies.iter().map(BTreeMap::len).sum::<usize>() / 2;
+    writeln!(output, "{} {edge_count} 001", graph.node_count)?;
+    for adjacency in adjacencies {
+        let line: Vec<_> = adjacency
+            .into_iter()
+            .map(|(neighbor, weight)| format!("{} {}", neighbor + 1, weight.max(1)))
+            .collect();
+        writeln!(output, "{}", line.join(" "))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_metis;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_self_loops_and_parallel_arcs() {
+        let graph = read_arc_centric_graph(
+            "3\n0 0 5 0 0 ACGT\n0 1 2 1 2 AAAC\n1 0 3 2 1 GTTT\n0 1 0 1 2 AAAG\n".as_bytes(),
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_metis(&graph, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "3 1 001\n2 5\n1 5\n\n");
+    }
+}
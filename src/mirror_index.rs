@@ -0,0 +1,75 @@
+use crate::arc_centric_reader::{is_reverse_complement, ArcCentricGraph, ArcRecord};
+use anyhow::bail;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Find the mirror arc of each arc, i.e. the arc that is reverse complemental to it.
+/// An arc may be its own mirror, e.g. if it is a merged pair of reverse complemental arcs.
+/// DNA and RNA sequences are both supported.
+pub fn find_mirror_arcs(graph: &ArcCentricGraph) -> anyhow::Result<Vec<usize>> {
+    let mirrors = match_mirror_arcs(graph);
+    if let Some(index) = mirrors.iter().position(Option::is_none) {
+        let arc = &graph.arcs[index];
+        bail!("arc {index} ({} -> {}) has no mirror arc", arc.from, arc.to);
+    }
+    Ok(mirrors.into_iter().map(Option::unwrap).collect())
+}
+
+/// Pair each arc with its mirror arc like [`find_mirror_arcs`], where arcs without an unpaired mirror arc are `None`.
+pub fn match_mirror_arcs(graph: &ArcCentricGraph) -> Vec<Option<usize>> {
+    let arcs_by_endpoints = arcs_by_endpoints(graph);
+    let mut mirrors: Vec<Option<usize>> = vec![None; graph.arcs.len()];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        if mirrors[index].is_some() {
+            continue;
+        }
+
+        let mirror_index = if is_mirror_arc(arc, arc) {
+            Some(index)
+        } else {
+            arcs_by_endpoints
+                .get(&(arc.mirror_from, arc.mirror_to))
+                .into_iter()
+                .flatten()
+                .copied()
+                .find(|&mirror_index| {
+                    mirror_index != index
+                        && mirrors[mirror_index].is_none()
+                        && is_mirror_arc(arc, &graph.arcs[mirror_index])
+                })
+        };
+        if let Some(mirror_index) = mirror_index {
+            mirrors[index] = Some(mirror_index);
+            mirrors[mirror_index] = Some(index);
+        }
+    }
+    mirrors
+}
+
+/// The indices of the arcs by their endpoints.
+pub fn arcs_by_endpoints(graph: &ArcCentricGraph) -> HashMap<(usize, usize), Vec<usize>> {
+    let mut arcs_by_endpoints: HashMap<_, Vec<_>> = HashMap::new();
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        arcs_by_endpoints
+            .entry((arc.from, arc.to))
+            .or_default()
+            .push(index);
+    }
+    arcs_by_endpoints
+}
+
+/// True if the mirror columns of the arcs point to each other, and their sequences are reverse complements.
+pub fn is_mirror_arc(arc: &ArcRecord, mirror: &ArcRecord) -> bool {
+    (mirror.from, mirror.to) == (arc.mirror_from, arc.mirror_to)
+        && (mirror.mirror_from, mirror.mirror_to) == (arc.from, arc.to)
+        && is_reverse_complement(&arc.sequence, &mirror.sequence)
+}
+
+/// Write the line number of the mirror arc of each arc, as lines `<arc line> <mirror line>`.
+/// The line numbers refer to the arc-centric output, where the header is the first line.
+pub fn write_mirror_index(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    for (index, mirror_index) in find_mirror_arcs(graph)?.into_iter().enumerate() {
+        writeln!(output, "{} {}", index + 2, mirror_index + 2)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,172 @@
+#[cfg(not(target_os = "linux"))]
+pub use dummy_implementation::MmapWriter;
+#[cfg(target_os = "linux")]
+pub use implementation::MmapWriter;
+
+#[cfg(target_os = "linux")]
+mod implementation {
+    use std::fs::File;
+    use std::io::{self, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::ptr;
+
+    /// The smallest mapping, such that the mapping is never empty.
+    const MIN_CAPACITY: usize = 1 << 16;
+
+    /// Writes into a memory mapping of a file instead of through write calls.
+    ///
+    /// The file is extended to the capacity of the mapping, and the capacity doubles whenever it is exhausted,
+    /// which requires mapping the file again. Since the file is larger than the written data,
+    /// it has to be truncated with [`MmapWriter::finish`] after writing.
+    pub struct MmapWriter {
+        file: File,
+        mapping: *mut u8,
+        capacity: usize,
+        position: usize,
+    }
+
+    impl MmapWriter {
+        /// Map the file with the expected size of the output as initial capacity.
+        /// The file has to be opened for reading and writing.
+        pub fn new(file: File, expected_size: usize) -> io::Result<Self> {
+            let mut writer = Self {
+                file,
+                mapping: ptr::null_mut(),
+                capacity: 0,
+                position: 0,
+            };
+            writer.remap(expected_size.max(MIN_CAPACITY))?;
+            Ok(writer)
+        }
+
+        /// Unmap the file, and truncate it to the written data.
+        pub fn finish(mut self) -> io::Result<()> {
+            self.unmap()?;
+            self.file.set_len(self.position as u64)
+        }
+
+        fn remap(&mut self, capacity: usize) -> io::Result<()> {
+            self.unmap()?;
+            self.file.set_len(capacity as u64)?;
+            // SAFETY: the file descriptor is valid for the lifetime of `file`, and the file is at least as long as the mapping.
+            let mapping = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    capacity,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    self.file.as_raw_fd(),
+                    0,
+                )
+            };
+            if mapping == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            self.mapping = mapping.cast();
+            self.capacity = capacity;
+            Ok(())
+        }
+
+        fn unmap(&mut self) -> io::Result<()> {
+            if self.mapping.is_null() {
+                return Ok(());
+            }
+            // SAFETY: the mapping was created by `remap` with this capacity, and is not used afterwards.
+            let result = unsafe { libc::munmap(self.mapping.cast(), self.capacity) };
+            self.mapping = ptr::null_mut();
+            self.capacity = 0;
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+    }
+
+    impl Write for MmapWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let end = self.position + buf.len();
+            if end > self.capacity {
+                self.remap(end.max(2 * self.capacity))?;
+            }
+            // SAFETY: the mapping is valid for `capacity` bytes, and `end` is at most the capacity.
+            unsafe {
+                ptr::copy_nonoverlapping(buf.as_ptr(), self.mapping.add(self.position), buf.len());
+            }
+            self.position = end;
+            Ok(buf.len())
+        }
+
+        /// The mapping is shared, so the written data is already visible to readers of the file.
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for MmapWriter {
+        fn drop(&mut self) {
+            // Errors cannot be reported here, and the mapping is released with the process anyway.
+            let _ = self.unmap();
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod dummy_implementation {
+    use std::fs::File;
+    use std::io::{self, Write};
+
+    pub enum MmapWriter {}
+
+    impl MmapWriter {
+        pub fn new(_file: File, _expected_size: usize) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "memory-mapped output is only supported on Linux",
+            ))
+        }
+
+        pub fn finish(self) -> io::Result<()> {
+            match self {}
+        }
+    }
+
+    impl Write for MmapWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            match *self {}
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::MmapWriter;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn test_growing_mapping() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("output.txt");
+        let data: Vec<u8> = (0..200_000)
+            .map(|index| b'A' + (index % 26) as u8)
+            .collect();
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        let mut writer = MmapWriter::new(file, 0).unwrap();
+        for chunk in data.chunks(1000) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+    }
+}
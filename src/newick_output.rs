@@ -0,0 +1,57 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::components::DisjointSets;
+use anyhow::bail;
+use std::io::Write;
+
+/// Write the graph in the Newick format, if its underlying undirected graph is a forest.
+///
+/// Each tree is written on its own line, rooted at its node with the lowest index,
+/// and the nodes are labelled by their indices.
+/// Fails if the graph has a cycle, including self-loops and parallel arcs.
+pub fn write_newick(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let mut components = DisjointSets::new(graph.node_count);
+    let mut neighbors = vec![Vec::new(); graph.node_count];
+    for arc in &graph.arcs {
+        if !components.union(arc.from, arc.to) {
+            bail!(
+                "The graph is not a forest, it has a cycle through the arc {} -> {}",
+                arc.from,
+                arc.to
+            );
+        }
+        neighbors[arc.from].push(arc.to);
+        neighbors[arc.to].push(arc.from);
+    }
+
+    let mut visited = vec![false; graph.node_count];
+    for root in 0..graph.node_count {
+        if visited[root] {
+            continue;
+        }
+
+        // Write the children of each node before the node itself, without recursion, since trees may be deep.
+        let mut stack = vec![(root, None, 0)];
+        visited[root] = true;
+        while let Some((node, parent, child_index)) = stack.pop() {
+            let children: Vec<_> = neighbors[node]
+                .iter()
+                .copied()
+                .filter(|&neighbor| Some(neighbor) != parent)
+                .collect();
+            if child_index < children.len() {
+                write!(output, "{}", if child_index == 0 { "(" } else { "," })?;
+                let child = children[child_index];
+                visited[child] = true;
+                stack.push((node, parent, child_index + 1));
+                stack.push((child, Some(node), 0));
+            } else {
+                if !children.is_empty() {
+                    write!(output, ")")?;
+                }
+                write!(output, "{node}")?;
+            }
+        }
+        writeln!(output, ";")?;
+    }
+    Ok(())
+}
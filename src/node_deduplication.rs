@@ -0,0 +1,48 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use crate::components::DisjointSets;
+use std::collections::HashMap;
+
+/// Merge all nodes that are the same (k-1)-mer, e.g. if the input contains duplicate unitig records
+/// that were not linked to the same neighbors.
+///
+/// The (k-1)-mer of a node is the prefix of its outgoing arcs and the suffix of its incoming arcs.
+/// The arcs are kept, with their endpoints and mirror columns remapped to the merged nodes,
+/// and the nodes are renumbered consecutively in the order of their lowest original node.
+pub fn deduplicate_nodes_by_sequence(graph: &ArcCentricGraph, k: usize) -> ArcCentricGraph {
+    let mut sets = DisjointSets::new(graph.node_count);
+    let mut nodes_by_kmer = HashMap::new();
+    for arc in &graph.arcs {
+        let length = arc.sequence.len();
+        for (node, kmer) in [
+            (arc.from, &arc.sequence[..k - 1]),
+            (arc.to, &arc.sequence[length - (k - 1)..]),
+        ] {
+            let representative = *nodes_by_kmer.entry(kmer).or_insert(node);
+            sets.union(node, representative);
+        }
+    }
+
+    let mut root_indices = vec![None; graph.node_count];
+    let mut node_count = 0;
+    let node_indices: Vec<_> = (0..graph.node_count)
+        .map(|node| {
+            *root_indices[sets.find(node)].get_or_insert_with(|| {
+                node_count += 1;
+                node_count - 1
+            })
+        })
+        .collect();
+
+    let arcs = graph
+        .arcs
+        .iter()
+        .map(|arc| ArcRecord {
+            from: node_indices[arc.from],
+            to: node_indices[arc.to],
+            mirror_from: node_indices[arc.mirror_from],
+            mirror_to: node_indices[arc.mirror_to],
+            ..arc.clone()
+        })
+        .collect();
+    ArcCentricGraph { node_count, arcs }
+}
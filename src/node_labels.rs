@@ -0,0 +1,55 @@
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// The tag of the label of the first node of an arc.
+pub const FROM_LABEL_TAG: &str = "FL:Z:";
+/// The tag of the label of the second node of an arc.
+pub const TO_LABEL_TAG: &str = "TL:Z:";
+
+/// Read external node labels from lines of the form `<node><TAB><label>`.
+/// Empty lines and lines starting with `#` are ignored.
+/// Since labels are written as tags, they must not contain whitespace.
+pub fn read_node_labels(input: impl BufRead) -> anyhow::Result<HashMap<usize, String>> {
+    let mut labels = HashMap::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((node, label)) = line.split_once('\t') else {
+            bail!("missing tab between node and label in line {line_number}");
+        };
+        let node: usize = node
+            .trim()
+            .parse()
+            .with_context(|| format!("malformed node {node:?} in line {line_number}"))?;
+        if label.is_empty() || label.contains(char::is_whitespace) {
+            bail!("label {label:?} in line {line_number} is empty or contains whitespace");
+        }
+        if labels.insert(node, label.to_owned()).is_some() {
+            bail!("duplicate label for node {node} in line {line_number}");
+        }
+    }
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::read_node_labels;
+
+    #[test]
+    fn test_read_node_labels() {
+        let labels =
+            read_node_labels("# taxonomy\n0\tEscherichia\n\n3\tBacillus\n".as_bytes()).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[&0], "Escherichia");
+        assert_eq!(labels[&3], "Bacillus");
+
+        assert!(read_node_labels("0 Escherichia\n".as_bytes()).is_err());
+        assert!(read_node_labels("0\tEscherichia coli\n".as_bytes()).is_err());
+        assert!(read_node_labels("0\ta\n0\tb\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::Graph;
+use anyhow::bail;
+use genome_graph::bigraph::traitgraph::index::GraphIndex;
+use genome_graph::bigraph::traitgraph::interface::{GraphBase, ImmutableGraphContainer};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Determines how nodes are named in the output.
+#[derive(Debug, Clone, Default)]
+pub enum NodeNames {
+    /// Nodes are named by their index in the graph.
+    #[default]
+    Indices,
+    /// Nodes are named by the given labels, indexed by node index.
+    Labels(Vec<String>),
+    /// Nodes are renumbered consecutively, leaving out nodes that have no name.
+    /// The names are indexed by node index.
+    Renumbered {
+        indices: Vec<Option<usize>>,
+        node_count: usize,
+    },
+}
+
+/// The name of a single node, as returned by [`NodeNames::name`].
+#[derive(Debug, Clone, Copy)]
+pub enum NodeName<'a> {
+    Index(usize),
+    Label(&'a str),
+}
+
+impl NodeName<'_> {
+    /// Add `offset` to the index. Labels are left as they are.
+    pub fn offset(self, offset: usize) -> Self {
+        match self {
+            Self::Index(index) => Self::Index(index + offset),
+            label => label,
+        }
+    }
+}
+
+impl NodeNames {
+    /// Name each node by the end of an original bcalm2 unitig it is attached to.
+    ///
+    /// The label `<id><orientation><side>` refers to the start (`s`) or end (`e`) of the unitig with bcalm2 id `<id>`
+    /// when read in forward (`+`) or reverse complement (`-`) orientation.
+    /// If a node is attached to multiple unitig ends, then the one with the smallest id is chosen,
+    /// preferring ends over starts and forward over reverse complement orientation.
+    pub fn from_original_ids(graph: &Graph) -> Self {
+        let mut keys: Vec<Option<(usize, bool, bool)>> = vec![None; graph.node_count()];
+        for edge_id in graph.edge_indices() {
+            let edge = graph.edge_endpoints(edge_id);
+            let edge_data = graph.edge_data(edge_id);
+            // Ordered such that ends and forward orientations are preferred.
+            for (node, is_start) in [(edge.from_node, true), (edge.to_node, false)] {
+                let key = (edge_data.id, is_start, !edge_data.forwards);
+                let current = &mut keys[node.as_usize()];
+                if current.map_or(true, |current| key < current) {
+                    *current = Some(key);
+                }
+            }
+        }
+
+        Self::Labels(
+            keys.into_iter()
+                .enumerate()
+                .map(|(node, key)| match key {
+                    Some((id, is_start, is_reverse)) => format!(
+                        "{id}{}{}",
+                        if is_reverse { '-' } else { '+' },
+                        if is_start { 's' } else { 'e' }
+                    ),
+                    // Nodes are only created for unitig ends, but fall back to the index just in case.
+                    None => node.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    /// Name each node by its entry in the external `mapping` from node indices to names.
+    /// Nodes without an entry are named by their index if `allow_unmapped` is true, and cause an error otherwise.
+    pub fn from_mapping(
+        graph: &Graph,
+        mapping: &HashMap<usize, String>,
+        allow_unmapped: bool,
+    ) -> anyhow::Result<Self> {
+        let mut labels = Vec::with_capacity(graph.node_count());
+        for node in 0..graph.node_count() {
+            labels.push(match mapping.get(&node) {
+                Some(name) => name.clone(),
+                None if allow_unmapped => node.to_string(),
+                None => bail!("Node {node} has no name in the node mapping"),
+            });
+        }
+        Ok(Self::Labels(labels))
+    }
+
+    /// Renumber the nodes consecutively in the order of their indices,
+    /// leaving out nodes that are not the endpoint of any edge for which `is_written` returns true.
+    pub fn without_isolated_nodes(
+        graph: &Graph,
+        is_written: impl Fn(<Graph as GraphBase>::EdgeIndex) -> bool,
+    ) -> Self {
+        let mut is_referenced = vec![false; graph.node_count()];
+        for edge_id in graph.edge_indices().filter(|&edge_id| is_written(edge_id)) {
+            let edge = graph.edge_endpoints(edge_id);
+            is_referenced[edge.from_node.as_usize()] = true;
+            is_referenced[edge.to_node.as_usize()] = true;
+        }
+
+        let mut node_count = 0;
+        let indices = is_referenced
+            .into_iter()
+            .map(|is_referenced| {
+                is_referenced.then(|| {
+                    node_count += 1;
+                    node_count - 1
+                })
+            })
+            .collect();
+        Self::Renumbered {
+            indices,
+            node_count,
+        }
+    }
+
+    /// The number of named nodes, to be written as header.
+    pub fn node_count(&self, graph: &Graph) -> usize {
+        match self {
+            Self::Indices | Self::Labels(_) => graph.node_count(),
+            Self::Renumbered { node_count, .. } => *node_count,
+        }
+    }
+
+    pub fn name(&self, node: usize) -> NodeName<'_> {
+        match self {
+            Self::Indices => NodeName::Index(node),
+            Self::Labels(labels) => NodeName::Label(&labels[node]),
+            Self::Renumbered { indices, .. } => {
+                NodeName::Index(indices[node].expect("isolated nodes have no name"))
+            }
+        }
+    }
+}
+
+impl Display for NodeName<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "{index}"),
+            Self::Label(label) => write!(f, "{label}"),
+        }
+    }
+}
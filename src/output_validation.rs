@@ -0,0 +1,98 @@
+use crate::arc_centric_reader::{read_arc_centric_graph, ArcCentricGraph};
+use crate::mirror_index::{arcs_by_endpoints, is_mirror_arc, match_mirror_arcs};
+use anyhow::bail;
+use log::warn;
+use std::io::BufRead;
+
+/// Check that the mirror columns of each arc point to an arc whose mirror columns point back,
+/// and whose sequence is the reverse complement, as matched by [`match_mirror_arcs`].
+/// Returns a description of each arc without such a reciprocal arc.
+pub fn find_arcs_without_mirror(graph: &ArcCentricGraph) -> Vec<String> {
+    graph
+        .arcs
+        .iter()
+        .zip(match_mirror_arcs(graph))
+        .enumerate()
+        .filter(|(_, (_, mirror))| mirror.is_none())
+        .map(|(index, (arc, _))| {
+            format!(
+                "arc {index} ({} -> {}) has no reciprocal arc {} -> {} with the reverse complement sequence",
+                arc.from, arc.to, arc.mirror_from, arc.mirror_to
+            )
+        })
+        .collect()
+}
+
+/// Read back an arc-centric graph and check that it parses and that its mirror columns are consistent.
+pub fn validate_output(input: impl BufRead) -> anyhow::Result<()> {
+    let graph = read_arc_centric_graph(input)?;
+    let inconsistencies = find_arcs_without_mirror(&graph);
+    for inconsistency in &inconsistencies {
+        warn!("Invalid output: {inconsistency}");
+    }
+    if !inconsistencies.is_empty() {
+        bail!(
+            "Output contains {} arcs without a reciprocal arc",
+            inconsistencies.len()
+        );
+    }
+    Ok(())
+}
+
+/// Check that each arc that is not its own mirror has exactly one reciprocal arc as in [`find_arcs_without_mirror`],
+/// and that the weights of reciprocal arcs agree.
+/// Returns a description of each violation.
+pub fn find_symmetry_violations(graph: &ArcCentricGraph) -> Vec<String> {
+    let arcs_by_endpoints = arcs_by_endpoints(graph);
+    let mirrors = match_mirror_arcs(graph);
+
+    let mut violations = Vec::new();
+    for (index, (arc, &mirror)) in graph.arcs.iter().zip(&mirrors).enumerate() {
+        if mirror == Some(index) {
+            continue;
+        }
+
+        let reciprocal_count = arcs_by_endpoints
+            .get(&(arc.mirror_from, arc.mirror_to))
+            .into_iter()
+            .flatten()
+            .filter(|&&mirror_index| is_mirror_arc(arc, &graph.arcs[mirror_index]))
+            .count();
+        match mirror {
+            None => violations.push(format!(
+                "arc {index} ({} -> {}) has no reciprocal arc {} -> {}",
+                arc.from, arc.to, arc.mirror_from, arc.mirror_to
+            )),
+            Some(mirror_index) if reciprocal_count == 1 => {
+                let mirror_weight = graph.arcs[mirror_index].weight;
+                if mirror_weight != arc.weight {
+                    violations.push(format!(
+                        "arc {index} ({} -> {}) has weight {}, but its reciprocal arc {mirror_index} has weight {mirror_weight}",
+                        arc.from, arc.to, arc.weight
+                    ));
+                }
+            }
+            Some(_) => violations.push(format!(
+                "arc {index} ({} -> {}) has {reciprocal_count} reciprocal arcs",
+                arc.from, arc.to
+            )),
+        }
+    }
+    violations
+}
+
+/// Read back an arc-centric graph and check that it is a proper bidirected graph, see [`find_symmetry_violations`].
+pub fn validate_bigraph_symmetry(input: impl BufRead) -> anyhow::Result<()> {
+    let graph = read_arc_centric_graph(input)?;
+    let violations = find_symmetry_violations(&graph);
+    for violation in &violations {
+        warn!("Asymmetric output: {violation}");
+    }
+    if !violations.is_empty() {
+        bail!(
+            "Output contains {} violations of the bigraph symmetry",
+            violations.len()
+        );
+    }
+    Ok(())
+}
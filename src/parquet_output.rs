@@ -0,0 +1,96 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use parquet::format::KeyValue;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The number of arcs per row group.
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+/// The key of the file metadata entry with the node count.
+pub const NODE_COUNT_KEY: &str = "node_count";
+
+/// The columns of the parquet format, one row per arc.
+fn schema() -> Schema {
+    let mut fields: Vec<_> = ["from", "to", "weight", "mirror_from", "mirror_to"]
+        .into_iter()
+        .map(|name| Field::new(name, DataType::UInt64, false))
+        .collect();
+    fields.push(Field::new("sequence", DataType::Utf8, false));
+    Schema::new(fields)
+}
+
+/// Write the graph as a parquet file with the columns `from`, `to`, `weight`, `mirror_from`, `mirror_to` and `sequence`,
+/// and the node count as file metadata with key [`NODE_COUNT_KEY`].
+/// Colors and tags are not written.
+///
+/// Each row group is flushed after [`ROW_GROUP_SIZE`] arcs and then written to the output,
+/// so at most about one row group is buffered in memory.
+pub fn write_parquet(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let schema = Arc::new(schema());
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(ROW_GROUP_SIZE)
+        .build();
+    let buffer = SharedBuffer::default();
+    let mut writer = ArrowWriter::try_new(buffer.clone(), schema.clone(), Some(properties))?;
+    writer.append_key_value_metadata(KeyValue::new(
+        NODE_COUNT_KEY.to_owned(),
+        graph.node_count.to_string(),
+    ));
+
+    for arcs in graph.arcs.chunks(ROW_GROUP_SIZE) {
+        let column = |value: fn(&_) -> usize| -> ArrayRef {
+            Arc::new(UInt64Array::from_iter_values(
+                arcs.iter().map(|arc| value(arc) as u64),
+            ))
+        };
+        let columns = vec![
+            column(|arc| arc.from),
+            column(|arc| arc.to),
+            column(|arc| arc.weight),
+            column(|arc| arc.mirror_from),
+            column(|arc| arc.mirror_to),
+            Arc::new(StringArray::from_iter_values(
+                arcs.iter().map(|arc| &arc.sequence),
+            )),
+        ];
+        writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+        writer.flush()?;
+        buffer.drain_into(output)?;
+    }
+
+    writer.close()?;
+    buffer.drain_into(output)?;
+    Ok(())
+}
+
+/// The output of the parquet writer, which requires a thread-safe output.
+/// The bytes written into it are moved to the actual output with [`SharedBuffer::drain_into`].
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn drain_into(&self, output: &mut impl Write) -> io::Result<()> {
+        let mut buffer = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        output.write_all(&buffer)?;
+        buffer.clear();
+        Ok(())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use anyhow::ensure;
+
+/// Collapse each chain of arcs whose inner nodes have exactly one incoming and one outgoing arc into a single arc.
+///
+/// The sequence of the collapsed arc is the concatenation of the sequences of the chain,
+/// where consecutive sequences overlap by `k-1` characters. Its weight is the average of the weights of the chain,
+/// weighted by the number of k-mers of each arc, and it keeps the tags and colors of the first arc of the chain.
+/// Since the mirror arcs of a chain form a chain as well, the collapsed arcs are mirrors of each other.
+///
+/// The inner nodes are removed, and the remaining nodes are renumbered consecutively in the order of their indices.
+/// Cycles that consist only of inner nodes are left as they are.
+pub fn collapse_linear_paths(graph: &ArcCentricGraph, k: usize) -> anyhow::Result<ArcCentricGraph> {
+    let mut incoming = vec![Vec::new(); graph.node_count];
+    let mut outgoing = vec![Vec::new(); graph.node_count];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        ensure!(
+            arc.sequence.len() >= k,
+            "Arc {} -> {} has a sequence shorter than k = {k}",
+            arc.from,
+            arc.to
+        );
+        outgoing[arc.from].push(index);
+        incoming[arc.to].push(index);
+    }
+    let is_inner = |node: usize| {
+        incoming[node].len() == 1 && outgoing[node].len() == 1 && incoming[node] != outgoing[node]
+    };
+
+    let mut is_collapsed = vec![false; graph.arcs.len()];
+    let mut chains = Vec::new();
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        if is_inner(arc.from) {
+            continue;
+        }
+        let mut chain = vec![index];
+        let mut node = arc.to;
+        while is_inner(node) {
+            let next = outgoing[node][0];
+            chain.push(next);
+            node = graph.arcs[next].to;
+        }
+        for &index in &chain {
+            is_collapsed[index] = true;
+        }
+        chains.push(chain);
+    }
+    // Arcs on cycles of inner nodes are not reached from any chain start, so their nodes are kept.
+    let mut is_on_cycle = vec![false; graph.node_count];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        if !is_collapsed[index] {
+            chains.push(vec![index]);
+            is_on_cycle[arc.from] = true;
+            is_on_cycle[arc.to] = true;
+        }
+    }
+    chains.sort_unstable_by_key(|chain| chain[0]);
+
+    let mut node_indices = vec![None; graph.node_count];
+    let mut node_count = 0;
+    for node in 0..graph.node_count {
+        if !is_inner(node) || is_on_cycle[node] {
+            node_indices[node] = Some(node_count);
+            node_count += 1;
+        }
+    }
+    let node_index = |node: usize| node_indices[node].expect("endpoints of chains are not removed");
+
+    let arcs = chains
+        .into_iter()
+        .map(|chain| {
+            let first = &graph.arcs[chain[0]];
+            let last = &graph.arcs[*chain.last().unwrap()];
+            let mut sequence = first.sequence.clone();
+            let mut weighted_sum = 0;
+            let mut kmer_count = 0;
+            for &index in &chain {
+                let arc = &graph.arcs[index];
+                if index != chain[0] {
+                    sequence.push_str(&arc.sequence[k - 1..]);
+                }
+                let arc_kmer_count = arc.sequence.len() - (k - 1);
+                weighted_sum += arc.weight * arc_kmer_count;
+                kmer_count += arc_kmer_count;
+            }
+            ArcRecord {
+                from: node_index(first.from),
+                to: node_index(last.to),
+                weight: (weighted_sum + kmer_count / 2) / kmer_count,
+                mirror_from: node_index(last.mirror_from),
+                mirror_to: node_index(first.mirror_to),
+                sequence,
+                ..first.clone()
+            }
+        })
+        .collect();
+    Ok(ArcCentricGraph { node_count, arcs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collapse_linear_paths;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::output_validation::find_arcs_without_mirror;
+
+    #[test]
+    fn test_collapse_cycle() {
+        // A cycle through nodes 0 and 1 and its mirror cycle through nodes 2 and 3 have no chain start.
+        let input = "4\n0 1 2 3 2 AACG\n1 0 4 2 3 CGAA\n2 3 4 1 0 TTCG\n3 2 2 0 1 CGTT\n";
+        let graph = read_arc_centric_graph(input.as_bytes()).unwrap();
+        let collapsed = collapse_linear_paths(&graph, 3).unwrap();
+        assert_eq!(collapsed, graph);
+        assert!(find_arcs_without_mirror(&collapsed).is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use log::warn;
+use std::fs::File;
+use std::io::{self, Seek};
+
+/// Reserve `size` bytes for the file, to reduce fragmentation when the size of the output is known approximately.
+/// On Linux, the space is allocated with `fallocate`, elsewhere the file is only extended with [`File::set_len`].
+/// Since this extends the file, it has to be truncated with [`truncate_to_position`] after writing.
+pub fn preallocate(file: &File, size: u64) -> io::Result<()> {
+    if size == 0 {
+        return Ok(());
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        let Ok(length) = libc::off_t::try_from(size) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot preallocate {size} bytes"),
+            ));
+        };
+        // SAFETY: the file descriptor is valid for the lifetime of `file`.
+        if unsafe { libc::fallocate(file.as_raw_fd(), 0, 0, length) } == 0 {
+            return Ok(());
+        }
+        warn!(
+            "Could not preallocate with fallocate, extending the file instead: {}",
+            io::Error::last_os_error()
+        );
+    }
+    file.set_len(size)
+}
+
+/// Truncate the file to the current position, removing the preallocated space that was not written.
+pub fn truncate_to_position(file: &mut File) -> io::Result<()> {
+    let position = file.stream_position()?;
+    file.set_len(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preallocate, truncate_to_position};
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn test_truncate_after_preallocation() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("output.txt");
+        for size in [0, 5, 10, 1 << 20] {
+            let mut file = File::create(&path).unwrap();
+            preallocate(&file, size).unwrap();
+            file.write_all(b"4\n0 1 1 AC").unwrap();
+            truncate_to_position(&mut file).unwrap();
+            drop(file);
+            assert_eq!(std::fs::read(&path).unwrap(), b"4\n0 1 1 AC");
+        }
+    }
+}
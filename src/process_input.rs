@@ -0,0 +1,71 @@
+use anyhow::Context;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+/// Reads the standard output of a command, as given to `--input-from-process`.
+///
+/// The command is run with `sh -c`. When its output is exhausted, the command is waited for,
+/// and a nonzero exit status is reported as a read error, such that a failing command
+/// does not silently result in a truncated graph.
+pub struct ProcessInput {
+    command: String,
+    /// The running command, until it was waited for.
+    child: Option<Child>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessInput {
+    pub fn spawn(command: &str) -> anyhow::Result<Self> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Could not run the input process `{command}`"))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        Ok(Self {
+            command: command.to_owned(),
+            child: Some(child),
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    /// Wait for the command, and report an error if it failed.
+    fn wait(&mut self) -> io::Result<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        let status = child.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("The input process `{}` failed with {status}", self.command),
+            ))
+        }
+    }
+}
+
+impl Read for ProcessInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl BufRead for ProcessInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.stdout.fill_buf()?.is_empty() {
+            self.wait()?;
+        }
+        self.stdout.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.stdout.consume(amount);
+    }
+}
@@ -0,0 +1,120 @@
+use log::info;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The number of past reports over which the processing rate is averaged for the ETA.
+const RATE_WINDOW: usize = 10;
+
+/// Logs the progress of the writer every `interval` processed nodes, with an estimate of the remaining time.
+/// Meant for log files, where a progress bar would be useless.
+pub struct ProgressReporter {
+    interval: usize,
+    total: usize,
+    processed: usize,
+    /// The times and processed counts of the last reports, starting with the creation of the reporter.
+    window: VecDeque<(Instant, usize)>,
+}
+
+impl ProgressReporter {
+    /// Create a new progress reporter for `total` nodes.
+    /// If `interval` is zero, then no progress is reported.
+    pub fn new(interval: usize, total: usize) -> Self {
+        Self {
+            interval,
+            total,
+            processed: 0,
+            window: VecDeque::from([(Instant::now(), 0)]),
+        }
+    }
+
+    /// Mark one more node as processed.
+    pub fn advance(&mut self) {
+        self.processed += 1;
+        if self.interval > 0 && self.processed % self.interval == 0 {
+            let now = Instant::now();
+            // Average the rate over the last reports only, such that the estimate adapts to changing rates.
+            let &(window_start, window_start_processed) = self.window.front().unwrap();
+            let eta = estimate_eta(
+                self.processed - window_start_processed,
+                self.total.saturating_sub(window_start_processed),
+                now - window_start,
+            );
+            self.window.push_back((now, self.processed));
+            if self.window.len() > RATE_WINDOW {
+                self.window.pop_front();
+            }
+
+            info!(
+                "Processed {} / {} nodes, ETA {:.1?}",
+                format_thousands(self.processed),
+                format_thousands(self.total),
+                eta.unwrap_or_default()
+            );
+        }
+    }
+
+    /// Log the number of processed nodes, independently of the interval.
+    pub fn report_snapshot(&self) {
+        info!(
+            "Snapshot: processed {} / {} nodes",
+            format_thousands(self.processed),
+            format_thousands(self.total)
+        );
+    }
+}
+
+/// Estimate the time until `total` items are processed,
+/// if `processed` items were processed in `elapsed` time and the rate stays the same.
+/// Returns `None` if nothing was processed yet.
+pub fn estimate_eta(processed: usize, total: usize, elapsed: Duration) -> Option<Duration> {
+    if processed == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(processed);
+    Some(elapsed.mul_f64(remaining as f64 / processed as f64))
+}
+
+/// Format a number with commas as thousands separators, e.g. `1,000,000`.
+pub fn format_thousands(number: usize) -> String {
+    let digits = number.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_eta, format_thousands};
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_thousands() {
+        assert_eq!(format_thousands(0), "0");
+        assert_eq!(format_thousands(999), "999");
+        assert_eq!(format_thousands(1000), "1,000");
+        assert_eq!(format_thousands(1_000_000), "1,000,000");
+        assert_eq!(format_thousands(12_345_678), "12,345,678");
+    }
+
+    #[test]
+    fn test_estimate_eta() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(estimate_eta(0, 100, elapsed), None);
+        assert_eq!(
+            estimate_eta(10, 100, elapsed),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            estimate_eta(50, 100, elapsed),
+            Some(Duration::from_secs(10))
+        );
+        let eta = estimate_eta(90, 100, elapsed).unwrap();
+        assert!((eta.as_secs_f64() - 10.0 / 9.0).abs() < 1e-6);
+        assert_eq!(estimate_eta(100, 100, elapsed), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::arc_centric_reader::{ArcCentricGraph, ArcRecord};
+use anyhow::{ensure, Context};
+use prost::Message;
+use std::io::{Read, Write};
+
+/// The first message of the protobuf format, see `proto/arc_record.proto`.
+#[derive(Clone, PartialEq, Message)]
+pub struct GraphHeaderMessage {
+    #[prost(uint64, tag = "1")]
+    pub node_count: u64,
+}
+
+/// An arc in the protobuf format, see `proto/arc_record.proto`.
+#[derive(Clone, PartialEq, Message)]
+pub struct ArcRecordMessage {
+    #[prost(uint64, tag = "1")]
+    pub from: u64,
+    #[prost(uint64, tag = "2")]
+    pub to: u64,
+    #[prost(uint64, tag = "3")]
+    pub weight: u64,
+    #[prost(uint64, tag = "4")]
+    pub mirror_from: u64,
+    #[prost(uint64, tag = "5")]
+    pub mirror_to: u64,
+    #[prost(string, tag = "6")]
+    pub sequence: String,
+    #[prost(bool, repeated, tag = "7")]
+    pub colors: Vec<bool>,
+    #[prost(string, repeated, tag = "8")]
+    pub tags: Vec<String>,
+}
+
+impl From<&ArcRecord> for ArcRecordMessage {
+    fn from(arc: &ArcRecord) -> Self {
+        Self {
+            from: arc.from as u64,
+            to: arc.to as u64,
+            weight: arc.weight as u64,
+            mirror_from: arc.mirror_from as u64,
+            mirror_to: arc.mirror_to as u64,
+            sequence: arc.sequence.clone(),
+            colors: arc.colors.clone(),
+            tags: arc.tags.clone(),
+        }
+    }
+}
+
+impl TryFrom<ArcRecordMessage> for ArcRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(message: ArcRecordMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            from: message.from.try_into()?,
+            to: message.to.try_into()?,
+            weight: message.weight.try_into()?,
+            mirror_from: message.mirror_from.try_into()?,
+            mirror_to: message.mirror_to.try_into()?,
+            sequence: message.sequence,
+            colors: message.colors,
+            tags: message.tags,
+        })
+    }
+}
+
+/// Write the graph as a stream of length-delimited protobuf messages,
+/// starting with a [`GraphHeaderMessage`] followed by an [`ArcRecordMessage`] for each arc.
+pub fn write_protobuf(graph: &ArcCentricGraph, output: &mut impl Write) -> anyhow::Result<()> {
+    let header = GraphHeaderMessage {
+        node_count: graph.node_count as u64,
+    };
+    output.write_all(&header.encode_length_delimited_to_vec())?;
+    for arc in &graph.arcs {
+        output.write_all(&ArcRecordMessage::from(arc).encode_length_delimited_to_vec())?;
+    }
+    Ok(())
+}
+
+/// Read a graph written by [`write_protobuf`].
+pub fn read_protobuf(mut input: impl Read) -> anyhow::Result<ArcCentricGraph> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let mut buffer = bytes.as_slice();
+
+    let header = GraphHeaderMessage::decode_length_delimited(&mut buffer)
+        .context("malformed graph header message")?;
+    let node_count = header.node_count.try_into()?;
+
+    let mut arcs = Vec::new();
+    while !buffer.is_empty() {
+        let arc: ArcRecord = ArcRecordMessage::decode_length_delimited(&mut buffer)
+            .with_context(|| format!("malformed message of arc {}", arcs.len()))?
+            .try_into()?;
+        for node in [arc.from, arc.to, arc.mirror_from, arc.mirror_to] {
+            ensure!(
+                node < node_count,
+                "node {node} of arc {} is out of range for {node_count} nodes",
+                arcs.len()
+            );
+        }
+        arcs.push(arc);
+    }
+
+    Ok(ArcCentricGraph { node_count, arcs })
+}
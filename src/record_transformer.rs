@@ -0,0 +1,226 @@
+use std::io::{BufRead, Read};
+
+/// A fasta record as it is passed through a [`RecordTransformer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastaRecord {
+    /// The header line without the leading `>` and the trailing newline.
+    pub header: String,
+    /// The sequence, with the lines of multi-line records concatenated.
+    pub sequence: Vec<u8>,
+}
+
+impl FastaRecord {
+    /// Set the value of the header tag with the given prefix, e.g. `LN:i:`.
+    /// If the tag does not exist, then it is appended.
+    pub fn set_tag(&mut self, prefix: &str, value: impl std::fmt::Display) {
+        let tag = format!("{prefix}{value}");
+        let mut found = false;
+        let mut header: Vec<String> = self
+            .header
+            .split_whitespace()
+            .map(|token| {
+                if token.starts_with(prefix) {
+                    found = true;
+                    tag.clone()
+                } else {
+                    token.to_owned()
+                }
+            })
+            .collect();
+        if !found {
+            header.push(tag);
+        }
+        self.header = header.join(" ");
+    }
+}
+
+/// A [`BufRead`] adapter that applies a function to each fasta record before passing it on.
+/// If the function returns `false`, then the record is dropped.
+///
+/// Data before the first header is passed through unchanged, so that the fasta reader can report it.
+pub struct RecordTransformer<R, F> {
+    inner: R,
+    transform: F,
+    next_header: Option<Vec<u8>>,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl<R: BufRead, F: FnMut(&mut FastaRecord) -> bool> RecordTransformer<R, F> {
+    pub fn new(inner: R, transform: F) -> Self {
+        Self {
+            inner,
+            transform,
+            next_header: None,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Refill the buffer with the next record, or with the next line if it is not part of a record.
+    /// Leaves the buffer empty at the end of the input.
+    fn refill(&mut self) -> std::io::Result<()> {
+        self.buffer.clear();
+        self.position = 0;
+
+        while self.buffer.is_empty() {
+            let header = if let Some(header) = self.next_header.take() {
+                header
+            } else {
+                let mut line = Vec::new();
+                if self.inner.read_until(b'\n', &mut line)? == 0 {
+                    return Ok(());
+                }
+                if line[0] != b'>' {
+                    self.buffer = line;
+                    return Ok(());
+                }
+                line
+            };
+
+            let mut sequence = Vec::new();
+            loop {
+                let mut line = Vec::new();
+                if self.inner.read_until(b'\n', &mut line)? == 0 {
+                    break;
+                }
+                if line[0] == b'>' {
+                    self.next_header = Some(line);
+                    break;
+                }
+                if line.last() == Some(&b'\n') {
+                    line.pop();
+                }
+                sequence.extend_from_slice(&line);
+            }
+
+            let header = String::from_utf8_lossy(&header[1..]);
+            let mut record = FastaRecord {
+                header: header.trim_end_matches('\n').to_owned(),
+                sequence,
+            };
+            if (self.transform)(&mut record) {
+                self.buffer.push(b'>');
+                self.buffer.extend_from_slice(record.header.as_bytes());
+                self.buffer.push(b'\n');
+                self.buffer.extend_from_slice(&record.sequence);
+                self.buffer.push(b'\n');
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead, F: FnMut(&mut FastaRecord) -> bool> Read for RecordTransformer<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buf.len());
+        buf[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+        Ok(amount)
+    }
+}
+
+impl<R: BufRead, F: FnMut(&mut FastaRecord) -> bool> BufRead for RecordTransformer<R, F> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.position == self.buffer.len() {
+            self.refill()?;
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.buffer.len());
+    }
+}
+
+/// Collapse each run of identical characters into a single character, e.g. `AAAACGT` becomes `ACGT`.
+pub fn compress_homopolymers(sequence: &mut Vec<u8>) {
+    sequence.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+}
+
+/// Compress the homopolymers of the sequence of a bcalm2 record and update its length tag.
+pub fn compress_record_homopolymers(record: &mut FastaRecord) -> bool {
+    compress_homopolymers(&mut record.sequence);
+    record.set_tag("LN:i:", record.sequence.len());
+    true
+}
+
+/// The prefixes of the bcalm2 header tags that are kept by [`strip_record_tags`].
+pub const BCALM2_TAG_PREFIXES: [&str; 4] = ["LN:", "KC:", "km:", "L:"];
+
+/// Remove all header tags of a bcalm2 record except for the record id and the tags in [`BCALM2_TAG_PREFIXES`].
+pub fn strip_record_tags(record: &mut FastaRecord) -> bool {
+    let mut tokens = record.header.split_whitespace();
+    let header: Vec<_> = tokens
+        .next()
+        .into_iter()
+        .chain(tokens.filter(|token| {
+            BCALM2_TAG_PREFIXES
+                .iter()
+                .any(|prefix| token.starts_with(prefix))
+        }))
+        .collect();
+    record.header = header.join(" ");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress_homopolymers, FastaRecord, RecordTransformer};
+    use std::io::{BufReader, Read};
+
+    fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+        sequence
+            .iter()
+            .rev()
+            .map(|c| match c {
+                b'A' => b'T',
+                b'C' => b'G',
+                b'G' => b'C',
+                b'T' => b'A',
+                c => panic!("{c}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compress_homopolymers() {
+        let mut sequence = b"AAAACGT".to_vec();
+        compress_homopolymers(&mut sequence);
+        assert_eq!(sequence, b"ACGT");
+
+        for sequence in [&b"AAAACGT"[..], b"ACCGGGTTTTAC", b"TTTTTT", b""] {
+            let mut compressed_reverse_complement = reverse_complement(sequence);
+            compress_homopolymers(&mut compressed_reverse_complement);
+            let mut compressed = sequence.to_vec();
+            compress_homopolymers(&mut compressed);
+            assert_eq!(
+                compressed_reverse_complement,
+                reverse_complement(&compressed)
+            );
+        }
+    }
+
+    #[test]
+    fn test_record_transformer() {
+        let input = ">0 LN:i:4 KC:i:2\nAC\nGT\n>1 LN:i:2\nCC\n>2 LN:i:3\nGGA";
+        let mut output = String::new();
+        RecordTransformer::new(
+            BufReader::with_capacity(3, input.as_bytes()),
+            |record: &mut FastaRecord| {
+                record.set_tag("LN:i:", 5);
+                record.set_tag("XX:i:", 1);
+                !record.header.starts_with('1')
+            },
+        )
+        .read_to_string(&mut output)
+        .unwrap();
+
+        assert_eq!(
+            output,
+            ">0 LN:i:5 KC:i:2 XX:i:1\nACGT\n>2 LN:i:5 XX:i:1\nGGA\n"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::record_transformer::FastaRecord;
+use genome_graph::compact_genome::interface::alphabet::dna_alphabet::DnaCharacter;
+
+/// Check that the bcalm2 graph reader accepts the record, before it is passed to the reader.
+/// Returns a description of the first problem found otherwise.
+///
+/// This repeats the checks of the reader for single records: the id must be numeric,
+/// the tags `LN:i:`, `KC:i:` and `km:f:` must each occur exactly once with a valid value,
+/// the length must match the sequence, the links must be well-formed, and no other tags are allowed.
+/// The sequence must consist of `ACGT` only, since the reader panics on other characters.
+pub fn check_record(record: &FastaRecord) -> Result<(), String> {
+    let mut tokens = record.header.split_whitespace();
+    let id = tokens.next().unwrap_or_default();
+    if id.parse::<usize>().is_err() {
+        return Err(format!("the id {id:?} is not numeric"));
+    }
+
+    let mut length = None;
+    let mut has_total_abundance = false;
+    let mut has_mean_abundance = false;
+    for token in tokens {
+        let is_valid = if let Some(value) = token.strip_prefix("LN:i:") {
+            let is_first = length.is_none();
+            length = value.parse::<usize>().ok();
+            is_first && length.is_some()
+        } else if let Some(value) = token.strip_prefix("KC:i:") {
+            let is_first = !has_total_abundance;
+            has_total_abundance = true;
+            is_first && value.parse::<usize>().is_ok()
+        } else if let Some(value) = token
+            .strip_prefix("km:f:")
+            .or_else(|| token.strip_prefix("KM:f:"))
+        {
+            let is_first = !has_mean_abundance;
+            has_mean_abundance = true;
+            is_first && value.parse::<f64>().is_ok()
+        } else if token.starts_with("L:") {
+            let parts: Vec<_> = token.split(':').collect();
+            parts.len() == 4
+                && matches!(parts[1], "+" | "-")
+                && parts[2].parse::<usize>().is_ok()
+                && matches!(parts[3], "+" | "-")
+        } else {
+            false
+        };
+        if !is_valid {
+            return Err(format!(
+                "the tag {token:?} is malformed, duplicate or unknown"
+            ));
+        }
+    }
+
+    let Some(length) = length else {
+        return Err("the length tag LN:i: is missing".to_owned());
+    };
+    if !has_total_abundance || !has_mean_abundance {
+        return Err("the abundance tags KC:i: and km:f: are missing".to_owned());
+    }
+    if length != record.sequence.len() {
+        return Err(format!(
+            "the length {length} does not match the sequence length {}",
+            record.sequence.len()
+        ));
+    }
+    if let Some(&character) = record
+        .sequence
+        .iter()
+        .find(|&&character| DnaCharacter::try_from(character).is_err())
+    {
+        return Err(format!(
+            "the sequence contains the character {:?}",
+            char::from(character)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_record;
+    use crate::record_transformer::FastaRecord;
+
+    #[test]
+    fn test_check_record() {
+        let check = |header: &str, sequence: &str| {
+            check_record(&FastaRecord {
+                header: header.to_owned(),
+                sequence: sequence.as_bytes().to_vec(),
+            })
+        };
+        assert_eq!(check("0 LN:i:4 KC:i:6 km:f:3.0 L:+:1:-", "ACGT"), Ok(()));
+        for (header, sequence) in [
+            ("x LN:i:4 KC:i:6 km:f:3.0", "ACGT"),
+            ("0 LN:i:4 KC:i:6", "ACGT"),
+            ("0 LN:i:5 KC:i:6 km:f:3.0", "ACGT"),
+            ("0 LN:i:4 LN:i:4 KC:i:6 km:f:3.0", "ACGT"),
+            ("0 LN:i:4 KC:i:6 km:f:3.0 L:+:1", "ACGT"),
+            ("0 LN:i:4 KC:i:6 km:f:3.0 XX:i:1", "ACGT"),
+            ("0 LN:i:4 KC:i:6 km:f:3.0", "ACNT"),
+        ] {
+            assert!(check(header, sequence).is_err(), "{header} {sequence}");
+        }
+    }
+}
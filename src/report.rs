@@ -0,0 +1,69 @@
+use crate::alphabet::Alphabet;
+use crate::kmer_spectrum::KmerSpectrum;
+use crate::statistics::GraphStatistics;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Statistics collected while converting a graph.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionReport {
+    /// The time spent reading the node-centric input graph.
+    pub read_duration: Duration,
+    /// The time spent writing the arc-centric output graph.
+    pub write_duration: Duration,
+    /// The time spent on the whole conversion.
+    pub total_duration: Duration,
+    /// The number of bytes of link records released before writing.
+    pub released_link_record_bytes: usize,
+    /// The number of unitig sequences that were not stored because they are identical to another one.
+    /// Only counted with sequence deduplication.
+    pub deduplicated_sequences: usize,
+    /// The alphabet of the input sequences, as detected with [`Alphabet::Auto`].
+    pub alphabet: Alphabet,
+    /// The number of bytes read from the input, after decompression.
+    pub input_bytes: usize,
+    /// The number of bytes written to the output.
+    pub output_bytes: usize,
+    /// The number of arcs of the graph, counting reverse complemental arcs separately.
+    pub arc_count: usize,
+    /// Histograms of the written graph. Only collected for the arc-centric format.
+    pub statistics: GraphStatistics,
+    /// The k-mer spectrum of the input graph. Only collected if requested.
+    pub kmer_spectrum: KmerSpectrum,
+}
+
+impl ConversionReport {
+    /// The throughput of the conversion phases.
+    pub fn throughput(&self) -> Throughput {
+        Throughput {
+            arcs_per_second: self.arc_count as f64 / self.total_duration.as_secs_f64(),
+            input_megabytes_per_second: self.input_bytes as f64
+                / 1e6
+                / self.read_duration.as_secs_f64(),
+            output_megabytes_per_second: self.output_bytes as f64
+                / 1e6
+                / self.write_duration.as_secs_f64(),
+        }
+    }
+}
+
+/// The throughput of a conversion, see [`ConversionReport::throughput`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throughput {
+    /// The arcs of the graph over the total conversion time.
+    pub arcs_per_second: f64,
+    /// The input bytes over the reading time, in megabytes per second.
+    pub input_megabytes_per_second: f64,
+    /// The output bytes over the writing time, in megabytes per second.
+    pub output_megabytes_per_second: f64,
+}
+
+impl Display for Throughput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.0} arcs/s, reading {:.2}MB/s, writing {:.2}MB/s",
+            self.arcs_per_second, self.input_megabytes_per_second, self.output_megabytes_per_second
+        )
+    }
+}
@@ -0,0 +1,121 @@
+use crate::arc_centric_reader::{is_reverse_complement, ArcCentricGraph, ArcRecord};
+use crate::output_validation::find_arcs_without_mirror;
+use anyhow::{bail, ensure};
+use std::collections::HashMap;
+
+/// Whether the arc is its own mirror and could be merged with an equal arc, i.e. it has the same endpoints
+/// as its mirror, and its sequence is its own reverse complement.
+fn is_mergeable(arc: &ArcRecord) -> bool {
+    arc.from == arc.mirror_from
+        && arc.to == arc.mirror_to
+        && is_reverse_complement(&arc.sequence, &arc.sequence)
+}
+
+/// Merge pairs of equal arcs that are their own mirrors and have a palindromic sequence into a single arc,
+/// adding their weights, like the converter merges reverse complemental arcs.
+/// The merged arc takes the place of the first arc of the pair, and an unpaired arc is kept as is.
+pub fn merge_mirror_pairs(graph: &ArcCentricGraph) -> ArcCentricGraph {
+    let mut arcs: Vec<Option<ArcRecord>> = Vec::with_capacity(graph.arcs.len());
+    // The index of the unpaired arc with each line, ignoring the weight.
+    let mut unpaired: HashMap<String, usize> = HashMap::new();
+    for arc in &graph.arcs {
+        if !is_mergeable(arc) {
+            arcs.push(Some(arc.clone()));
+            continue;
+        }
+        let key = ArcRecord {
+            weight: 0,
+            ..arc.clone()
+        }
+        .to_string();
+        match unpaired.remove(&key) {
+            Some(index) => arcs[index].as_mut().unwrap().weight += arc.weight,
+            None => {
+                unpaired.insert(key, arcs.len());
+                arcs.push(Some(arc.clone()));
+            }
+        }
+    }
+
+    ArcCentricGraph {
+        node_count: graph.node_count,
+        arcs: arcs.into_iter().flatten().collect(),
+    }
+}
+
+/// The arc lines of the graph in sorted order, to compare graphs independently of the order of their arcs.
+fn sorted_arc_lines(graph: &ArcCentricGraph) -> Vec<String> {
+    let mut lines: Vec<_> = graph.arcs.iter().map(ToString::to_string).collect();
+    lines.sort_unstable();
+    lines
+}
+
+/// Check that merging reverse complemental arcs is stable: both graphs must have a mirror for each arc,
+/// the `merged` graph must not contain arcs that can still be merged, and merging the arcs of the `materialized` graph,
+/// which was converted with `--materialize-mirrors`, must result in the `merged` graph.
+pub fn check_merge_stability(
+    merged: &ArcCentricGraph,
+    materialized: &ArcCentricGraph,
+) -> anyhow::Result<()> {
+    for (name, graph) in [("merged", merged), ("materialized", materialized)] {
+        let arcs_without_mirror = find_arcs_without_mirror(graph);
+        if let Some(arc) = arcs_without_mirror.first() {
+            bail!(
+                "Found {} arcs without mirror in the {name} graph, e.g. {arc}",
+                arcs_without_mirror.len()
+            );
+        }
+    }
+    ensure!(
+        merge_mirror_pairs(merged) == *merged,
+        "The merged graph contains arcs that can be merged again"
+    );
+    ensure!(
+        merged.node_count == materialized.node_count,
+        "The merged graph has {} nodes, but the materialized graph has {}",
+        merged.node_count,
+        materialized.node_count
+    );
+
+    let remerged = sorted_arc_lines(&merge_mirror_pairs(materialized));
+    let merged = sorted_arc_lines(merged);
+    if let Some((remerged_arc, merged_arc)) = remerged
+        .iter()
+        .zip(&merged)
+        .find(|(remerged_arc, merged_arc)| remerged_arc != merged_arc)
+    {
+        bail!("Merging the materialized graph results in the arc {remerged_arc}, but the merged graph has {merged_arc}");
+    }
+    ensure!(
+        remerged.len() == merged.len(),
+        "Merging the materialized graph results in {} arcs, but the merged graph has {}",
+        remerged.len(),
+        merged.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_merge_stability, merge_mirror_pairs};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+
+    #[test]
+    fn test_merge_mirror_pairs() {
+        let graph = |input: &str| read_arc_centric_graph(input.as_bytes()).unwrap();
+        // Only the palindromic arcs from node 0 to node 1 are merged.
+        let materialized =
+            graph("3\n0 1 2 0 1 ACGT\n1 2 3 2 1 AACC\n2 1 3 1 2 GGTT\n0 1 2 0 1 ACGT\n");
+        let merged = graph("3\n0 1 4 0 1 ACGT\n1 2 3 2 1 AACC\n2 1 3 1 2 GGTT\n");
+        assert_eq!(merge_mirror_pairs(&materialized), merged);
+        check_merge_stability(&merged, &materialized).unwrap();
+
+        // The materialized arcs do not add up to the weight of the merged arc.
+        let materialized =
+            graph("3\n0 1 2 0 1 ACGT\n1 2 3 2 1 AACC\n2 1 3 1 2 GGTT\n0 1 3 0 1 ACGT\n");
+        assert!(check_merge_stability(&merged, &materialized).is_err());
+        // The merged graph contains an arc pair that should have been merged.
+        let unmerged = graph("2\n0 1 2 0 1 ACGT\n0 1 2 0 1 ACGT\n");
+        assert!(check_merge_stability(&unmerged, &unmerged).is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use crate::mirror_index::find_mirror_arcs;
+use log::info;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Create the random number generator for all features that sample randomly.
+/// If no seed is given, then a random seed is generated.
+/// The seed is logged either way, such that each run can be reproduced with `--seed`.
+pub fn make_rng(seed: Option<u64>) -> StdRng {
+    let seed = seed.unwrap_or_else(rand::random);
+    info!("Using random seed {seed}");
+    StdRng::seed_from_u64(seed)
+}
+
+/// Keep each arc together with its mirror arc with the given probability.
+/// The order of the arcs and the node count are not changed.
+pub fn sample_arcs(
+    graph: &ArcCentricGraph,
+    probability: f64,
+    rng: &mut impl Rng,
+) -> anyhow::Result<ArcCentricGraph> {
+    let mirrors = find_mirror_arcs(graph)?;
+    let mut kept = vec![None; graph.arcs.len()];
+    for (index, &mirror_index) in mirrors.iter().enumerate() {
+        if kept[index].is_none() {
+            let is_kept = rng.gen_bool(probability);
+            kept[index] = Some(is_kept);
+            kept[mirror_index] = Some(is_kept);
+        }
+    }
+
+    Ok(ArcCentricGraph {
+        node_count: graph.node_count,
+        arcs: graph
+            .arcs
+            .iter()
+            .zip(kept)
+            .filter(|(_, kept)| *kept == Some(true))
+            .map(|(arc, _)| arc.clone())
+            .collect(),
+    })
+}
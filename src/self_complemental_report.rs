@@ -0,0 +1,27 @@
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+/// An arc that is its own reverse complement, and hence was merged with its mirror arc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfComplementalArc {
+    pub from: String,
+    pub to: String,
+    pub sequence: String,
+}
+
+impl Display for SelfComplementalArc {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.from, self.to, self.sequence)
+    }
+}
+
+/// Write one line `<from> <to> <sequence>` per arc, in the order in which the arcs were written.
+pub fn write_self_complemental_report(
+    arcs: &[SelfComplementalArc],
+    output: &mut impl Write,
+) -> std::io::Result<()> {
+    for arc in arcs {
+        writeln!(output, "{arc}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::arc_centric_reader::ArcCentricGraph;
+use std::io::Write;
+
+/// Write the length of the sequence of each arc, one per line, in the order of the arcs.
+/// Merged reverse complemental arcs are a single arc, and hence have a single length.
+pub fn write_sequence_lengths(
+    graph: &ArcCentricGraph,
+    output: &mut impl Write,
+) -> anyhow::Result<()> {
+    for arc in &graph.arcs {
+        writeln!(output, "{}", arc.sequence.len())?;
+    }
+    Ok(())
+}
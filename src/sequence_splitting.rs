@@ -0,0 +1,167 @@
+use crate::arc_centric_reader::{reverse_complement, ArcCentricGraph, ArcRecord};
+use crate::mirror_index::find_mirror_arcs;
+use anyhow::{ensure, Context};
+
+/// The prefix of the tag with the offset of an arc sequence in the sequence of the arc it was split from.
+pub const SOURCE_OFFSET_TAG: &str = "SO:i:";
+
+/// Split each arc with a sequence longer than `max_length` into a chain of arcs with sequences of at most `max_length`,
+/// where consecutive arcs overlap by `k-1` characters.
+///
+/// The intermediate nodes of the chains are appended after the existing nodes.
+/// The mirror arc of a split arc is split into the reverse complements of the same pieces, in reverse order,
+/// such that each piece has a mirror piece.
+/// The pieces keep the weight and the tags of the split arc, except that the offset of each piece
+/// is added to the source offset tag `SO:i:` of the split arc, if any.
+pub fn split_long_arcs(
+    graph: &ArcCentricGraph,
+    k: usize,
+    max_length: usize,
+) -> anyhow::Result<ArcCentricGraph> {
+    ensure!(
+        max_length >= k,
+        "The maximum sequence length {max_length} must be at least k = {k}"
+    );
+    let mirrors = find_mirror_arcs(graph)?;
+
+    let mut node_count = graph.node_count;
+    let mut pieces: Vec<Option<Vec<ArcRecord>>> = vec![None; graph.arcs.len()];
+    for (index, arc) in graph.arcs.iter().enumerate() {
+        let mirror_index = mirrors[index];
+        if pieces[index].is_some() {
+            continue;
+        }
+        if arc.sequence.len() <= max_length {
+            pieces[index] = Some(vec![arc.clone()]);
+            pieces[mirror_index] = Some(vec![graph.arcs[mirror_index].clone()]);
+            continue;
+        }
+
+        let kmer_counts = piece_kmer_counts(arc.sequence.len() - (k - 1), max_length - (k - 1));
+        let piece_count = kmer_counts.len();
+
+        // The nodes of the chain of the arc, and the nodes of the chain of its mirror, such that
+        // the `i`th node of the mirror chain is the reverse complement of the `i`th node of the chain.
+        let mut nodes = vec![arc.from];
+        nodes.extend(node_count..node_count + piece_count - 1);
+        node_count += piece_count - 1;
+        nodes.push(arc.to);
+        let mirror_nodes: Vec<_> = if mirror_index == index {
+            nodes.iter().rev().copied().collect()
+        } else {
+            let mut mirror_nodes = vec![arc.mirror_to];
+            mirror_nodes.extend(node_count..node_count + piece_count - 1);
+            node_count += piece_count - 1;
+            mirror_nodes.push(arc.mirror_from);
+            mirror_nodes
+        };
+
+        let mut arc_pieces = Vec::new();
+        let mut mirror_pieces = Vec::new();
+        let mut offset = 0;
+        for (piece, kmer_count) in kmer_counts.into_iter().enumerate() {
+            let sequence = &arc.sequence[offset..offset + kmer_count + k - 1];
+            let mirror_offset = arc.sequence.len() - offset - sequence.len();
+            let piece_offset = offset;
+            offset += kmer_count;
+
+            arc_pieces.push(ArcRecord {
+                from: nodes[piece],
+                to: nodes[piece + 1],
+                mirror_from: mirror_nodes[piece + 1],
+                mirror_to: mirror_nodes[piece],
+                sequence: sequence.to_owned(),
+                tags: shift_source_offset(&arc.tags, piece_offset)?,
+                ..arc.clone()
+            });
+            mirror_pieces.push(ArcRecord {
+                from: mirror_nodes[piece + 1],
+                to: mirror_nodes[piece],
+                mirror_from: nodes[piece],
+                mirror_to: nodes[piece + 1],
+                sequence: reverse_complement(sequence),
+                tags: shift_source_offset(&graph.arcs[mirror_index].tags, mirror_offset)?,
+                ..graph.arcs[mirror_index].clone()
+            });
+        }
+
+        if mirror_index != index {
+            mirror_pieces.reverse();
+            pieces[mirror_index] = Some(mirror_pieces);
+        }
+        pieces[index] = Some(arc_pieces);
+    }
+
+    Ok(ArcCentricGraph {
+        node_count,
+        arcs: pieces.into_iter().flatten().flatten().collect(),
+    })
+}
+
+/// The tags with `offset` added to the source offset tag `SO:i:`.
+fn shift_source_offset(tags: &[String], offset: usize) -> anyhow::Result<Vec<String>> {
+    tags.iter()
+        .map(|tag| {
+            Ok(match tag.strip_prefix(SOURCE_OFFSET_TAG) {
+                Some(value) => {
+                    let source_offset: usize = value
+                        .parse()
+                        .with_context(|| format!("Malformed source offset tag {tag}"))?;
+                    format!("{SOURCE_OFFSET_TAG}{}", source_offset + offset)
+                }
+                None => tag.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Distribute `kmer_count` k-mers into as few pieces as possible with at most `max_kmer_count` k-mers each.
+/// The counts are symmetric, i.e. the same when reversed, such that splitting a palindromic sequence
+/// results in pieces that are reverse complements of each other.
+fn piece_kmer_counts(kmer_count: usize, max_kmer_count: usize) -> Vec<usize> {
+    let mut piece_count = (kmer_count + max_kmer_count - 1) / max_kmer_count;
+    // Symmetric counts of an even number of pieces have an even sum.
+    if piece_count % 2 == 0 && kmer_count % 2 == 1 {
+        piece_count += 1;
+    }
+
+    let base = kmer_count / piece_count;
+    let remainder = kmer_count % piece_count;
+    let mut counts = vec![base; piece_count];
+    for index in 0..remainder / 2 {
+        counts[index] += 1;
+        counts[piece_count - 1 - index] += 1;
+    }
+    if remainder % 2 == 1 {
+        counts[piece_count / 2] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{piece_kmer_counts, split_long_arcs};
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::output_validation::find_arcs_without_mirror;
+
+    #[test]
+    fn test_piece_kmer_counts() {
+        assert_eq!(piece_kmer_counts(4, 4), vec![4]);
+        assert_eq!(piece_kmer_counts(10, 4), vec![3, 4, 3]);
+        assert_eq!(piece_kmer_counts(11, 4), vec![4, 3, 4]);
+        // Four pieces cannot be symmetric with an odd sum.
+        assert_eq!(piece_kmer_counts(13, 4), vec![3, 2, 3, 2, 3]);
+        assert_eq!(piece_kmer_counts(14, 4), vec![4, 3, 3, 4]);
+    }
+
+    #[test]
+    fn test_split_palindromic_arc() {
+        // A merged palindromic arc is its own mirror.
+        let graph = read_arc_centric_graph("2\n0 1 4 0 1 AAAACGCGTTTT\n".as_bytes()).unwrap();
+        let split = split_long_arcs(&graph, 3, 6).unwrap();
+        assert_eq!(split.node_count, 4);
+        assert!(find_arcs_without_mirror(&split).is_empty());
+        let sequences: Vec<_> = split.arcs.iter().map(|arc| arc.sequence.as_str()).collect();
+        assert_eq!(sequences, vec!["AAAAC", "ACGCGT", "GTTTT"]);
+    }
+}
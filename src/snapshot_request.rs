@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A request for a snapshot of the progress and the memory usage, e.g. triggered by a signal.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotRequest {
+    requested: Arc<AtomicBool>,
+}
+
+impl SnapshotRequest {
+    /// Create a request that is triggered whenever the process receives `SIGUSR1`.
+    #[cfg(unix)]
+    pub fn on_signal() -> anyhow::Result<Self> {
+        let request = Self::default();
+        signal_hook::flag::register(signal_hook::consts::SIGUSR1, request.requested.clone())?;
+        Ok(request)
+    }
+
+    /// Signals are only supported on Unix, so elsewhere the request is never triggered.
+    #[cfg(not(unix))]
+    pub fn on_signal() -> anyhow::Result<Self> {
+        log::warn!("Snapshots on signals are only supported on Unix");
+        Ok(Self::default())
+    }
+
+    /// Returns true if a snapshot was requested since the last call.
+    pub fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::Relaxed)
+    }
+}
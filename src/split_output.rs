@@ -0,0 +1,262 @@
+use crate::arc_centric_reader::{normalise_sequence, reverse_complement};
+use anyhow::{bail, Context};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The path of the part with the given index, e.g. `out.part0.txt` for `out.txt`.
+pub fn part_path(output: &Path, index: usize) -> PathBuf {
+    numbered_path(output, "part", index)
+}
+
+/// The path of the shard with the given index, e.g. `out.shard0.txt` for `out.txt`.
+pub fn shard_path(output: &Path, index: usize) -> PathBuf {
+    numbered_path(output, "shard", index)
+}
+
+fn numbered_path(output: &Path, label: &str, index: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match output.extension() {
+        Some(extension) => format!("{stem}.{label}{index}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{label}{index}"),
+    };
+    output.with_file_name(file_name)
+}
+
+/// Split an arc-centric graph into parts of at most `max_size` bytes each.
+/// Each part repeats the node count header, and each arc is written into the same part as its reverse complement.
+/// Parts are created by `open_part` with increasing indices, starting from zero.
+/// Returns the number of parts.
+pub fn write_split_output<W: Write>(
+    output: &str,
+    max_size: usize,
+    mut open_part: impl FnMut(usize) -> anyhow::Result<W>,
+) -> anyhow::Result<usize> {
+    let mut lines = output.lines();
+    let header = lines.next().context("missing node count header")?;
+    let header = format!("{header}\n");
+
+    // Group each arc with its reverse complement, keeping the order of first appearance.
+    let mut group_indices = HashMap::new();
+    let mut groups: Vec<Vec<&str>> = Vec::new();
+    for line in lines {
+        let key = reverse_complement_pair_key(line)?;
+        let group_index = *group_indices.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group_index].push(line);
+    }
+
+    let mut part_count = 0;
+    let mut part: Option<W> = None;
+    let mut part_size = 0;
+    for group in groups {
+        let group_size: usize = group.iter().map(|line| line.len() + 1).sum();
+        if header.len() + group_size > max_size {
+            bail!(
+                "A pair of reverse complemental arcs needs {} bytes, which exceeds the maximum output size of {max_size} bytes",
+                header.len() + group_size
+            );
+        }
+
+        if part.is_none() || part_size + group_size > max_size {
+            if let Some(mut part) = part.take() {
+                part.flush()?;
+            }
+            let mut new_part = open_part(part_count)?;
+            new_part.write_all(header.as_bytes())?;
+            part = Some(new_part);
+            part_size = header.len();
+            part_count += 1;
+        }
+
+        let part = part.as_mut().unwrap();
+        for line in group {
+            writeln!(part, "{line}")?;
+        }
+        part_size += group_size;
+    }
+
+    if let Some(mut part) = part {
+        part.flush()?;
+    }
+    Ok(part_count)
+}
+
+/// Partition an arc-centric graph into `shard_count` shards by hashing the endpoints of each arc.
+/// Each shard repeats the node count header, and each arc is written into the same shard as its reverse complement.
+/// Shards are created by `open_shard` for each index from zero to `shard_count`, even if they stay empty.
+pub fn write_sharded_output<W: Write>(
+    output: &str,
+    shard_count: usize,
+    mut open_shard: impl FnMut(usize) -> anyhow::Result<W>,
+) -> anyhow::Result<()> {
+    if shard_count == 0 {
+        bail!("The number of shards must be positive");
+    }
+
+    let mut lines = output.lines();
+    let header = lines.next().context("missing node count header")?;
+    let mut shards = (0..shard_count)
+        .map(|index| {
+            let mut shard = open_shard(index)?;
+            writeln!(shard, "{header}")?;
+            Ok(shard)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    for line in lines {
+        writeln!(shards[shard_of(line, shard_count)?], "{line}")?;
+    }
+    for mut shard in shards {
+        shard.flush()?;
+    }
+    Ok(())
+}
+
+/// Partition an arc-centric graph written with orientation tags `OR:A:+` and `OR:A:-` into three outputs:
+/// the arcs that spell their unitig, the arcs that spell its reverse complement,
+/// and the merged self-complemental arcs, which are their own mirror and therefore belong to both strands.
+/// Each output repeats the node count header. If `keep_orientation_tags` is false, then the tags are removed.
+/// Returns the number of arcs written into each output.
+pub fn write_strand_output<W: Write>(
+    output: &str,
+    keep_orientation_tags: bool,
+    outputs: [&mut W; 3],
+) -> anyhow::Result<[usize; 3]> {
+    let mut lines = output.lines();
+    let header = lines.next().context("missing node count header")?;
+    let arcs: Vec<_> = lines.collect();
+    let mut group_sizes = HashMap::new();
+    for line in &arcs {
+        *group_sizes
+            .entry(reverse_complement_pair_key(line)?)
+            .or_insert(0) += 1;
+    }
+
+    let [forward, reverse, merged] = outputs;
+    for output in [&mut *forward, &mut *reverse, &mut *merged] {
+        writeln!(output, "{header}")?;
+    }
+    let mut arc_counts = [0; 3];
+    for line in arcs {
+        let mut orientation = None;
+        let columns: Vec<_> = line
+            .split(' ')
+            .filter(|column| match *column {
+                "OR:A:+" | "OR:A:-" => {
+                    orientation = Some(*column == "OR:A:+");
+                    keep_orientation_tags
+                }
+                _ => true,
+            })
+            .collect();
+        let strand = if group_sizes[&reverse_complement_pair_key(line)?] == 1 {
+            2
+        } else {
+            match orientation {
+                Some(true) => 0,
+                Some(false) => 1,
+                None => bail!("missing orientation tag in arc {line:?}"),
+            }
+        };
+        let output = match strand {
+            0 => &mut *forward,
+            1 => &mut *reverse,
+            _ => &mut *merged,
+        };
+        writeln!(output, "{}", columns.join(" "))?;
+        arc_counts[strand] += 1;
+    }
+
+    for output in [forward, reverse, merged] {
+        output.flush()?;
+    }
+    Ok(arc_counts)
+}
+
+/// The shard of an arc.
+/// This hashes the smaller of the endpoint pairs of the arc and of its reverse complement,
+/// so both arcs of a reverse complemental pair are in the same shard.
+/// The hash function is fixed, so the shards are the same on every run and platform.
+pub fn shard_of(line: &str, shard_count: usize) -> anyhow::Result<usize> {
+    let columns: Vec<_> = line.split_whitespace().collect();
+    let [from, to, _weight, mirror_from, mirror_to, ..] = columns[..] else {
+        bail!("malformed arc: {line:?}");
+    };
+    let parse = |node: &str| -> anyhow::Result<u64> {
+        node.parse()
+            .with_context(|| format!("malformed node {node:?} in arc {line:?}"))
+    };
+    let (first, second) = (parse(from)?, parse(to)?).min((parse(mirror_from)?, parse(mirror_to)?));
+
+    // 64-bit FNV-1a
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in first.to_le_bytes().into_iter().chain(second.to_le_bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    Ok((hash % shard_count as u64) as usize)
+}
+
+/// A key that is equal for an arc and its reverse complement.
+fn reverse_complement_pair_key(line: &str) -> anyhow::Result<(&str, &str, String)> {
+    let columns: Vec<_> = line.split_whitespace().collect();
+    let [from, to, _weight, mirror_from, mirror_to, sequence, ..] = columns[..] else {
+        bail!("malformed arc: {line:?}");
+    };
+    let sequence = normalise_sequence(sequence);
+    let mirror_sequence = reverse_complement(&sequence);
+    Ok((from, to, sequence).min((mirror_from, mirror_to, mirror_sequence)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part_path, reverse_complement_pair_key, shard_of, shard_path};
+    use std::path::Path;
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("dir/out.txt"), 3),
+            Path::new("dir/out.part3.txt")
+        );
+        assert_eq!(part_path(Path::new("out"), 0), Path::new("out.part0"));
+    }
+
+    #[test]
+    fn test_shard_path() {
+        assert_eq!(
+            shard_path(Path::new("dir/out.txt"), 3),
+            Path::new("dir/out.shard3.txt")
+        );
+    }
+
+    #[test]
+    fn test_shard_of() {
+        let arc = "0 1 42 0 1 ATCGATCGATCGAT";
+        let mirror = "2 3 5 4 5 ACGTACGT";
+        let reverse_complement = "4 5 5 2 3 ACGTACGT";
+        for shard_count in 1..10 {
+            let shard = shard_of(arc, shard_count).unwrap();
+            assert!(shard < shard_count);
+            assert_eq!(shard_of(arc, shard_count).unwrap(), shard);
+            assert_eq!(
+                shard_of(mirror, shard_count).unwrap(),
+                shard_of(reverse_complement, shard_count).unwrap()
+            );
+        }
+        // The hash function is fixed, so the shards never change.
+        assert_eq!(shard_of(arc, 1000).unwrap(), 812);
+        assert!(shard_of("0 1 42", 2).is_err());
+    }
+
+    #[test]
+    fn test_rna_pair_key() {
+        assert_eq!(
+            reverse_complement_pair_key("0 1 4 2 3 AAAC").unwrap(),
+            reverse_complement_pair_key("2 3 4 0 1 GUUU").unwrap()
+        );
+    }
+}
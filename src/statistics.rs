@@ -0,0 +1,125 @@
+use crate::abundance_histogram::{AbundanceHistogram, HistogramScale};
+use crate::abundance_outliers::AbundanceOutliers;
+use crate::self_complemental_report::SelfComplementalArc;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Histograms of the written arc-centric graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphStatistics {
+    /// The number of nodes with each out-degree.
+    pub out_degrees: BTreeMap<usize, usize>,
+    /// The number of arcs with sequence lengths in each bucket.
+    /// The bucket `b` counts lengths `l` with `b <= l < 2 * b`, except that bucket zero counts only length zero.
+    pub sequence_lengths: BTreeMap<usize, usize>,
+    /// The number of arcs whose mirror arc has the same endpoints.
+    pub self_complemental_arcs: usize,
+    /// The number of arcs whose sequence is its own reverse complement.
+    pub palindromic_arcs: usize,
+    /// The number of arcs that were merged with their reverse complemental mirror arc.
+    pub merged_arcs: usize,
+    /// The number of arcs with each weight, before log-scaling.
+    pub weights: BTreeMap<usize, usize>,
+    /// The arcs with extreme weights, if requested.
+    pub abundance_outliers: Option<AbundanceOutliers>,
+    /// The arcs that are their own reverse complement and were merged with their mirror arc, if requested.
+    pub self_complemental_report: Option<Vec<SelfComplementalArc>>,
+}
+
+impl GraphStatistics {
+    pub fn record_node(&mut self, out_degree: usize) {
+        *self.out_degrees.entry(out_degree).or_default() += 1;
+    }
+
+    pub fn record_arc(&mut self, sequence_length: usize) {
+        let bucket = if sequence_length == 0 {
+            0
+        } else {
+            1 << sequence_length.ilog2()
+        };
+        *self.sequence_lengths.entry(bucket).or_default() += 1;
+    }
+
+    pub fn record_arc_weight(&mut self, weight: usize) {
+        *self.weights.entry(weight).or_default() += 1;
+    }
+
+    /// The weights of the arcs distributed into at most `bin_count` bins.
+    pub fn abundance_histogram(
+        &self,
+        bin_count: usize,
+        scale: HistogramScale,
+    ) -> AbundanceHistogram {
+        AbundanceHistogram::new(&self.weights, bin_count, scale)
+    }
+
+    pub fn record_arc_structure(
+        &mut self,
+        is_self_complemental: bool,
+        is_palindromic: bool,
+        is_merged: bool,
+    ) {
+        self.self_complemental_arcs += usize::from(is_self_complemental);
+        self.palindromic_arcs += usize::from(is_palindromic);
+        self.merged_arcs += usize::from(is_merged);
+    }
+
+    /// The fraction of arcs that were merged with their mirror arc, or zero if there are no arcs.
+    pub fn merged_arc_ratio(&self) -> f64 {
+        if self.arc_count() == 0 {
+            0.0
+        } else {
+            self.merged_arcs as f64 / self.arc_count() as f64
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.out_degrees.values().sum()
+    }
+
+    pub fn arc_count(&self) -> usize {
+        self.sequence_lengths.values().sum()
+    }
+}
+
+impl Display for GraphStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} nodes by out-degree:", self.node_count())?;
+        for (out_degree, count) in &self.out_degrees {
+            writeln!(f, "  {out_degree}: {count}")?;
+        }
+        write!(f, "{} arcs by sequence length:", self.arc_count())?;
+        for (bucket, count) in &self.sequence_lengths {
+            if *bucket == 0 {
+                write!(f, "\n  0: {count}")?;
+            } else {
+                write!(f, "\n  {bucket}-{}: {count}", 2 * bucket - 1)?;
+            }
+        }
+        write!(
+            f,
+            "\n{} self-complemental arcs, {} palindromic arcs, {} merged arcs ({:.1}% of all arcs)",
+            self.self_complemental_arcs,
+            self.palindromic_arcs,
+            self.merged_arcs,
+            self.merged_arc_ratio() * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphStatistics;
+
+    #[test]
+    fn test_length_buckets() {
+        let mut statistics = GraphStatistics::default();
+        for length in [0, 1, 2, 3, 4, 7, 8, 31] {
+            statistics.record_arc(length);
+        }
+        assert_eq!(
+            statistics.sequence_lengths.into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 1), (2, 2), (4, 2), (8, 1), (16, 1)]
+        );
+    }
+}
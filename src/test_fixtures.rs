@@ -0,0 +1,39 @@
+//! Graphs in the bcalm2 format used as test inputs.
+//! This file is also included by the integration tests.
+
+pub const COMPLEX_FILE: &str = ">0 LN:i:14 KC:i:21 km:f:21.0   L:-:2:+  L:+:2:+
+ATCGATCGATCGAT
+>1 LN:i:14 KC:i:20 km:f:20.0   L:-:2:-  L:+:2:-
+CGATCGATCGATCG
+>2 LN:i:14 KC:i:43 km:f:43.0   L:+:1:+ L:+:1:- L:+:3:+  L:-:0:+ L:-:0:-
+TCGATCGATCGATC
+>3 LN:i:16 KC:i:3 km:f:1.0   L:-:2:-
+CGATCGATCGATCAGT";
+
+pub const COMPLEX_CIRCULARISED_FILE: &str = ">0 LN:i:14 KC:i:20 km:f:20.0   L:-:1:-  L:+:1:-
+CGATCGATCGATCG
+>1 LN:i:14 KC:i:43 km:f:43.0   L:+:0:+ L:+:0:- L:+:4:+ L:+:5:+  L:-:2:+ L:-:2:- L:-:5:-
+TCGATCGATCGATC
+>2 LN:i:14 KC:i:21 km:f:21.0   L:-:1:+  L:+:1:+
+ATCGATCGATCGAT
+>3 LN:i:27 KC:i:14 km:f:1.0   L:-:4:-  L:+:4:-
+GATCGATCGATCAGTGATCGATCGATC
+>4 LN:i:14 KC:i:2 km:f:2.0   L:-:1:-  L:+:3:+ L:+:3:-
+CGATCGATCGATCA
+>5 LN:i:26 KC:i:13 km:f:1.0   L:-:1:-  L:+:1:+
+CGATCGATCGATCTCGATCGATCGAT";
+
+pub const PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE: &str =
+    ">0 LN:i:30 KC:i:16 km:f:1.0   L:-:1:-  L:+:1:-
+ATATATATATATGGCACCATATATATATAT
+>1 LN:i:16 KC:i:4 km:f:2.0   L:-:1:+ L:-:2:+  L:+:0:+ L:+:0:-
+ATATATATATATATGG
+>2 LN:i:15 KC:i:1 km:f:1.0   L:+:2:- L:+:4:+  L:-:1:+ L:-:2:+
+ATATATATATATATA
+>3 LN:i:19 KC:i:5 km:f:1.0    L:+:4:-
+ACGGGGGGGGGGACACACA
+>4 LN:i:38 KC:i:29 km:f:1.2   L:-:2:- L:-:4:+  L:+:3:- L:+:5:-
+TATATATATATATAAAAACAACCGTGTGTGTCCCCCCC
+>5 LN:i:19 KC:i:5 km:f:1.0    L:+:4:-
+ATGCTGGGGGGGACACACA
+";
@@ -0,0 +1,41 @@
+//! A logger for tests that captures the log messages of the current thread.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::sync::Once;
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<(Level, String)>>> = const { RefCell::new(None) };
+}
+
+struct CapturingLogger;
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        CAPTURED.with(|captured| {
+            if let Some(captured) = captured.borrow_mut().as_mut() {
+                captured.push((record.level(), record.args().to_string()));
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Run `f` and return all messages it logged on the current thread.
+/// Each test runs in its own thread, so messages of concurrently running tests are not captured.
+pub fn capture_logs(f: impl FnOnce()) -> Vec<(Level, String)> {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&CapturingLogger).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap())
+}
@@ -0,0 +1,50 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// The exit code of a conversion that is aborted by a [`Timeout`], the same as the one of GNU `timeout`.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// A wall-clock budget for the conversion, starting when it is created.
+#[derive(Debug, Clone, Copy)]
+pub struct Timeout {
+    duration: Duration,
+    deadline: Instant,
+}
+
+impl Timeout {
+    pub fn start(duration: Duration) -> Self {
+        Self {
+            duration,
+            deadline: Instant::now() + duration,
+        }
+    }
+
+    /// Fails if the budget is exceeded.
+    pub fn check(&self) -> Result<(), TimeoutError> {
+        if Instant::now() >= self.deadline {
+            Err(TimeoutError {
+                duration: self.duration,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error of a conversion that exceeded its [`Timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutError {
+    pub duration: Duration,
+}
+
+impl Display for TimeoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The conversion did not finish within the timeout of {:.2?}",
+            self.duration
+        )
+    }
+}
+
+impl std::error::Error for TimeoutError {}
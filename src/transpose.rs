@@ -0,0 +1,44 @@
+use crate::arc_centric_reader::{reverse_complement, ArcCentricGraph, ArcRecord};
+
+/// Reverse the direction of each arc, such that it goes from its original end to its original start
+/// and has the reverse complement sequence. The mirror of a transposed arc is the transposed mirror arc.
+///
+/// Transposing twice gives the original graph.
+/// The order of the arcs, the node count and the tags are not changed.
+pub fn transpose(graph: &ArcCentricGraph) -> ArcCentricGraph {
+    ArcCentricGraph {
+        node_count: graph.node_count,
+        arcs: graph
+            .arcs
+            .iter()
+            .map(|arc| ArcRecord {
+                from: arc.to,
+                to: arc.from,
+                mirror_from: arc.mirror_to,
+                mirror_to: arc.mirror_from,
+                sequence: reverse_complement(&arc.sequence),
+                ..arc.clone()
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transpose;
+    use crate::arc_centric_reader::read_arc_centric_graph;
+    use crate::output_validation::find_arcs_without_mirror;
+
+    #[test]
+    fn test_transpose() {
+        let graph =
+            read_arc_centric_graph("4\n0 1 5 2 3 AACG\n2 3 5 0 1 CGTT\n".as_bytes()).unwrap();
+        let transposed = transpose(&graph);
+        assert_eq!(
+            transposed,
+            read_arc_centric_graph("4\n1 0 5 3 2 CGTT\n3 2 5 1 0 AACG\n".as_bytes()).unwrap()
+        );
+        assert!(find_arcs_without_mirror(&transposed).is_empty());
+        assert_eq!(transpose(&transposed), graph);
+    }
+}
@@ -0,0 +1,26 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How the weight of an arc is computed from the abundance of its unitig.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WeightMetric {
+    /// The mean abundance of the k-mers of the unitig.
+    #[default]
+    MeanAbundance,
+    /// The total abundance of the k-mers of the unitig, i.e. the number of k-mer occurrences.
+    TotalOccurrences,
+    /// The total abundance of the k-mers of the unitig divided by the length of the unitig.
+    MeanAbundancePerBase,
+}
+
+impl WeightMetric {
+    /// The number by which the total abundance of a unitig of length `length` is divided to get its weight.
+    pub fn divisor(self, length: usize, k: usize) -> usize {
+        match self {
+            Self::MeanAbundance => length - (k - 1),
+            Self::TotalOccurrences => 1,
+            Self::MeanAbundancePerBase => length,
+        }
+    }
+}
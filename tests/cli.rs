@@ -0,0 +1,476 @@
+//! Tests that run the compiled binary.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+
+#[path = "../src/test_fixtures.rs"]
+mod test_fixtures;
+
+use test_fixtures::{
+    COMPLEX_CIRCULARISED_FILE, COMPLEX_FILE, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE,
+};
+
+fn write_input(directory: &TempDir, content: &str) -> std::path::PathBuf {
+    let path = directory.path().join("input.fa");
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+fn command() -> Command {
+    let mut command = Command::cargo_bin("node-to-arc-centric-dbg").unwrap();
+    command.env_remove("NODE_TO_ARC_LOG");
+    command
+}
+
+#[test]
+fn test_success() {
+    for (k, input, node_count) in [
+        (14, COMPLEX_FILE, 6),
+        (14, COMPLEX_CIRCULARISED_FILE, 6),
+        (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE, 10),
+    ] {
+        let directory = TempDir::new().unwrap();
+        let input = write_input(&directory, input);
+        let output = directory.path().join("output.txt");
+
+        command()
+            .arg("-k")
+            .arg(k.to_string())
+            .arg("--input")
+            .arg(&input)
+            .arg("--output")
+            .arg(&output)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Success!"));
+
+        let output = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(output.lines().next(), Some(node_count.to_string().as_str()));
+        assert!(output.lines().count() > 1);
+    }
+}
+
+#[test]
+fn test_missing_input_file() {
+    let directory = TempDir::new().unwrap();
+    let input = directory.path().join("missing.fa");
+    let output = directory.path().join("output.txt");
+
+    command()
+        .args(["-k", "14", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not open input file"));
+    assert!(!Path::new(&output).exists());
+}
+
+#[test]
+fn test_bad_k() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let output = directory.path().join("output.txt");
+
+    command()
+        .args(["-k", "fourteen", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("invalid value 'fourteen'"));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_parse_only() {
+    let directory = TempDir::new().unwrap();
+    let output = directory.path().join("output.txt");
+
+    let input = write_input(&directory, COMPLEX_FILE);
+    command()
+        .args(["-k", "14", "--parse-only", "--input"])
+        .arg(&input)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Parsed graph with 6 nodes and 8 edges",
+        ));
+
+    let input = write_input(&directory, &COMPLEX_FILE.replacen(">0", ">zero", 1));
+    command()
+        .args(["-k", "14", "--parse-only", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Could not parse"));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_preallocate() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let expected_output = directory.path().join("expected.txt");
+    command()
+        .args(["-k", "14", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&expected_output)
+        .assert()
+        .success();
+    let expected_output = std::fs::read(&expected_output).unwrap();
+
+    for preallocate in [1, expected_output.len(), 1 << 20] {
+        let output = directory.path().join("output.txt");
+        command()
+            .args([
+                "-k",
+                "14",
+                "--preallocate",
+                &preallocate.to_string(),
+                "--input",
+            ])
+            .arg(&input)
+            .arg("--output")
+            .arg(&output)
+            .assert()
+            .success();
+        assert_eq!(std::fs::read(&output).unwrap(), expected_output);
+    }
+}
+
+#[test]
+fn test_atomic_output() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let output = directory.path().join("output.txt");
+    let temporary_output = directory.path().join("output.txt.tmp");
+
+    command()
+        .args(["-k", "14", "--atomic-output", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success();
+    assert!(output.exists());
+    assert!(!temporary_output.exists());
+    std::fs::remove_file(&output).unwrap();
+
+    // A node count that is too small fails while writing the arcs.
+    command()
+        .args([
+            "-k",
+            "14",
+            "--atomic-output",
+            "--node-count",
+            "1",
+            "--input",
+        ])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not below the node count"));
+    assert!(!output.exists());
+    assert!(!temporary_output.exists());
+}
+
+#[test]
+fn test_timeout() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let output = directory.path().join("output.txt");
+    let temporary_output = directory.path().join("output.txt.tmp");
+
+    // A zero timeout is exceeded as soon as the graph is read.
+    command()
+        .args(["-k", "14", "--timeout", "0", "--atomic-output", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .code(124)
+        .stderr(predicate::str::contains(
+            "did not finish within the timeout",
+        ));
+    assert!(!output.exists());
+    assert!(!temporary_output.exists());
+
+    command()
+        .args(["-k", "14", "--timeout=-1", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid timeout"));
+
+    command()
+        .args(["-k", "14", "--timeout", "3600", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_manifest() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let output = directory.path().join("output.txt");
+    let manifest = directory.path().join("manifest.json");
+
+    command()
+        .args(["-k", "14", "--shards", "3", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&output)
+        .arg("--manifest")
+        .arg(&manifest)
+        .assert()
+        .success();
+
+    let manifest = std::fs::read_to_string(&manifest).unwrap();
+    let entries: Vec<_> = manifest
+        .lines()
+        .filter(|line| line.contains("\"path\""))
+        .collect();
+    assert_eq!(entries.len(), 3);
+    for (index, entry) in entries.into_iter().enumerate() {
+        let shard = directory.path().join(format!("output.shard{index}.txt"));
+        let size = std::fs::metadata(&shard).unwrap().len();
+        assert!(entry.contains(&format!("\"path\": \"{}\"", shard.display())));
+        assert!(entry.contains(&format!("\"role\": \"shard\", \"size\": {size},")));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_mmap_output() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE);
+    let expected_output = directory.path().join("expected.txt");
+    command()
+        .args(["-k", "15", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&expected_output)
+        .assert()
+        .success();
+    let expected_output = std::fs::read(&expected_output).unwrap();
+
+    for preallocate in [None, Some(1), Some(1 << 20)] {
+        let output = directory.path().join("output.txt");
+        let mut command = command();
+        command.args(["-k", "15", "--mmap-output", "--input"]);
+        command.arg(&input).arg("--output").arg(&output);
+        if let Some(preallocate) = preallocate {
+            command.args(["--preallocate", &preallocate.to_string()]);
+        }
+        command.assert().success();
+        assert_eq!(std::fs::read(&output).unwrap(), expected_output);
+    }
+}
+
+#[test]
+fn test_parallel_io() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE);
+    let mut outputs = Vec::new();
+    for parallel_io in [false, true] {
+        let output = directory.path().join(format!("output_{parallel_io}.txt"));
+        let mut command = command();
+        command.args(["-k", "15", "--input"]);
+        command.arg(&input).arg("--output").arg(&output);
+        if parallel_io {
+            command.args(["--parallel-io", "--preallocate", "4096"]);
+        }
+        command.assert().success();
+        outputs.push(std::fs::read(&output).unwrap());
+    }
+    assert!(!outputs[0].is_empty());
+    assert_eq!(outputs[0], outputs[1]);
+}
+
+#[test]
+fn test_input_from_process() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE);
+    let expected_output = directory.path().join("expected.txt");
+    command()
+        .args(["-k", "15", "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&expected_output)
+        .assert()
+        .success();
+
+    let output = directory.path().join("output.txt");
+    command()
+        .args(["-k", "15", "--input-from-process"])
+        .arg(format!("cat '{}'", input.display()))
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read(&output).unwrap(),
+        std::fs::read(&expected_output).unwrap()
+    );
+
+    command()
+        .args(["-k", "15", "--input-from-process"])
+        .arg(format!("cat '{}'; exit 3", input.display()))
+        .arg("--output")
+        .arg(&output)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed with exit status: 3"));
+}
+
+#[test]
+fn test_self_test() {
+    for (k, content) in [
+        (14, COMPLEX_FILE),
+        (14, COMPLEX_CIRCULARISED_FILE),
+        (15, PSEUDO_REVERSE_COMPLEMENTAL_ARC_FILE),
+    ] {
+        let directory = TempDir::new().unwrap();
+        let input = write_input(&directory, content);
+        command()
+            .args(["-k", &k.to_string(), "--self-test", "--input"])
+            .arg(&input)
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn test_conflicting_options() {
+    let directory = TempDir::new().unwrap();
+    let input = write_input(&directory, COMPLEX_FILE);
+    let strand_options = [
+        "--split-by-strand",
+        "--forward-out",
+        "forward.txt",
+        "--reverse-out",
+        "reverse.txt",
+    ];
+    for (options, conflicting) in [
+        (["--expand-kmers"].as_slice(), ["--trim-overlap"].as_slice()),
+        (&["--expand-kmers"], &["--node-count", "100"]),
+        (&["--expand-kmers"], &["--annotate-nodes"]),
+        (&["--expand-kmers"], &["--preserve-original-ids"]),
+        (&["--max-output-size", "100"], &["--trim-overlap"]),
+        (&strand_options, &["--atomic-output"]),
+        (&strand_options, &["--mmap-output"]),
+        (&strand_options, &["--preallocate", "100"]),
+        (&strand_options, &["--trim-overlap"]),
+        (&strand_options, &["--base", "base.txt"]),
+        (&["--mirror-index", "mirrors.txt"], &["--annotate-nodes"]),
+        (&strand_options, &["--validate-output"]),
+        (&strand_options, &["--strict-bigraph-symmetry"]),
+        (&strand_options, &["--mirror-index", "mirrors.txt"]),
+        (&strand_options, &["--lengths-file", "lengths.txt"]),
+        (&["--base", "base.txt"], &["--max-output-size", "100"]),
+        (&["--base", "base.txt"], &["--mirror-index", "mirrors.txt"]),
+        (&["--base", "base.txt"], &["--lengths-file", "lengths.txt"]),
+        (&["--base", "base.txt"], &["--global-sort"]),
+        (
+            &["--base", "base.txt"],
+            &["--buffered-sort", "--sort-tmp-dir", "."],
+        ),
+    ] {
+        command()
+            .args(["-k", "14", "--input"])
+            .arg(&input)
+            .arg("--output")
+            .arg(directory.path().join("output.txt"))
+            .args(options)
+            .args(conflicting)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}
+
+#[test]
+fn test_input_glob() {
+    let directory = TempDir::new().unwrap();
+    for name in ["first.bcalm2", "second.bcalm2", "ignored.txt"] {
+        std::fs::write(directory.path().join(name), COMPLEX_FILE).unwrap();
+    }
+    let template = directory.path().join("{name}.arcs");
+    command()
+        .args(["-k", "14", "--input-glob"])
+        .arg(directory.path().join("*.bcalm2"))
+        .arg("--output-template")
+        .arg(&template)
+        .assert()
+        .success();
+
+    let expected = std::fs::read_to_string(directory.path().join("first.arcs")).unwrap();
+    assert_eq!(expected.lines().next(), Some("6"));
+    assert_eq!(
+        std::fs::read_to_string(directory.path().join("second.arcs")).unwrap(),
+        expected
+    );
+    assert!(!directory.path().join("ignored.arcs").exists());
+
+    // A failing file does not stop the others, unless failing fast.
+    std::fs::write(directory.path().join("broken.bcalm2"), ">0 LN:i:3\nAC\n").unwrap();
+    command()
+        .args(["-k", "14", "--input-glob"])
+        .arg(directory.path().join("*.bcalm2"))
+        .arg("--output-template")
+        .arg(&template)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Converted").count(2));
+    command()
+        .args(["-k", "14", "--fail-fast", "--input-glob"])
+        .arg(directory.path().join("*.bcalm2"))
+        .arg("--output-template")
+        .arg(&template)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Converted").count(0));
+
+    // All files would be written to the same output.
+    command()
+        .args(["-k", "14", "--input-glob"])
+        .arg(directory.path().join("*.bcalm2"))
+        .arg("--output-template")
+        .arg(directory.path().join("output.arcs"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not contain {name}"));
+    assert!(!directory.path().join("output.arcs").exists());
+
+    // Options that only describe a single conversion.
+    for option in [
+        ["--stats"].as_slice(),
+        &["--benchmark"],
+        &["--preallocate", "100"],
+    ] {
+        command()
+            .args(["-k", "14", "--input-glob"])
+            .arg(directory.path().join("*.bcalm2"))
+            .arg("--output-template")
+            .arg(&template)
+            .args(option)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("cannot be used with"));
+    }
+}